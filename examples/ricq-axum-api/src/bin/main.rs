@@ -59,7 +59,7 @@ impl Processor for ClientProcessor {
                             message.elements.to_string()
                         );
                         client
-                            .send_group_message(message.group_code, message.elements)
+                            .send_group_message(message.group_code, message.elements.clone())
                             .await
                             .ok();
                     }
@@ -74,7 +74,7 @@ impl Processor for ClientProcessor {
                             message.elements.to_string()
                         );
                         client
-                            .send_friend_message(message.from_uin, message.elements)
+                            .send_friend_message(message.from_uin, message.elements.clone())
                             .await
                             .ok();
                     }
@@ -1,9 +1,11 @@
 use std::collections::HashMap;
 
-use bytes::Bytes;
+use bytes::{BufMut, Bytes, BytesMut};
 use jcers::JcePut;
 
 use crate::command::common::pack_uni_request_data;
+use crate::command::config_push_svc::SSO_SERVER_LIST_KEY;
+use crate::crypto::qqtea_encrypt;
 use crate::jce;
 use crate::protocol::packet::Packet;
 
@@ -29,4 +31,27 @@ impl super::super::super::Engine {
         };
         self.uni_packet("ConfigPushSvc.PushResp", pkt.freeze())
     }
+
+    /// HttpServerListReq，POST 到 `SSO_SERVER_LIST_URL`
+    pub fn build_sso_server_list_request(&self) -> Bytes {
+        let req = jce::HttpServerListReq {
+            c: 1,
+            imsi: "00000".into(),
+            is_wifi_conn: 100,
+            app_id: self.transport.version.app_id as i32,
+            imei: self.transport.device.imei.clone(),
+            ..Default::default()
+        };
+        let pkt = jce::pack_request(
+            0,
+            "ConfigHttp",
+            "HttpServerListReq",
+            "HttpServerListReq",
+            req,
+        );
+        let mut body = BytesMut::with_capacity(pkt.len() + 4);
+        body.put_u32(pkt.len() as u32);
+        body.put_slice(&pkt);
+        Bytes::from(qqtea_encrypt(&body, &SSO_SERVER_LIST_KEY))
+    }
 }
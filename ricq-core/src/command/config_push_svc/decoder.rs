@@ -1,6 +1,7 @@
-use bytes::Bytes;
+use bytes::{Buf, Bytes};
 
 use crate::command::config_push_svc::*;
+use crate::crypto::qqtea_decrypt;
 use crate::{jce, pb, RQError, RQResult};
 use prost::Message;
 
@@ -52,4 +53,18 @@ impl super::super::super::Engine {
             body,
         })
     }
+
+    /// SSO_SERVER_LIST_URL 的响应
+    pub fn decode_sso_server_list_response(
+        &self,
+        payload: &[u8],
+    ) -> RQResult<Vec<jce::SsoServerInfo>> {
+        let mut payload = Bytes::from(qqtea_decrypt(payload, &SSO_SERVER_LIST_KEY));
+        if payload.len() < 4 {
+            return Err(RQError::Decode("sso server list too short".into()));
+        }
+        payload.advance(4);
+        let rsp: jce::HttpServerListRes = jce::unpack_response_v3(payload, "HttpServerListRes")?;
+        Ok(rsp.sso_server_infos)
+    }
 }
@@ -6,6 +6,14 @@ use crate::{jce, pb};
 pub mod builder;
 pub mod decoder;
 
+/// 获取 SSO 服务器列表的地址，请求由 `Engine::build_sso_server_list_request` 生成
+pub const SSO_SERVER_LIST_URL: &str =
+    "https://configsvr.msf.3g.qq.com/configsvr/serverlist.jsp?mType=getssolist";
+
+const SSO_SERVER_LIST_KEY: [u8; 16] = [
+    0xF0, 0x44, 0x1F, 0x5F, 0xF4, 0x2D, 0xA5, 0x8F, 0xDC, 0xF7, 0x94, 0x9A, 0xBA, 0x62, 0xD4, 0x11,
+];
+
 #[derive(Default, Debug)]
 pub struct ConfigPushReq {
     pub resp: ConfigPushResp,
@@ -16,6 +16,30 @@ impl super::super::super::Engine {
         friend_list_count: i16,
         group_start_index: i16,
         group_list_count: i16,
+    ) -> Packet {
+        self.build_friend_list_request_packet(
+            friend_start_index,
+            friend_list_count,
+            group_start_index,
+            group_list_count,
+            Vec::new(),
+        )
+    }
+
+    // friendlist.getFriendGroupList
+    /// 只查询 uins 中的好友，返回的好友列表中不包含非好友
+    pub fn build_friend_list_query_packet(&self, uins: Vec<i64>) -> Packet {
+        let count = uins.len() as i16;
+        self.build_friend_list_request_packet(0, count, 0, 0, uins)
+    }
+
+    fn build_friend_list_request_packet(
+        &self,
+        friend_start_index: i16,
+        friend_list_count: i16,
+        group_start_index: i16,
+        group_list_count: i16,
+        uin_list: Vec<i64>,
     ) -> Packet {
         let mut d50 = BytesMut::new();
         prost::Message::encode(
@@ -44,7 +68,7 @@ impl super::super::super::Engine {
             if_get_msf_group: 0,
             if_show_term_type: 1,
             version: 27,
-            uin_list: vec![],
+            uin_list,
             app_type: 0,
             if_get_dov_id: 0,
             if_get_both_flag: 0,
@@ -110,7 +134,10 @@ impl super::super::super::Engine {
             group_code,
             next_uin,
             group_uin: group_code2uin(group_code),
-            version: 2,
+            // version 3 + rich_card_name_ver 1 时才会稳定返回入群时间、最后发言时间和群等级
+            version: 3,
+            req_type: 1,
+            rich_card_name_ver: 1,
             ..Default::default()
         };
         let mut b = BytesMut::new();
@@ -190,8 +217,31 @@ impl super::super::super::Engine {
         self.uni_packet("friendlist.delFriend", pkt.freeze())
     }
 
+    // friendlist.getUserAddFriendSetting
+    pub fn build_get_user_add_friend_setting_packet(&self, query_uin: i64) -> Packet {
+        let payload = jce::GetUserAddFriendSettingReq {
+            uin: self.uin(),
+            version: 1,
+            query_uin,
+            fetch_question: 1,
+            ..Default::default()
+        };
+        let buf = jce::RequestDataVersion3 {
+            map: HashMap::from([("FS".to_string(), pack_uni_request_data(&payload.freeze()))]),
+        };
+        let pkt = jce::RequestPacket {
+            i_version: 3,
+            i_request_id: self.next_packet_seq(),
+            s_servant_name: "mqq.IMService.FriendListServiceServantObj".to_string(),
+            s_func_name: "GetUserAddFriendSettingReq".to_string(),
+            s_buffer: buf.freeze(),
+            ..Default::default()
+        };
+        self.uni_packet("friendlist.getUserAddFriendSetting", pkt.freeze())
+    }
+
     /// 好友分组操作
-    fn build_friend_list_set_group_req_packet(&self, req_type: i32, body: Bytes) -> Packet {
+    pub fn build_friend_list_set_group_req_packet(&self, req_type: i32, body: Bytes) -> Packet {
         let payload = jce::FriendListSetGroupReq {
             req_type,
             uin: self.uin(),
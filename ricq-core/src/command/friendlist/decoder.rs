@@ -2,7 +2,9 @@ use bytes::{Buf, Bytes};
 use jcers::Jce;
 
 use crate::command::friendlist::*;
-use crate::structs::{FriendInfo, GroupInfo, GroupMemberInfo, GroupMemberPermission};
+use crate::structs::{
+    FriendInfo, GroupInfo, GroupMemberInfo, GroupMemberPermission, GroupSettings,
+};
 use crate::{jce, RQError, RQResult};
 
 impl super::super::super::Engine {
@@ -78,6 +80,11 @@ impl super::super::super::Engine {
                 max_member_count: g.max_group_member_num as u16,
                 shut_up_timestamp: g.shut_up_timestamp,
                 my_shut_up_timestamp: g.my_shut_up_timestamp,
+                settings: GroupSettings::from_flags(
+                    g.group_flag_ext as u32,
+                    g.group_flag_ext3 as u32,
+                    0,
+                ),
                 ..Default::default()
             })
             .collect();
@@ -88,9 +95,20 @@ impl super::super::super::Engine {
     }
 
     // friendlist.GetTroopMemberListReq
+    // 返回的成员 group_code 为 0，需要调用方填写
     pub fn decode_group_member_list_response(
+        &self,
+        payload: Bytes,
+        group_owner_uin: i64,
+    ) -> RQResult<GroupMemberListResponse> {
+        self.decode_group_member_list_response_with_group(payload, 0, group_owner_uin)
+    }
+
+    // friendlist.GetTroopMemberListReq，成员的 group_code 设为参数中的群号
+    pub fn decode_group_member_list_response_with_group(
         &self,
         mut payload: Bytes,
+        group_code: i64,
         group_owner_uin: i64,
     ) -> RQResult<GroupMemberListResponse> {
         let mut request: jce::RequestPacket =
@@ -107,6 +125,7 @@ impl super::super::super::Engine {
         let mut l: Vec<GroupMemberInfo> = Vec::new();
         for m in members {
             l.push(GroupMemberInfo {
+                group_code,
                 uin: m.member_uin,
                 gender: m.gender,
                 nickname: m.nick,
@@ -143,4 +162,96 @@ impl super::super::super::Engine {
             .ok_or_else(|| RQError::Decode("decode_remove_friend `DFRESP` not found".into()))?;
         jcers::from_buf(&mut r).map_err(Into::into)
     }
+
+    // friendlist.getUserAddFriendSetting
+    pub fn decode_get_user_add_friend_setting_response(
+        &self,
+        mut payload: Bytes,
+    ) -> RQResult<UserAddFriendSetting> {
+        let mut req: jce::RequestPacket = jcers::from_buf(&mut payload)?;
+        let mut data: jce::RequestDataVersion3 = jcers::from_buf(&mut req.s_buffer)?;
+        let mut r = data.map.remove("FSRESP").ok_or_else(|| {
+            RQError::Decode("decode_get_user_add_friend_setting_response `FSRESP` not found".into())
+        })?;
+        let rsp: jce::GetUserAddFriendSettingResp = jcers::from_buf(&mut r)?;
+        if rsp.result != 0 {
+            return Err(RQError::Other(format!(
+                "get user add friend setting failed: {}",
+                rsp.result
+            )));
+        }
+        Ok(UserAddFriendSetting {
+            uin: rsp.query_uin,
+            setting: AddFriendSetting::from(rsp.query_uin_setting),
+            questions: rsp.questions,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::jce::{pack_request, TroopMemberInfo};
+    use crate::protocol::device::Device;
+    use crate::protocol::version::ANDROID_PHONE;
+    use crate::structs::GroupMemberPermission;
+    use crate::{Engine, JceStruct};
+
+    JceStruct!(TroopMemberListResp {
+        3 => members: Vec<TroopMemberInfo>,
+        4 => next_uin: i64,
+    });
+
+    fn member_list_payload() -> bytes::Bytes {
+        let resp = TroopMemberListResp {
+            members: vec![
+                TroopMemberInfo {
+                    member_uin: 10000,
+                    nick: "owner".into(),
+                    member_level: 5,
+                    join_time: 1600000000,
+                    last_speak_time: 1700000000,
+                    ..Default::default()
+                },
+                TroopMemberInfo {
+                    member_uin: 20000,
+                    nick: "admin".into(),
+                    name: "card".into(),
+                    flag: 1,
+                    ..Default::default()
+                },
+            ],
+            next_uin: 0,
+        };
+        pack_request(
+            1,
+            "mqq.IMService.FriendListServiceServantObj",
+            "GetTroopMemberListReq",
+            "GTMLRESP",
+            resp,
+        )
+    }
+
+    #[test]
+    fn test_decode_group_member_list() {
+        let engine = Engine::new(Device::random(), ANDROID_PHONE);
+        let resp = engine
+            .decode_group_member_list_response_with_group(member_list_payload(), 123456, 10000)
+            .unwrap();
+        assert_eq!(resp.next_uin, 0);
+        let owner = &resp.list[0];
+        assert_eq!(owner.group_code, 123456);
+        assert_eq!(owner.permission, GroupMemberPermission::Owner);
+        assert_eq!(owner.level, 5);
+        assert_eq!(owner.join_time, 1600000000);
+        assert_eq!(owner.last_speak_time, 1700000000);
+        let admin = &resp.list[1];
+        assert_eq!(admin.card_name, "card");
+        assert_eq!(admin.permission, GroupMemberPermission::Administrator);
+
+        // 旧接口不填写 group_code
+        let resp = engine
+            .decode_group_member_list_response(member_list_payload(), 10000)
+            .unwrap();
+        assert_eq!(resp.list[0].group_code, 0);
+    }
 }
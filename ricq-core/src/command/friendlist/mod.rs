@@ -19,6 +19,24 @@ pub struct FriendListResponse {
     pub online_friend_count: i16,
 }
 
+impl FriendListResponse {
+    /// 查询与 uin 的关系
+    pub fn relationship(&self, uin: i64) -> Relationship {
+        if self.friends.iter().any(|f| f.uin == uin) {
+            Relationship::Friend
+        } else {
+            Relationship::Stranger
+        }
+    }
+}
+
+/// 与某个用户的关系
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Relationship {
+    Friend,
+    Stranger,
+}
+
 #[derive(Debug)]
 pub struct GroupListResponse {
     pub groups: Vec<GroupInfo>,
@@ -30,3 +48,48 @@ pub struct GroupMemberListResponse {
     pub next_uin: i64,
     pub list: Vec<GroupMemberInfo>,
 }
+
+/// 对方的加好友方式
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum AddFriendSetting {
+    /// 允许任何人
+    AllowAll,
+    /// 需要验证
+    #[default]
+    NeedVerify,
+    /// 拒绝任何人
+    RejectAll,
+    /// 需要正确回答问题
+    NeedAnswer,
+    /// 需要回答问题并由对方确认
+    NeedAnswerAndVerify,
+    Other(i32),
+}
+
+impl From<i32> for AddFriendSetting {
+    fn from(v: i32) -> Self {
+        match v {
+            0 => Self::AllowAll,
+            1 => Self::NeedVerify,
+            2 => Self::RejectAll,
+            3 => Self::NeedAnswer,
+            4 => Self::NeedAnswerAndVerify,
+            v => Self::Other(v),
+        }
+    }
+}
+
+impl AddFriendSetting {
+    /// 添加好友时是否需要回答问题
+    pub fn requires_answer(&self) -> bool {
+        matches!(self, Self::NeedAnswer | Self::NeedAnswerAndVerify)
+    }
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct UserAddFriendSetting {
+    pub uin: i64,
+    pub setting: AddFriendSetting,
+    /// 需要回答的问题
+    pub questions: Vec<String>,
+}
@@ -39,4 +39,36 @@ impl super::super::super::Engine {
         };
         self.uni_packet("ImgStore.GroupPicUp", req.to_bytes())
     }
+
+    // ImgStore.GroupPicDown
+    pub fn build_group_image_down_packet(
+        &self,
+        group_code: i64,
+        file_id: i64,
+        md5: Vec<u8>,
+        size: u64,
+    ) -> Packet {
+        let req = pb::cmd0x388::D388ReqBody {
+            net_type: Some(3),
+            subcmd: Some(2),
+            getimg_url_req: vec![pb::cmd0x388::GetImgUrlReq {
+                group_code: Some(group_code as u64),
+                dst_uin: Some(self.uin() as u64),
+                fileid: Some(file_id as u64),
+                file_md5: Some(md5),
+                url_flag: Some(1),
+                url_type: Some(3),
+                req_term: Some(5),
+                req_platform_type: Some(9),
+                bu_type: Some(1),
+                build_ver: Some(self.transport.version.build_ver.as_bytes().to_vec()),
+                file_size: Some(size),
+                original_pic: Some(1),
+                req_transfer_type: Some(1),
+                ..Default::default()
+            }],
+            ..Default::default()
+        };
+        self.uni_packet("ImgStore.GroupPicDown", req.to_bytes())
+    }
 }
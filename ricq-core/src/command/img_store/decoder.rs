@@ -41,4 +41,31 @@ impl super::super::super::Engine {
             }
         })
     }
+
+    // ImgStore.GroupPicDown
+    pub fn decode_group_image_down_response(&self, payload: Bytes) -> RQResult<String> {
+        let mut rsp = pb::cmd0x388::D388RspBody::decode(&*payload)?;
+        let rsp = rsp
+            .getimg_url_rsp
+            .pop()
+            .ok_or(EmptyField("getimg_url_rsp"))?;
+        if rsp.result() != 0 {
+            return Err(RQError::Other(
+                String::from_utf8_lossy(&rsp.fail_msg.unwrap_or_default()).into_owned(),
+            ));
+        }
+        if let Some(url) = rsp.original_down_url.into_iter().next() {
+            return Ok(String::from_utf8_lossy(&url).into_owned());
+        }
+        let domain = rsp.down_domain.ok_or(EmptyField("down_domain"))?;
+        let para = rsp
+            .original_down_para
+            .or(rsp.big_down_para)
+            .ok_or(EmptyField("original_down_para"))?;
+        Ok(format!(
+            "https://{}{}",
+            String::from_utf8_lossy(&domain),
+            String::from_utf8_lossy(&para)
+        ))
+    }
 }
@@ -36,4 +36,28 @@ impl crate::Engine {
         };
         self.uni_packet("LongConn.OffPicUp", req.to_bytes())
     }
+
+    // LongConn.OffPicDown
+    pub fn build_off_pic_down_packet(&self, sender_uin: i64, res_id: String) -> Packet {
+        let req = crate::pb::cmd0x352::ReqBody {
+            subcmd: Some(2),
+            getimg_url_req: vec![crate::pb::cmd0x352::GetImgUrlReq {
+                src_uin: Some(sender_uin as u64),
+                dst_uin: Some(self.uin() as u64),
+                file_resid: Some(res_id.into_bytes()),
+                url_flag: Some(1),
+                url_type: Some(3),
+                req_term: Some(5),
+                req_platform_type: Some(9),
+                src_file_type: Some(1),
+                bu_type: Some(1),
+                build_ver: Some(self.transport.version.build_ver.as_bytes().to_vec()),
+                req_transfer_type: Some(1),
+                ..Default::default()
+            }],
+            net_type: Some(3),
+            ..Default::default()
+        };
+        self.uni_packet("LongConn.OffPicDown", req.to_bytes())
+    }
 }
@@ -1,7 +1,7 @@
 use crate::common::RQAddr;
 
-mod builder;
-mod decoder;
+pub mod builder;
+pub mod decoder;
 
 #[derive(Debug, Clone)]
 pub enum OffPicUpResp {
@@ -153,6 +153,24 @@ impl super::super::super::Engine {
         self.uni_packet("MessageSvc.PbGetGroupMsg", req.to_bytes())
     }
 
+    // MessageSvc.PbGetOneDayRoamMsg
+    /// 拉取好友漫游消息，从 last_msg_time 往前最多 count 条
+    pub fn build_get_one_day_roam_msg_request(
+        &self,
+        peer_uin: i64,
+        last_msg_time: i64,
+        random: i64,
+        count: u32,
+    ) -> Packet {
+        let req = pb::msg::PbGetOneDayRoamMsgReq {
+            peer_uin: Some(peer_uin as u64),
+            last_msg_time: Some(last_msg_time as u64),
+            random: Some(random as u64),
+            read_cnt: Some(count),
+        };
+        self.uni_packet("MessageSvc.PbGetOneDayRoamMsg", req.to_bytes())
+    }
+
     pub fn build_friend_recall_packet(
         &self,
         uin: i64,
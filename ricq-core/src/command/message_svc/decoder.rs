@@ -1,7 +1,7 @@
 use bytes::{Buf, Bytes};
 
 use crate::pb::msg::GetMessageResponse;
-use crate::{jce, RQError, RQResult};
+use crate::{jce, pb, RQError, RQResult};
 use prost::Message;
 
 impl crate::Engine {
@@ -57,4 +57,47 @@ impl crate::Engine {
                 .collect(),
         })
     }
+
+    // MessageSvc.PbGetGroupMsg
+    pub fn decode_get_group_msg_response(&self, payload: Bytes) -> RQResult<Vec<pb::msg::Message>> {
+        let resp = pb::msg::GetGroupMsgResp::decode(&*payload)?;
+        if resp.result() != 0 {
+            return Err(RQError::Other(format!(
+                "get group msg error: {} {}",
+                resp.result(),
+                resp.errmsg()
+            )));
+        }
+        Ok(resp.msg)
+    }
+
+    // MessageSvc.PbGetOneDayRoamMsg
+    pub fn decode_get_one_day_roam_msg_response(
+        &self,
+        payload: Bytes,
+    ) -> RQResult<Vec<pb::msg::Message>> {
+        let resp = pb::msg::PbGetOneDayRoamMsgResp::decode(&*payload)?;
+        if resp.result() != 0 {
+            return Err(RQError::Other(format!(
+                "get roam msg error: {} {}",
+                resp.result(),
+                resp.err_msg()
+            )));
+        }
+        Ok(resp.msg)
+    }
+
+    // MessageSvc.PbSendMsg
+    /// 返回服务器确认的发送时间，服务器未返回时为 0
+    pub fn decode_send_message_response(&self, payload: Bytes) -> RQResult<i64> {
+        let resp = pb::msg::SendMessageResponse::decode(&*payload)?;
+        if resp.result() != 0 {
+            return Err(RQError::Other(format!(
+                "send message error: {} {}",
+                resp.result(),
+                resp.err_msg()
+            )));
+        }
+        Ok(resp.send_time() as i64)
+    }
 }
@@ -9,12 +9,14 @@ pub mod long_conn;
 pub mod longmsg;
 pub mod message_svc;
 pub mod multi_msg;
+pub mod offline_file;
 pub mod oidb_svc;
 pub mod online_push;
 pub mod pb_message_svc;
 pub mod profile_service;
 pub mod ptt_center_svr;
 pub mod ptt_store;
+pub mod ptt_trans;
 pub mod reg_prxy_svc;
 pub mod signature;
 pub mod stat_svc;
@@ -0,0 +1,29 @@
+use crate::command::common::PbToBytes;
+use crate::pb;
+use crate::protocol::packet::Packet;
+
+impl super::super::super::Engine {
+    // OfflineFilleHandleSvr.pb_ftn_CMD_REQ_APPLY_DOWNLOAD-1200
+    pub fn build_offline_file_download_request_packet(&self, file_uuid: Vec<u8>) -> Packet {
+        let req = pb::cmd0x346::C346ReqBody {
+            cmd: 1200,
+            business_id: 3,
+            client_type: 104,
+            apply_download_req: Some(pb::cmd0x346::ApplyDownloadReq {
+                uin: self.uin(),
+                uuid: file_uuid,
+                owner_type: 2,
+                ..Default::default()
+            }),
+            extension_req: Some(pb::cmd0x346::ExtensionReq {
+                download_url_type: 1,
+                ..Default::default()
+            }),
+            ..Default::default()
+        };
+        self.uni_packet(
+            "OfflineFilleHandleSvr.pb_ftn_CMD_REQ_APPLY_DOWNLOAD-1200",
+            req.to_bytes(),
+        )
+    }
+}
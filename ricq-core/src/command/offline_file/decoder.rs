@@ -0,0 +1,26 @@
+use bytes::Bytes;
+use prost::Message;
+
+use crate::{pb, RQError, RQResult};
+
+impl super::super::super::Engine {
+    // OfflineFilleHandleSvr.pb_ftn_CMD_REQ_APPLY_DOWNLOAD-1200
+    pub fn decode_offline_file_download_response(&self, payload: Bytes) -> RQResult<String> {
+        let rsp = pb::cmd0x346::C346RspBody::decode(&*payload)?
+            .apply_download_rsp
+            .ok_or(RQError::EmptyField("apply_download_rsp"))?;
+        if rsp.ret_code != 0 {
+            return Err(RQError::Other(format!(
+                "apply download failed: {} {}",
+                rsp.ret_code, rsp.ret_msg
+            )));
+        }
+        let info = rsp
+            .download_info
+            .ok_or(RQError::EmptyField("download_info"))?;
+        Ok(format!(
+            "http://{}{}",
+            info.download_domain, info.download_url
+        ))
+    }
+}
@@ -4,6 +4,7 @@ use super::*;
 use crate::command::common::PbToBytes;
 use crate::pb;
 use crate::protocol::packet::Packet;
+use crate::structs::{GroupJoinMode, GroupSettings};
 
 impl super::super::super::Engine {
     // OidbSvc.0x4ff_9_IMCore
@@ -34,6 +35,7 @@ impl super::super::super::Engine {
                         group_uin: Some(0),
                         group_create_time: Some(0),
                         group_flag: Some(0),
+                        group_flag_ext: Some(0),
                         group_member_max_num: Some(0),
                         group_member_num: Some(0),
                         group_option: Some(0),
@@ -55,6 +57,7 @@ impl super::super::super::Engine {
                         cmduin_privilege: Some(0),
                         no_finger_open_flag: Some(0),
                         no_code_finger_open_flag: Some(0),
+                        group_flagext3: Some(0),
                         ..Default::default()
                     }),
                     ..Default::default()
@@ -84,7 +87,7 @@ impl super::super::super::Engine {
     }
 
     // OidbSvc.0x89a_0
-    fn build_group_operation_packet(&self, body: pb::oidb::D89aReqBody) -> Packet {
+    pub fn build_group_operation_packet(&self, body: pb::oidb::D89aReqBody) -> Packet {
         let payload = self.transport.encode_oidb_packet(0x89a, 0, body.to_bytes());
         self.uni_packet("OidbSvc.0x89a_0", payload)
     }
@@ -129,6 +132,49 @@ impl super::super::super::Engine {
         self.build_group_operation_packet(body)
     }
 
+    // OidbSvc.0x89a_0
+    pub fn build_group_join_mode_packet(&self, group_code: i64, mode: &GroupJoinMode) -> Packet {
+        let (question, answer) = match mode {
+            GroupJoinMode::Question { question } => (question.as_str(), ""),
+            GroupJoinMode::QuestionAnswer { question, answer } => {
+                (question.as_str(), answer.as_str())
+            }
+            _ => ("", ""),
+        };
+        let body = pb::oidb::D89aReqBody {
+            group_code,
+            st_group_info: Some(pb::oidb::D89aGroupinfo {
+                add_option: mode.option() as i32,
+                ing_group_question: question.as_bytes().to_vec(),
+                ing_group_answer: answer.as_bytes().to_vec(),
+                ..Default::default()
+            }),
+            ..Default::default()
+        };
+        self.build_group_operation_packet(body)
+    }
+
+    // OidbSvc.0x89a_0
+    pub fn build_group_settings_update_packet(
+        &self,
+        group_code: i64,
+        settings: &GroupSettings,
+    ) -> Packet {
+        let (flag_ext3, flag_ext3_mask) = settings.flag_ext3();
+        let body = pb::oidb::D89aReqBody {
+            group_code,
+            st_group_info: Some(pb::oidb::D89aGroupinfo {
+                allow_member_invite: Some(settings.allow_member_invite as i32),
+                group_flagext3: flag_ext3 as i32,
+                group_flagext3_mask: flag_ext3_mask as i32,
+                no_finger_open_flag: Some(!settings.searchable as i32),
+                ..Default::default()
+            }),
+            ..Default::default()
+        };
+        self.build_group_operation_packet(body)
+    }
+
     // OidbSvc.0x8a0_0
     pub fn build_group_kick_packet(
         &self,
@@ -5,9 +5,9 @@ use bytes::{Bytes, BytesMut};
 use crate::command::oidb_svc::GroupAtAllRemainInfo;
 use crate::structs::{
     GroupFileCount, GroupFileInfo, GroupFileItem, GroupFileList, GroupFolderInfo, GroupInfo,
-    GroupMemberPermission,
+    GroupJoinMode, GroupMemberPermission, GroupSettings,
 };
-use crate::{pb, RQResult};
+use crate::{pb, RQError, RQResult};
 use prost::Message;
 
 use super::OcrResponse;
@@ -35,11 +35,37 @@ impl super::super::super::Engine {
                     shut_up_timestamp: info.shutup_timestamp.unwrap_or_default() as i64,
                     my_shut_up_timestamp: info.shutup_timestamp_me.unwrap_or_default() as i64,
                     last_msg_seq: info.group_cur_msg_seq.unwrap_or_default() as i64,
+                    settings: GroupSettings::from_flags(
+                        info.group_flag_ext.unwrap_or_default(),
+                        info.group_flagext3.unwrap_or_default(),
+                        info.no_finger_open_flag.unwrap_or_default(),
+                    ),
                 })
             })
             .collect())
     }
 
+    // OidbSvc.0x88d_0
+    pub fn decode_group_join_mode_response(
+        &self,
+        payload: Bytes,
+    ) -> RQResult<Vec<(i64, GroupJoinMode)>> {
+        let pkg = pb::oidb::OidbssoPkg::decode(&*payload)?;
+        let groups = pb::oidb::D88dRspBody::decode(&*pkg.bodybuffer)?.rsp_group_info;
+        Ok(groups
+            .into_iter()
+            .filter_map(|g| {
+                let info = g.group_info?;
+                let mode = GroupJoinMode::from_option(
+                    info.group_option?,
+                    String::from_utf8_lossy(info.group_question()).into_owned(),
+                    String::from_utf8_lossy(info.group_answer()).into_owned(),
+                )?;
+                Some((g.group_code? as i64, mode))
+            })
+            .collect())
+    }
+
     // // OidbSvc.0x8a7_0
     pub fn decode_group_at_all_remain_response(
         &self,
@@ -167,15 +193,40 @@ impl super::super::super::Engine {
         payload: Bytes,
         filename: &str,
     ) -> RQResult<String> {
+        self.decode_group_file_download_urls_response(payload, filename)?
+            .into_iter()
+            .next()
+            .ok_or(RQError::EmptyField("download_ip"))
+    }
+    // OidbSvc.0x6d6_2
+    /// 返回下载 ip 和域名对应的所有下载链接，ip 在前
+    pub fn decode_group_file_download_urls_response(
+        &self,
+        payload: Bytes,
+        filename: &str,
+    ) -> RQResult<Vec<String>> {
         let pkg = pb::oidb::OidbssoPkg::decode(&*payload)?;
         let resp = pb::oidb::D6d6RspBody::decode(&*pkg.bodybuffer)?;
-        let f_rsp = resp.download_file_rsp.unwrap();
-        Ok(format!(
-            "http://{}/ftn_handler/{:x}/?fname={}",
-            f_rsp.download_ip(),
+        let f_rsp = resp
+            .download_file_rsp
+            .ok_or(RQError::EmptyField("download_file_rsp"))?;
+        if f_rsp.ret_code() != 0 {
+            return Err(RQError::UnsuccessfulRetCode(f_rsp.ret_code()));
+        }
+        let path = format!(
+            "ftn_handler/{:x}/?fname={}",
             BytesMut::from(f_rsp.download_url()),
             filename
-        ))
+        );
+        let dns = String::from_utf8_lossy(f_rsp.download_dns());
+        let mut urls = Vec::new();
+        for host in [f_rsp.download_ip(), &*dns] {
+            if !host.is_empty() {
+                urls.push(format!("http://{host}/{path}"));
+            }
+        }
+        urls.dedup();
+        Ok(urls)
     }
     // OidbSvc.0x6d8_1
     pub fn decode_group_file_count_response(&self, payload: Bytes) -> RQResult<GroupFileCount> {
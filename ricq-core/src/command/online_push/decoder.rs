@@ -7,6 +7,34 @@ use crate::structs::{GroupDisband, GroupLeave, GroupMemberPermission, MemberPerm
 use crate::{jce, pb, RQError, RQResult};
 use prost::Message;
 
+/// OnlinePush.PbPushGroupMsg 的群号，只解析消息头
+pub fn peek_group_code(payload: &[u8]) -> Option<i64> {
+    pb::msg::PushMessagePeek::decode(payload)
+        .ok()?
+        .message?
+        .head?
+        .group_info?
+        .group_code
+}
+
+/// 推送消息的发送者，只解析消息头
+pub fn peek_from_uin(payload: &[u8]) -> Option<i64> {
+    pb::msg::PushMessagePeek::decode(payload)
+        .ok()?
+        .message?
+        .head?
+        .from_uin
+}
+
+/// OnlinePush.PbC2CMsgSync 的 (from_uin, to_uin)，只解析消息头
+pub fn peek_c2c_uins(payload: &[u8]) -> Option<(i64, i64)> {
+    let head = pb::msg::PushMessagePeek::decode(payload)
+        .ok()?
+        .message?
+        .head?;
+    Some((head.from_uin?, head.to_uin?))
+}
+
 impl super::super::super::Engine {
     // 解析群消息分片 长消息需要合并
     // OnlinePush.PbPushGroupMsg
@@ -0,0 +1,49 @@
+use bytes::Bytes;
+use prost::Message;
+
+use crate::{pb, RQError, RQResult};
+
+impl super::super::super::Engine {
+    // PbMessageSvc.PbMsgReadedReport
+    pub fn decode_msg_readed_response(&self, payload: Bytes) -> RQResult<()> {
+        let resp = pb::msg::PbMsgReadedReportResp::decode(&*payload)?;
+        let results = resp
+            .grp_read_report
+            .into_iter()
+            .map(|r| r.result)
+            .chain(resp.c2_c_read_report.map(|r| r.result));
+        for result in results {
+            match result.unwrap_or_default() {
+                0 => {}
+                code => return Err(RQError::UnsuccessfulRetCode(code as i32)),
+            }
+        }
+        Ok(())
+    }
+
+    // PbMessageSvc.PbMsgWithDraw
+    pub fn decode_msg_withdraw_response(&self, payload: Bytes) -> RQResult<()> {
+        let resp = pb::msg::MsgWithDrawResp::decode(&*payload)?;
+        let results = resp
+            .c2c_with_draw
+            .into_iter()
+            .map(|r| (r.result, r.err_msg))
+            .chain(
+                resp.group_with_draw
+                    .into_iter()
+                    .map(|r| (r.result, r.err_msg)),
+            );
+        for (result, err_msg) in results {
+            let result = result.unwrap_or_default();
+            let err_msg = err_msg.unwrap_or_default();
+            // 部分失败的情况 err_msg 为空，需要先判断 result
+            if result != 0 || (!err_msg.is_empty() && err_msg != "Success") {
+                return Err(RQError::Other(format!(
+                    "recall error: {} {}",
+                    result, err_msg
+                )));
+            }
+        }
+        Ok(())
+    }
+}
@@ -1,6 +1,7 @@
 use std::collections::HashMap;
 
 use bytes::Bytes;
+use serde::{Deserialize, Serialize};
 
 pub mod builder;
 pub mod decoder;
@@ -12,7 +13,7 @@ pub struct GroupSystemMessages {
 }
 
 // 自己被邀请
-#[derive(Debug, Default, Clone)]
+#[derive(Debug, Default, Clone, Serialize, Deserialize)]
 pub struct SelfInvited {
     pub msg_seq: i64,
     pub msg_time: i64,
@@ -25,7 +26,7 @@ pub struct SelfInvited {
 }
 
 // 用户申请进群
-#[derive(Debug, Default, Clone)]
+#[derive(Debug, Default, Clone, Serialize, Deserialize)]
 pub struct JoinGroupRequest {
     pub msg_seq: i64,
     pub msg_time: i64,
@@ -45,7 +46,7 @@ pub struct FriendSystemMessages {
     pub requests: Vec<NewFriendRequest>,
 }
 
-#[derive(Debug, Default, Clone)]
+#[derive(Debug, Default, Clone, Serialize, Deserialize)]
 pub struct NewFriendRequest {
     pub msg_seq: i64,
     pub message: String,
@@ -0,0 +1,38 @@
+use crate::command::common::PbToBytes;
+use crate::pb;
+use crate::protocol::packet::Packet;
+
+impl super::super::super::Engine {
+    // pttTrans.TransGroupPttReq
+    pub fn build_group_ptt_trans_req(&self, group_code: i64, ptt: &pb::msg::Ptt) -> Packet {
+        let req = self.ptt_trans_req_body(group_code, 1, ptt);
+        self.uni_packet("pttTrans.TransGroupPttReq", req.to_bytes())
+    }
+
+    // pttTrans.TransC2CPttReq
+    pub fn build_c2c_ptt_trans_req(&self, sender_uin: i64, ptt: &pb::msg::Ptt) -> Packet {
+        let req = self.ptt_trans_req_body(sender_uin, 0, ptt);
+        self.uni_packet("pttTrans.TransC2CPttReq", req.to_bytes())
+    }
+
+    fn ptt_trans_req_body(
+        &self,
+        peer: i64,
+        chat_type: u32,
+        ptt: &pb::msg::Ptt,
+    ) -> pb::ptt_trans::TransPttReqBody {
+        pb::ptt_trans::TransPttReqBody {
+            sender_uin: Some(ptt.src_uin.unwrap_or_default() as u64),
+            receiver_uin: Some(peer as u64),
+            chat_type: Some(chat_type),
+            file_md5: ptt.file_md5.clone(),
+            file_uuid: ptt.file_uuid.clone(),
+            file_size: ptt.file_size.map(|s| s as u32),
+            codec: ptt.format.map(|f| f as u32),
+            voice_length: ptt.time.map(|t| t as u32),
+            req_term: Some(5),
+            platform_type: Some(9),
+            build_ver: Some(self.transport.version.build_ver.into()),
+        }
+    }
+}
@@ -0,0 +1,19 @@
+use bytes::Bytes;
+use prost::Message;
+
+use crate::{pb, RQError, RQResult};
+
+impl super::super::super::Engine {
+    // pttTrans.TransGroupPttReq / pttTrans.TransC2CPttReq
+    pub fn decode_ptt_trans_response(&self, payload: Bytes) -> RQResult<String> {
+        let rsp = pb::ptt_trans::TransPttRspBody::decode(&*payload)?;
+        if rsp.result.unwrap_or_default() != 0 {
+            return Err(RQError::Other(format!(
+                "ptt trans failed: {}, {}",
+                rsp.result.unwrap_or_default(),
+                String::from_utf8_lossy(&rsp.err_msg.unwrap_or_default())
+            )));
+        }
+        Ok(String::from_utf8_lossy(&rsp.text.ok_or(RQError::EmptyField("text"))?).into_owned())
+    }
+}
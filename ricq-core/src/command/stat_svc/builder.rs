@@ -17,6 +17,22 @@ impl super::super::super::Engine {
         online_status: i32,
         ext_online_status: i64,
         custom_status: Option<CustomOnlineStatus>,
+    ) -> Packet {
+        self.build_set_online_status_packet_with_battery(
+            online_status,
+            ext_online_status,
+            custom_status,
+            0,
+        )
+    }
+
+    /// 同 build_set_online_status_packet，battery_status 为电量百分比
+    pub fn build_set_online_status_packet_with_battery(
+        &self,
+        online_status: i32,
+        ext_online_status: i64,
+        custom_status: Option<CustomOnlineStatus>,
+        battery_status: i32,
     ) -> Packet {
         let transport = &self.transport;
         let svc = jce::SvcReqRegister {
@@ -40,6 +56,7 @@ impl super::super::super::Engine {
             vendor_name: transport.device.vendor_name.to_owned(),
             vendor_os_name: transport.device.vendor_os_name.to_owned(),
             ext_online_status,
+            battery_status,
             timestamp: UNIX_EPOCH.elapsed().unwrap().as_secs() as i64,
             custom_status: custom_status
                 .map(|custom_status| {
@@ -125,13 +142,24 @@ impl super::super::super::Engine {
 
     // StatSvc.GetDevLoginInfo
     pub fn build_device_list_request_packet(&self) -> Packet {
+        self.build_dev_login_info_request_packet(20)
+    }
+
+    // StatSvc.GetDevLoginInfo
+    pub fn build_online_device_list_request_packet(&self) -> Packet {
+        self.build_dev_login_info_request_packet(1)
+    }
+
+    // StatSvc.GetDevLoginInfo
+    /// get_dev_list_type: 1 在线设备，20 登录过的设备
+    pub fn build_dev_login_info_request_packet(&self, get_dev_list_type: i64) -> Packet {
         let transport = &self.transport;
         let req = jce::SvcReqGetDevLoginInfo {
             guid: transport.sig.guid.to_owned(),
             login_type: 1,
             app_name: "com.tencent.mobileqq".into(),
             require_max: 20,
-            get_dev_list_type: 20,
+            get_dev_list_type,
             ..Default::default()
         };
         let buf = jce::RequestDataVersion3 {
@@ -150,6 +178,32 @@ impl super::super::super::Engine {
         self.uni_packet("StatSvc.GetDevLoginInfo", pkt.freeze())
     }
 
+    // StatSvc.DelDevLoginInfo
+    pub fn build_kick_device_request_packet(&self, app_id: i64, guid: Bytes) -> Packet {
+        let transport = &self.transport;
+        let req = jce::SvcReqDelLoginInfo {
+            guid: transport.sig.guid.to_owned(),
+            app_name: "com.tencent.mobileqq".into(),
+            device_items: vec![jce::DeviceItemDes { item_des: guid }],
+            del_type: 1,
+            app_id,
+        };
+        let buf = jce::RequestDataVersion3 {
+            map: HashMap::from([(
+                "SvcReqDelLoginInfo".to_string(),
+                pack_uni_request_data(&req.freeze()),
+            )]),
+        };
+        let pkt = jce::RequestPacket {
+            i_version: 3,
+            s_servant_name: "StatSvc".to_string(),
+            s_func_name: "SvcReqDelLoginInfo".to_string(),
+            s_buffer: buf.freeze(),
+            ..Default::default()
+        };
+        self.uni_packet("StatSvc.DelDevLoginInfo", pkt.freeze())
+    }
+
     // StatSvc.RspMSFForceOffline
     pub fn build_msf_force_offline_rsp(&self, uin: i64, seq_no: i64) -> Packet {
         let rsp = jce::RspMSFForceOffline {
@@ -1,6 +1,7 @@
 use bytes::{Buf, Bytes};
 use jcers::Jce;
 
+use crate::structs::OnlineDevice;
 use crate::{jce, RQError, RQResult};
 
 impl super::super::super::Engine {
@@ -25,22 +26,8 @@ impl super::super::super::Engine {
     }
 
     // StatSvc.GetDevLoginInfo
-    pub fn decode_dev_list_response(
-        &self,
-        mut payload: Bytes,
-    ) -> RQResult<Vec<jce::SvcDevLoginInfo>> {
-        let mut request: jce::RequestPacket =
-            jcers::from_buf(&mut payload).map_err(RQError::from)?;
-        let mut data: jce::RequestDataVersion2 =
-            jcers::from_buf(&mut request.s_buffer).map_err(RQError::from)?;
-        let mut req = data
-            .map
-            .remove("SvcRspGetDevLoginInfo")
-            .ok_or_else(|| RQError::Decode("missing SvcRspGetDevLoginInfo".into()))?;
-        let mut msg = req
-            .remove("QQService.SvcRspGetDevLoginInfo")
-            .ok_or_else(|| RQError::Decode("missing QQService.SvcRspGetDevLoginInfo".into()))?;
-        msg.advance(1);
+    pub fn decode_dev_list_response(&self, payload: Bytes) -> RQResult<Vec<jce::SvcDevLoginInfo>> {
+        let mut msg = decode_dev_login_info_rsp(payload)?;
         let mut rsp = Jce::new(&mut msg);
         let d: Vec<jce::SvcDevLoginInfo> = rsp.get_by_tag(4).map_err(RQError::from)?;
         if !d.is_empty() {
@@ -57,6 +44,41 @@ impl super::super::super::Engine {
         Err(RQError::Decode("decode_dev_list_response".into()))
     }
 
+    // StatSvc.GetDevLoginInfo
+    pub fn decode_online_device_list_response(
+        &self,
+        payload: Bytes,
+    ) -> RQResult<Vec<OnlineDevice>> {
+        let mut msg = decode_dev_login_info_rsp(payload)?;
+        let mut rsp = Jce::new(&mut msg);
+        // tag 4 为当前在线的设备，没有其他设备在线时为空
+        let d: Vec<jce::SvcDevLoginInfo> = rsp.get_by_tag(4).unwrap_or_default();
+        Ok(d.into_iter().map(OnlineDevice::from).collect())
+    }
+
+    // StatSvc.DelDevLoginInfo
+    pub fn decode_kick_device_response(&self, mut payload: Bytes) -> RQResult<()> {
+        let mut request: jce::RequestPacket =
+            jcers::from_buf(&mut payload).map_err(RQError::from)?;
+        let mut data: jce::RequestDataVersion2 =
+            jcers::from_buf(&mut request.s_buffer).map_err(RQError::from)?;
+        let mut msg = data
+            .map
+            .remove("SvcRspDelLoginInfo")
+            .ok_or_else(|| RQError::Decode("missing SvcRspDelLoginInfo".into()))?
+            .remove("QQService.SvcRspDelLoginInfo")
+            .ok_or_else(|| RQError::Decode("missing QQService.SvcRspDelLoginInfo".into()))?;
+        msg.advance(1);
+        let rsp: jce::SvcRspDelLoginInfo = jcers::from_buf(&mut msg).map_err(RQError::from)?;
+        if rsp.result != 0 {
+            return Err(RQError::Other(format!(
+                "failed to kick device, result: {}, {}",
+                rsp.result, rsp.error_msg
+            )));
+        }
+        Ok(())
+    }
+
     // StatSvc.ReqMSFOffline
     pub fn decode_msf_force_offline(
         &self,
@@ -75,3 +97,18 @@ impl super::super::super::Engine {
         jcers::from_buf(&mut data).map_err(RQError::from)
     }
 }
+
+fn decode_dev_login_info_rsp(mut payload: Bytes) -> RQResult<Bytes> {
+    let mut request: jce::RequestPacket = jcers::from_buf(&mut payload).map_err(RQError::from)?;
+    let mut data: jce::RequestDataVersion2 =
+        jcers::from_buf(&mut request.s_buffer).map_err(RQError::from)?;
+    let mut req = data
+        .map
+        .remove("SvcRspGetDevLoginInfo")
+        .ok_or_else(|| RQError::Decode("missing SvcRspGetDevLoginInfo".into()))?;
+    let mut msg = req
+        .remove("QQService.SvcRspGetDevLoginInfo")
+        .ok_or_else(|| RQError::Decode("missing QQService.SvcRspGetDevLoginInfo".into()))?;
+    msg.advance(1);
+    Ok(msg)
+}
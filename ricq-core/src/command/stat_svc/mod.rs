@@ -6,6 +6,15 @@ pub struct Status {
     pub online_status: i32,
     pub ext_online_status: i64,
     pub custom_status: Option<CustomOnlineStatus>,
+    /// 电量百分比，仅在 ext_online_status 为 Battery 时有效
+    pub battery_status: i32,
+}
+
+impl Status {
+    /// 自定义状态（表情 + 文字）
+    pub fn custom(face_index: u64, wording: impl Into<String>) -> Self {
+        CustomOnlineStatus::new(face_index, wording).into()
+    }
 }
 
 #[derive(Debug, Copy, Clone)]
@@ -25,6 +34,7 @@ impl From<OnlineStatus> for Status {
             online_status: s as i32,
             ext_online_status: 0,
             custom_status: None,
+            battery_status: 0,
         }
     }
 }
@@ -58,22 +68,49 @@ impl From<ExtOnlineStatus> for Status {
             online_status: 11,
             ext_online_status: s as i64,
             custom_status: None,
+            battery_status: 0,
         }
     }
 }
 
+/// 电量状态，值为电量百分比
+#[derive(Debug, Copy, Clone)]
+pub struct Battery(pub u8);
+
+impl From<Battery> for Status {
+    fn from(s: Battery) -> Self {
+        Self {
+            online_status: 11,
+            ext_online_status: ExtOnlineStatus::Battery as i64,
+            custom_status: None,
+            battery_status: s.0.min(100) as i32,
+        }
+    }
+}
+
+/// 自定义状态，face_index 为状态前的表情，wording 为状态文字
 #[derive(Debug, Clone)]
 pub struct CustomOnlineStatus {
     pub face_index: u64,
     pub wording: String,
 }
 
+impl CustomOnlineStatus {
+    pub fn new(face_index: u64, wording: impl Into<String>) -> Self {
+        Self {
+            face_index,
+            wording: wording.into(),
+        }
+    }
+}
+
 impl From<CustomOnlineStatus> for Status {
     fn from(s: CustomOnlineStatus) -> Self {
         Self {
             online_status: 11,
             ext_online_status: 2000,
             custom_status: Some(s),
+            battery_status: 0,
         }
     }
 }
@@ -100,4 +100,99 @@ impl super::super::super::Engine {
         };
         self.uni_packet("SummaryCard.ReqSummaryCard", pkt.freeze())
     }
+
+    // SummaryCard.ReqSearch
+    pub fn build_search_request_packet(&self, keyword: String) -> Packet {
+        let req = jce::ReqSearch {
+            keyword,
+            country_code: "+86".into(),
+            version: 3,
+            ..Default::default()
+        };
+        let mut head = jcers::JceMut::new();
+        head.put_i32(2, 0);
+        let buf = jce::RequestDataVersion3 {
+            map: HashMap::from([
+                ("ReqHead".to_string(), pack_uni_request_data(&head.freeze())),
+                (
+                    "ReqSearch".to_string(),
+                    pack_uni_request_data(&req.freeze()),
+                ),
+            ]),
+        };
+        let pkt = jce::RequestPacket {
+            i_version: 3,
+            i_request_id: self.next_packet_seq(),
+            s_servant_name: "SummaryCardServantObj".to_string(),
+            s_func_name: "ReqSearch".to_string(),
+            s_buffer: buf.freeze(),
+            ..Default::default()
+        };
+        self.uni_packet("SummaryCard.ReqSearch", pkt.freeze())
+    }
+
+    // SummaryCard.ReqSearch
+    pub fn build_group_search_request_packet(&self, keyword: String) -> Packet {
+        let comm = pb::profilecard::BusiComm {
+            ver: Some(1),
+            seq: Some(self.next_seq() as i32),
+            fromuin: Some(self.uin()),
+            touin: Some(0),
+            service: Some(80000001),
+            platform: Some(2),
+            qqver: Some(self.transport.version.build_ver.into()),
+            build: Some(4945),
+            ..Default::default()
+        }
+        .to_bytes();
+        let search = pb::profilecard::AccountSearch {
+            start: Some(0),
+            end: Some(4),
+            keyword: Some(keyword.clone()),
+            highlight: vec![keyword.clone()],
+            user_location: Some(pb::profilecard::AccountSearchLocation {
+                latitude: Some(0.0),
+                longitude: Some(0.0),
+            }),
+            filtertype: Some(0),
+            ..Default::default()
+        }
+        .to_bytes();
+        let business_buf = {
+            let mut w = BytesMut::new();
+            w.put_u8(40);
+            w.put_u32(comm.len() as u32);
+            w.put_u32(search.len() as u32);
+            w.put_slice(&comm);
+            w.put_slice(&search);
+            w.put_u8(41);
+            w.freeze()
+        };
+        let req = jce::ReqSearch {
+            keyword,
+            country_code: "+86".into(),
+            version: 3,
+            req_services: vec![business_buf],
+        };
+        let mut head = jcers::JceMut::new();
+        head.put_i32(2, 0);
+        let buf = jce::RequestDataVersion3 {
+            map: HashMap::from([
+                ("ReqHead".to_string(), pack_uni_request_data(&head.freeze())),
+                (
+                    "ReqSearch".to_string(),
+                    pack_uni_request_data(&req.freeze()),
+                ),
+            ]),
+        };
+        let pkt = jce::RequestPacket {
+            i_version: 3,
+            i_request_id: self.next_packet_seq(),
+            s_servant_name: "SummaryCardServantObj".to_string(),
+            s_func_name: "ReqSearch".to_string(),
+            s_buffer: buf.freeze(),
+            ..Default::default()
+        };
+        self.uni_packet("SummaryCard.ReqSearch", pkt.freeze())
+    }
 }
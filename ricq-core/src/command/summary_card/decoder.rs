@@ -1,8 +1,30 @@
 use bytes::{Buf, Bytes};
+use prost::Message;
 
-use crate::jce::{RespSummaryCard, RespSummaryCardHead};
+use crate::command::summary_card::{PublicGroupInfo, SearchUserInfo};
+use crate::jce::{
+    RespGroupSearch, RespSearch, RespSummaryCard, RespSummaryCardHead, SearchRespHead,
+};
 use crate::structs::SummaryCardInfo;
-use crate::{jce, RQError, RQResult};
+use crate::{jce, pb, RQError, RQResult};
+
+// busi 响应格式为 0x28 + comm 长度 + body 长度 + comm + body + 结束符，service 16 对应资料卡 gate
+fn decode_gate_rsp(mut buf: Bytes) -> Option<pb::profilecard::GateVaProfileGateRsp> {
+    if buf.len() < 9 {
+        return None;
+    }
+    buf.advance(1);
+    let comm_len = buf.get_u32() as usize;
+    let body_len = buf.get_u32() as usize;
+    if buf.remaining() < comm_len + body_len {
+        return None;
+    }
+    let comm = pb::profilecard::BusiComm::decode(buf.split_to(comm_len)).ok()?;
+    if comm.service != Some(16) {
+        return None;
+    }
+    pb::profilecard::GateVaProfileGateRsp::decode(buf.split_to(body_len)).ok()
+}
 
 impl super::super::super::Engine {
     // SummaryCard.ReqSummaryCard
@@ -27,6 +49,13 @@ impl super::super::super::Engine {
             .ok_or_else(|| RQError::Decode("missing SummaryCard_Old.RespSummaryCard".into()))?;
         rsp.advance(1);
         let rsp: RespSummaryCard = jcers::from_buf(&mut rsp)?;
+        let qid = rsp
+            .services
+            .iter()
+            .find_map(|buf| decode_gate_rsp(buf.clone()))
+            .and_then(|gate| gate.qid_info)
+            .and_then(|qid| qid.qid)
+            .unwrap_or_default();
         let info = SummaryCardInfo {
             sex: rsp.sex,
             age: rsp.age,
@@ -37,9 +66,89 @@ impl super::super::super::Engine {
             mobile: rsp.mobile,
             uin: rsp.uin,
             login_days: rsp.login_days,
+            qid,
             cookie: head.cookie,
         };
         // TODO more info
         Ok(info)
     }
+
+    // SummaryCard.ReqSearch
+    pub fn decode_search_response(&self, mut payload: Bytes) -> RQResult<Vec<SearchUserInfo>> {
+        let mut request: jce::RequestPacket =
+            jcers::from_buf(&mut payload).map_err(RQError::from)?;
+        let mut data: jce::RequestDataVersion2 =
+            jcers::from_buf(&mut request.s_buffer).map_err(RQError::from)?;
+        let mut rsp = data
+            .map
+            .remove("RespSearch")
+            .ok_or_else(|| RQError::Decode("missing RespSearch".into()))?
+            .remove("SummaryCard.RespSearch")
+            .ok_or_else(|| RQError::Decode("missing SummaryCard.RespSearch".into()))?;
+        rsp.advance(1);
+        let rsp: RespSearch = jcers::from_buf(&mut rsp)?;
+        Ok(rsp
+            .records
+            .into_iter()
+            .map(|r| SearchUserInfo {
+                uin: r.uin,
+                nick: r.nick,
+                gender: r.gender,
+                age: r.age,
+                add_friend_setting: None,
+            })
+            .collect())
+    }
+
+    // SummaryCard.ReqSearch
+    pub fn decode_group_search_response(
+        &self,
+        mut payload: Bytes,
+    ) -> RQResult<Vec<PublicGroupInfo>> {
+        let mut request: jce::RequestPacket =
+            jcers::from_buf(&mut payload).map_err(RQError::from)?;
+        let mut data: jce::RequestDataVersion2 =
+            jcers::from_buf(&mut request.s_buffer).map_err(RQError::from)?;
+        let mut head = data
+            .map
+            .remove("RespHead")
+            .ok_or_else(|| RQError::Decode("missing RespHead".into()))?
+            .remove("SummaryCard.RespHead")
+            .ok_or_else(|| RQError::Decode("missing SummaryCard.RespHead".into()))?;
+        head.advance(1);
+        let head: SearchRespHead = jcers::from_buf(&mut head)?;
+        // 找不到群时 result 不为 0，并带有错误信息
+        if head.result != 0 {
+            return Ok(Vec::new());
+        }
+        let mut rsp = data
+            .map
+            .remove("RespSearch")
+            .ok_or_else(|| RQError::Decode("missing RespSearch".into()))?
+            .remove("SummaryCard.RespSearch")
+            .ok_or_else(|| RQError::Decode("missing SummaryCard.RespSearch".into()))?;
+        rsp.advance(1);
+        let rsp: RespGroupSearch = jcers::from_buf(&mut rsp)?;
+        let mut service = match rsp.resp_services.into_iter().next() {
+            Some(service) if service.len() > 9 => service,
+            _ => return Ok(Vec::new()),
+        };
+        service.advance(1);
+        let comm_len = service.get_u32() as usize;
+        let search_len = service.get_u32() as usize;
+        if service.remaining() < comm_len + search_len {
+            return Err(RQError::Decode("invalid search response".into()));
+        }
+        service.advance(comm_len);
+        let search = pb::profilecard::AccountSearch::decode(service.split_to(search_len))?;
+        Ok(search
+            .list
+            .into_iter()
+            .map(|r| PublicGroupInfo {
+                code: r.code.unwrap_or_default() as i64,
+                name: r.name.unwrap_or_default(),
+                memo: r.brief.unwrap_or_default(),
+            })
+            .collect())
+    }
 }
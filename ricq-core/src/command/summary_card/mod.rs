@@ -1,2 +1,35 @@
+use crate::command::friendlist::UserAddFriendSetting;
+use crate::structs::GroupInfo;
+
 pub mod builder;
 pub mod decoder;
+
+/// 按关键词搜索到的用户
+#[derive(Debug, Clone, Default)]
+pub struct SearchUserInfo {
+    pub uin: i64,
+    pub nick: String,
+    pub gender: u8,
+    pub age: u8,
+    /// 加好友设置，查询失败时为 None
+    pub add_friend_setting: Option<UserAddFriendSetting>,
+}
+
+/// 通过搜索获取的群公开信息，不需要是群成员
+#[derive(Debug, Clone, Default)]
+pub struct PublicGroupInfo {
+    pub code: i64,
+    pub name: String,
+    /// 群简介
+    pub memo: String,
+}
+
+impl From<&GroupInfo> for PublicGroupInfo {
+    fn from(info: &GroupInfo) -> Self {
+        Self {
+            code: info.code,
+            name: info.name.clone(),
+            memo: info.memo.clone(),
+        }
+    }
+}
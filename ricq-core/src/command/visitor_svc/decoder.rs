@@ -0,0 +1,32 @@
+use bytes::{Buf, Bytes};
+
+use crate::{jce, RQError, RQResult};
+
+// 今日点赞次数已用完
+const LIKE_LIMIT_REPLY_CODE: i32 = 51;
+
+impl super::super::super::Engine {
+    // VisitorSvc.ReqFavorite
+    pub fn decode_send_like_response(&self, mut payload: Bytes) -> RQResult<()> {
+        let mut request: jce::RequestPacket =
+            jcers::from_buf(&mut payload).map_err(RQError::from)?;
+        let mut data: jce::RequestDataVersion2 =
+            jcers::from_buf(&mut request.s_buffer).map_err(RQError::from)?;
+        let mut rsp = data
+            .map
+            .remove("RespFavorite")
+            .ok_or_else(|| RQError::Decode("missing RespFavorite".into()))?
+            .remove("QQService.RespFavorite")
+            .ok_or_else(|| RQError::Decode("missing QQService.RespFavorite".into()))?;
+        rsp.advance(1);
+        let rsp: jce::RespFavorite = jcers::from_buf(&mut rsp)?;
+        match rsp.header.reply_code {
+            0 => Ok(()),
+            LIKE_LIMIT_REPLY_CODE => Err(RQError::LikeLimitExceeded(rsp.header.result)),
+            code => Err(RQError::Other(format!(
+                "send like failed: {code} {}",
+                rsp.header.result
+            ))),
+        }
+    }
+}
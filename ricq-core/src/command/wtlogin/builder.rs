@@ -1,6 +1,6 @@
 use bytes::{BufMut, BytesMut};
 
-use crate::binary::packet_writer::{CounterWriter, Either, PacketAppender, PacketWriter, WriteLV};
+use crate::binary::packet_writer::{CounterWriter, PacketAppender, PacketWriter, WriteLV};
 use crate::binary::BinaryWriter;
 use crate::command::wtlogin::builder::utils::*;
 use crate::command::wtlogin::tlv_writer::*;
@@ -219,7 +219,8 @@ impl super::super::super::Engine {
                 ))
                 .append(t516())
                 .append(t521(8))
-                .append(t318(tgt_qr));
+                .append(t318(tgt_qr))
+                .append(t545(transport.device.qimei16()));
             w.put_u16(tlv_writer.count as u16);
             tlv_writer.write(&mut w);
 
@@ -492,11 +493,7 @@ impl super::super::super::Engine {
                 .append(t516())
                 .append(t521(0))
                 .append(t525(t536(&[0x01, 0x00])))
-                .append(if let Some(ref qimei) = transport.device.qimei {
-                    Either::Left(tlv(0x545, qimei.q16.as_bytes()))
-                } else {
-                    Either::Right(tlv(0x545, transport.device.imei.as_bytes()))
-                });
+                .append(t545(transport.device.qimei16()));
             // TODO 544
 
             w.put_u16(tlv_writer.count as u16);
@@ -597,7 +594,8 @@ impl super::super::super::Engine {
                 .append(t202(
                     &transport.device.wifi_bssid,
                     &transport.device.wifi_ssid,
-                ));
+                ))
+                .append(t545(transport.device.qimei16()));
             // TODO 544
             w.put_u16(tlv_writer.count as u16);
             tlv_writer.write(&mut w);
@@ -711,11 +709,7 @@ impl super::super::super::Engine {
                 .append(t521(0))
                 .append(t525(t536(&[0x01, 0x00])))
                 .append(tlv(0x544, sign))
-                .append(if let Some(ref qimei) = transport.device.qimei {
-                    Either::Left(tlv(0x545, qimei.q16.as_bytes()))
-                } else {
-                    Either::Right(tlv(0x545, transport.device.imei.as_bytes()))
-                });
+                .append(t545(transport.device.qimei16()));
             w.put_u16(tlv_writer.count as u16);
             tlv_writer.write(&mut w);
 
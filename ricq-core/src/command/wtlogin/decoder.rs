@@ -88,13 +88,27 @@ impl super::super::super::Engine {
         ))
     }
 
-    pub fn decode_login_response(&self, mut reader: Bytes) -> RQResult<LoginResponse> {
+    pub fn decode_login_response(&self, reader: Bytes) -> RQResult<LoginResponse> {
+        self.decode_login_response_with_cancel(reader, &|| false)
+    }
+
+    /// 同 decode_login_response，需要计算 T546 PoW 时可以通过 is_cancelled 中止
+    pub fn decode_login_response_with_cancel(
+        &self,
+        mut reader: Bytes,
+        is_cancelled: &dyn Fn() -> bool,
+    ) -> RQResult<LoginResponse> {
         let _sub_command = reader.get_u16(); // sub command
         let status = reader.get_u8();
         // TODO status=213 不能执行下面的步骤 panic
         reader.get_u16();
         let tlv_map = reader.read_tlv_map(2);
-        LoginResponse::decode(status, tlv_map, &self.transport.sig.tgtgt_key)
+        LoginResponse::decode_with_cancel(
+            status,
+            tlv_map,
+            &self.transport.sig.tgtgt_key,
+            is_cancelled,
+        )
     }
 
     pub fn decode_exchange_emp_response(&self, mut payload: Bytes) -> RQResult<LoginResponse> {
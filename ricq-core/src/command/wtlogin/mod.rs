@@ -9,8 +9,8 @@ use crate::binary::{BinaryReader, BinaryWriter};
 use crate::command::wtlogin::tlv_reader::*;
 use crate::{RQError, RQResult};
 
-mod builder;
-mod decoder;
+pub mod builder;
+pub mod decoder;
 pub mod tlv_reader;
 pub mod tlv_writer;
 
@@ -117,10 +117,16 @@ pub struct LoginUnknownStatus {
 }
 
 impl LoginResponse {
-    pub fn decode(
+    pub fn decode(status: u8, tlv_map: HashMap<u16, Bytes>, encrypt_key: &[u8]) -> RQResult<Self> {
+        Self::decode_with_cancel(status, tlv_map, encrypt_key, &|| false)
+    }
+
+    /// 同 decode，is_cancelled 返回 true 时中止 T546 PoW 计算，返回 `RQError::Cancelled`
+    pub fn decode_with_cancel(
         status: u8,
         mut tlv_map: HashMap<u16, Bytes>,
         encrypt_key: &[u8],
+        is_cancelled: &dyn Fn() -> bool,
     ) -> RQResult<Self> {
         let resp = match status {
             0 => {
@@ -164,7 +170,10 @@ impl LoginResponse {
                         image: img_data,
                     }
                 }),
-                t547: tlv_map.remove(&0x546).map(t546_to_t547),
+                t547: tlv_map
+                    .remove(&0x546)
+                    .map(|t546| t546_to_t547_with_cancel(t546, is_cancelled))
+                    .transpose()?,
             }),
             40 => LoginResponse::AccountFrozen,
             160 | 239 => {
@@ -219,7 +228,19 @@ impl LoginResponse {
     }
 }
 
-pub fn t546_to_t547(mut data: Bytes) -> Bytes {
+/// 计算登录验证需要的 PoW，在解码登录响应时同步执行
+pub fn t546_to_t547(data: Bytes) -> Bytes {
+    match t546_to_t547_with_cancel(data, &|| false) {
+        Ok(t547) => t547,
+        Err(_) => unreachable!("t546 pow is never cancelled"),
+    }
+}
+
+/// 可取消的 t546_to_t547，计算过程中定期检查 is_cancelled，返回 true 时返回 `RQError::Cancelled`
+pub fn t546_to_t547_with_cancel(
+    mut data: Bytes,
+    is_cancelled: &dyn Fn() -> bool,
+) -> RQResult<Bytes> {
     let a = data.get_u8();
     let typ = data.get_u8();
     let c = data.get_u8();
@@ -238,6 +259,9 @@ pub fn t546_to_t547(mut data: Bytes) -> Bytes {
         use sha2::{Digest, Sha256};
         let mut hash = Sha256::digest(tmp.to_bytes_be()).to_vec();
         while hash != tgt {
+            if cnt % 4096 == 0 && is_cancelled() {
+                return Err(RQError::Cancelled);
+            }
             tmp += 1u8;
             hash = Sha256::digest(tmp.to_bytes_be()).to_vec();
             cnt += 1;
@@ -261,5 +285,27 @@ pub fn t546_to_t547(mut data: Bytes) -> Bytes {
         w.put_u32(elp);
         w.put_u32(cnt);
     }
-    w.freeze()
+    Ok(w.freeze())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_t546_pow_cancel() {
+        let mut w = BytesMut::new();
+        w.put_u8(1);
+        w.put_u8(2);
+        w.put_u8(0);
+        w.put_u8(0);
+        w.put_u16(0);
+        w.put_u16(0);
+        w.write_bytes_short(&[1]);
+        // 不可能命中的目标，只能通过取消结束
+        w.write_bytes_short(&[0; 32]);
+        w.write_bytes_short(&[]);
+        let res = t546_to_t547_with_cancel(w.freeze(), &|| true);
+        assert!(matches!(res, Err(RQError::Cancelled)));
+    }
 }
@@ -541,6 +541,10 @@ pub fn t536<B: BufMut + WriteLV>(login_extra_data: &[u8]) -> impl PacketWriter<B
     tlv(0x526, login_extra_data)
 }
 
+pub fn t545<B: BufMut + WriteLV>(qimei16: &str) -> impl PacketWriter<B> + '_ {
+    tlv(0x545, qimei16.as_bytes())
+}
+
 pub fn guid_flag() -> u32 {
     let mut flag: u32 = 0;
     flag |= 1 << 24 & 0xFF000000;
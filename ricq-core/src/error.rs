@@ -30,6 +30,9 @@ pub enum RQError {
     #[error("network error")]
     Network,
 
+    #[error("operation cancelled")]
+    Cancelled,
+
     #[error("jce error, {0}")]
     Jce(#[from] jcers::JceError),
     #[error("io error, {0}")]
@@ -51,6 +54,10 @@ pub enum RQError {
     SessionExpired,
     #[error("unsuccessful ret code: {0}")]
     UnsuccessfulRetCode(i32),
+    #[error("like limit exceeded: {0}")]
+    LikeLimitExceeded(String),
+    #[error("send rate limited, retry after {0:?}")]
+    RateLimited(std::time::Duration),
 
     #[error("Token login failed")]
     TokenLoginFailed,
@@ -1,11 +1,42 @@
-use std::collections::HashMap;
+//! JCE 结构体，用于 StatSvc、friendlist 等 JCE 协议的请求和响应
+//!
+//! 自定义结构体可以使用 [`JceStruct!`](crate::JceStruct) 定义，derive 展开后引用 `jcers`，
+//! 需要在使用处 `use ricq_core::jce::jcers;` 或直接依赖 jcers。
+//! 请求通过 [`pack_request`] 打包后作为 `Engine::uni_packet` 的 body，
+//! 响应通过 [`unpack_response_v2`] / [`unpack_response_v3`] 解析。
 
-use bytes::Bytes;
-use jcers::{JceGet, JcePut};
+use std::collections::HashMap;
 
+use bytes::{Buf, Bytes};
+use serde::{Deserialize, Serialize};
+
+pub use jcers::{self, from_buf, Jce, JceError, JceGet, JceMut, JcePut};
+
+use crate::command::common::pack_uni_request_data;
+use crate::{RQError, RQResult};
+
+/// 定义 JCE 结构体，字段为 `tag => name: Type`
+///
+/// ```ignore
+/// use ricq_core::jce::jcers;
+///
+/// ricq_core::JceStruct!(SvcReqFoo {
+///     0 => uin: i64,
+///     1 => name: String,
+/// });
+/// ```
+#[macro_export]
 macro_rules! JceStruct {
     ($struct_name: ident {$($tag: expr => $field: ident: $field_t: ty,)*}) => {
-        #[derive(Debug, Clone, PartialEq, Eq, JceGet, JcePut, Default)]
+        #[derive(
+            Debug,
+            Clone,
+            PartialEq,
+            Eq,
+            $crate::jce::JceGet,
+            $crate::jce::JcePut,
+            Default
+        )]
         pub struct $struct_name {
             $(#[jce($tag)]
             pub $field: $field_t),*
@@ -13,6 +44,55 @@ macro_rules! JceStruct {
     };
 }
 
+/// 把 req 以 key 放入 RequestDataVersion3，再包装为 RequestPacket
+///
+/// seq 通常为 `Engine::next_packet_seq`，servant / func 为服务名和方法名，如
+/// `"mqq.IMService.FriendListServiceServantObj"` / `"SetGroupReq"`
+pub fn pack_request<T: JcePut>(seq: i32, servant: &str, func: &str, key: &str, req: T) -> Bytes {
+    let data = RequestDataVersion3 {
+        map: HashMap::from([(key.to_string(), pack_uni_request_data(&req.freeze()))]),
+    };
+    RequestPacket {
+        i_version: 3,
+        i_request_id: seq,
+        s_servant_name: servant.to_string(),
+        s_func_name: func.to_string(),
+        s_buffer: data.freeze(),
+        ..Default::default()
+    }
+    .freeze()
+}
+
+/// 解析 RequestPacket 中 RequestDataVersion3 的 key 对应的结构
+pub fn unpack_response_v3<T: JceGet>(mut payload: Bytes, key: &str) -> RQResult<T> {
+    let mut request: RequestPacket = from_buf(&mut payload)?;
+    let mut data: RequestDataVersion3 = from_buf(&mut request.s_buffer)?;
+    let mut buf = data
+        .map
+        .remove(key)
+        .ok_or_else(|| RQError::Decode(format!("missing {key}")))?;
+    buf.advance(1);
+    Ok(from_buf(&mut buf)?)
+}
+
+/// 解析 RequestPacket 中 RequestDataVersion2 的 key / inner_key 对应的结构，
+/// inner_key 通常为带包名的类型名，如 `"QQService.SvcRspGetDevLoginInfo"`
+pub fn unpack_response_v2<T: JceGet>(
+    mut payload: Bytes,
+    key: &str,
+    inner_key: &str,
+) -> RQResult<T> {
+    let mut request: RequestPacket = from_buf(&mut payload)?;
+    let mut data: RequestDataVersion2 = from_buf(&mut request.s_buffer)?;
+    let mut buf = data
+        .map
+        .remove(key)
+        .and_then(|mut m| m.remove(inner_key))
+        .ok_or_else(|| RQError::Decode(format!("missing {key}.{inner_key}")))?;
+    buf.advance(1);
+    Ok(from_buf(&mut buf)?)
+}
+
 #[derive(Debug, Clone, JceGet, JcePut, Default)]
 pub struct RequestPacket {
     #[jce(1)]
@@ -45,6 +125,22 @@ JceStruct!(RequestDataVersion2 {
     0 => map: HashMap<String,HashMap<String,Bytes>>,
 });
 
+JceStruct!(HttpServerListReq {
+    1 => uin: i64,
+    2 => timeout: i64,
+    3 => c: u8,
+    4 => imsi: String,
+    5 => is_wifi_conn: i32,
+    6 => app_id: i32,
+    7 => imei: String,
+    8 => cell_id: i64,
+    9 => d9: i64,
+    10 => d10: i64,
+    11 => d11: i64,
+    12 => d12: u8,
+    13 => d13: i64,
+});
+
 JceStruct!(HttpServerListRes {
     2 => sso_server_infos: Vec<SsoServerInfo>,
 });
@@ -303,6 +399,23 @@ JceStruct!(SvcDevLoginInfo {
     10 => can_be_kicked: i64,
 });
 
+JceStruct!(SvcReqDelLoginInfo {
+    0 => guid: Bytes,
+    1 => app_name: String,
+    2 => device_items: Vec<DeviceItemDes>,
+    3 => del_type: i32, // 同 SvcReqGetDevLoginInfo.get_dev_list_type
+    4 => app_id: i64,
+});
+
+JceStruct!(DeviceItemDes {
+    0 => item_des: Bytes,
+});
+
+JceStruct!(SvcRspDelLoginInfo {
+    0 => result: i32,
+    1 => error_msg: String,
+});
+
 JceStruct!(DelMsgInfo {
     0 => from_uin: i64,
     1 => msg_time: i64,
@@ -926,6 +1039,8 @@ pub struct RespSummaryCard {
     pub uin: i64,
     #[jce(36)]
     pub login_days: i64,
+    #[jce(46)]
+    pub services: Vec<Bytes>,
 }
 
 #[derive(Debug, Clone, JceGet, JcePut, Default)]
@@ -961,6 +1076,58 @@ JceStruct!(DelFriendResp{
     3 => error_code : i16,
 });
 
+JceStruct!(GetUserAddFriendSettingReq {
+    0 => uin: i64,
+    1 => version: i64,
+    2 => query_uin: i64,
+    3 => fetch_question: u8,
+    4 => source_id: i32,
+    5 => sub_source_id: i32,
+});
+
+JceStruct!(GetUserAddFriendSettingResp {
+    0 => uin: i64,
+    1 => query_uin: i64,
+    2 => query_uin_setting: i32,
+    3 => verify_switch: u8,
+    4 => questions: Vec<String>,
+    5 => result: i32,
+});
+
+JceStruct!(ReqSearch {
+    0 => keyword: String,
+    1 => country_code: String,
+    2 => version: i32,
+    3 => req_services: Vec<Bytes>,
+});
+
+JceStruct!(SearchRecord {
+    0 => uin: i64,
+    1 => source: i32,
+    2 => nick: String,
+    3 => face_id: i16,
+    4 => gender: u8,
+    5 => age: u8,
+});
+
+JceStruct!(RespSearch {
+    0 => records: Vec<SearchRecord>,
+    1 => version: i32,
+    2 => secure_sig: Bytes,
+});
+
+JceStruct!(SearchRespHead {
+    0 => version: i32,
+    1 => result: i32,
+    2 => error_msg: String,
+    3 => cookie: Bytes,
+});
+
+// 搜索群时 tag 2 为 busi 响应
+JceStruct!(RespGroupSearch {
+    2 => resp_services: Vec<Bytes>,
+});
+
 #[derive(Debug, Clone, JceGet, JcePut, Default)]
 pub struct QQServiceReqHead {
     #[jce(0)]
@@ -991,6 +1158,18 @@ pub struct ReqFavorite {
     pub count: i32,
 }
 
+JceStruct!(QQServiceRespHead {
+    0 => version: i16,
+    1 => seq: i32,
+    2 => reply_code: i32,
+    3 => result: String,
+    4 => cookies: Bytes,
+});
+
+JceStruct!(RespFavorite {
+    0 => header: QQServiceRespHead,
+});
+
 #[derive(Debug, Clone, JceGet, JcePut, Default)]
 pub struct MsgType0x210 {
     #[jce(0)]
@@ -999,7 +1178,7 @@ pub struct MsgType0x210 {
     pub v_protobuf: Bytes,
 }
 
-#[derive(Debug, Clone, JceGet, JcePut, Default)]
+#[derive(Debug, Clone, JceGet, JcePut, Default, Serialize, Deserialize)]
 pub struct RequestPushForceOffline {
     #[jce(0)]
     pub uin: i64,
@@ -1011,7 +1190,7 @@ pub struct RequestPushForceOffline {
     pub same_device: u8,
 }
 
-#[derive(Debug, Clone, JceGet, JcePut, Default)]
+#[derive(Debug, Clone, JceGet, JcePut, Default, Serialize, Deserialize)]
 pub struct RequestMSFForceOffline {
     #[jce(0)]
     pub uin: i64,
@@ -1050,6 +1229,18 @@ mod tests {
 
     use super::*;
 
+    #[test]
+    fn pack_unpack_request() {
+        let req = SsoServerInfo {
+            server: "127.0.0.1".into(),
+            port: 8080,
+            location: "sz".into(),
+        };
+        let payload = pack_request(1, "servant", "func", "SsoServerInfo", req.clone());
+        let decoded: SsoServerInfo = unpack_response_v3(payload, "SsoServerInfo").unwrap();
+        assert_eq!(decoded, req);
+    }
+
     #[test]
     fn sso_address_resp_decode() {
         static SSO_ADDRESS_RESP: &str = "6e477b1c09e193f1d6084a1e8a052c259f6cf4e608e614d5db262ba96fceb6e8a5d19d72860d0db5dfac554cb7de435e4400f9cd0e8b14f98d4020962c496393e6947f85adcaf00656782b9512713177b41d8489ddf8952766c9850639e329905911aa989d618be1e14b133d228f187efd0ae3a8bdec00c6028078862e965940ad8acc937c5522abf967de737632f19d4f27b5bdf34a2003b11d8547cd4f82c7f1fcb8ea8219ada296ca5ed38bc38b4bbb475f51974dffb85daf1a12b3be2d853ff7877eba722148612abe85535492dd955ae9ff2b6cd9bb570711acc0869cdab62f0aa7fb1caa9862abd1e3aca39a96a9b45116cc92a065c2736420cab691e540534307dcc2d872da82c35b03f7a94b0a9bd6fbf73caa002702c10c9af38616ed9e6c54912de021ace4d969ca264d7f9d94ea4913a1a2184e77b9a1bc850c38d7de55b82e21c2f0e45e0e12ab602c54641b20409d1013245f79ec151e1ee773b9cca9f6d172084d1a125b9ff0e3d5efac8f0ad9e4cdec94f9366e6346274b6c9994ae62f35060c961948b5aa23eb2402c008603fa2e416d7803c9e466a9658d5e3470abda44c9b00c985ee28ae01e2f837666b8b8c5fb5fdfd263fa4173c948cf282efcd779fc15e21dbc29d4fa826d92d75bd7ebf19036c621d2fb96e6940fcab78ebea48da089893374b67d8341a10d86c33a182a9354268a7a7e168bd5c4a33e8b6110cd7e838c9bf53b8869ddbe747daf94183627e8dab6f71c67f71c621eddce055eb20803c962433baa1c7b0d1caf8a8b8b10ed3d429adaa38afaf1effab224ea16454e6e99b35dbcc55bfa972b8511ca1dee7af4485e45a4c0b5a6940168678dc0471420e4d9c401a514f12423972d6deb28192e7e9f74987282a67c4e1ea7e987ebbac989a72c85faac5d2648a284dfdb9374dfd17887c4b31b3e676436bbd47c5a4258406552187f2ca3586c58b1187c979c9303561fd4308646f30908d8abb19bae79d493100c034e6b34b2cd810ddb700c1a2d5418f556a8c46ed8912b1ea507d289c350e0ac86e2b80cee31263c10f431353d049890e7bbb732fed29353e75c4172193f45c7260157ee6f5cf84f11c3183f9889e30e239cf2fae16f1d65f14b519f407f848bd014c7b95d61ef6393b137b6989979641af5325f53411090c54164148ce3f732e0cdaac01de3b8585594d1d2e8f76723d995497d2c314f377efbc3974363d031e5de9fa4799a42b4acc28f7b2834203a1f3fa00510604801c7777ae1f62e89e08b6a0248d46a2c055e93458498077b175a2f08313ee373d42b861a727935be574838cdc15e654c6a6d01143d23d072bf7ea93e1094d4c5330a1ee3ea421f78127b60ea65dc9628a4e2eb440f21daaa514d30dde9ba5eea69c19a8ae8c65b60bad64859692dcf9c947b99333c31b21418e2984b178ee61011c0a3cec9d97abc4511ee96599e49fa8dc7b27d38d1cac74071c0b840db24d6ad4f2923622f7482ca0de4dc35698b69bbb3692d85f698f629e1476ac54a35502098ebf607ec1ad0d053745c8685e5fa69f91694163ab89c3391b7e0856239486a3206465e521364b4de7d5e5e2124e8c4a2bf9ca140f2af2c65b5e5c0a29f2cbcaaf0cea569250c1ce9c9356f1ad0ace6c9f729319cd61b701d5b8ccdd4509bfed9c2dbdcb7f6e47c97570b6914b43fd14798337dd997ce6d3d520d3db2a1fb0d50730f89a96df64d5f383c7889c405bb28e261f56a4f22c8dd8d5c95ac5d8653bdaa67e826d0e4214559d70e5f82ce0846970399b11e875c08571165b16029c5c575bb21316c19269ffac6db84efb2aa35b1c877bd824e8d00d18aaf4a7dfec14d8bfa3a92029936aea0dd4eb9c3c8b3c633d2b59339ea5865997c5e52facb546a222fa27e9ec00063a620ab44550241f3a115fc6251ad8b244a3588d400ee18ca0769d2ae64e09b8a6c7e439a856093087930e442d036e6bcfeee0c7b6fa908ab1c6906552cf0cd6341ac14bc04ef45c4bd558d45fa51b0ca3465a9c681fbe24fec1d231a73080b067caa5ac0641e6da3e3d105bf6491752e308c026e94e616582150d3a8a52a7bac25d3880c8324c18a92bcdc73c1dfa7f3591754f8944ac6c32d86163edff93de1d3e435367714c390bcb6ee741032e1981450112a1a1303969008c5170c0774dd7a61952778ff2bd6abab52834b7e5fa1d4c2ae47026e4072bea7d4fa359c25b36658c0372b11ad1c5723fb9308ce31ec4a4e0c38244fdfe4918079";
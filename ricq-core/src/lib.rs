@@ -13,7 +13,7 @@ use protocol::oicq;
 use protocol::transport::Transport;
 use protocol::version::Version;
 
-pub use crate::token::Token;
+pub use crate::token::{A1Token, Token};
 
 pub mod binary;
 pub mod command;
@@ -102,9 +102,27 @@ impl Engine {
             out_packet_session_id: self.transport.sig.out_packet_session_id.to_vec(),
             tgtgt_key: self.transport.sig.tgtgt_key.to_vec(),
             wt_session_ticket_key: self.transport.oicq_codec.wt_session_ticket_key.to_vec(),
+            sync_cookie: self.transport.sig.sync_cookie.to_vec(),
+            pub_account_cookie: self.transport.sig.pub_account_cookie.to_vec(),
         }
     }
 
+    pub fn gen_a1_token(&self) -> A1Token {
+        A1Token {
+            uin: self.uin(),
+            encrypted_a1: self.transport.sig.encrypted_a1.to_vec(),
+            no_pic_sig: self.transport.sig.srm_token.to_vec(),
+            tgtgt_key: self.transport.sig.tgtgt_key.to_vec(),
+        }
+    }
+
+    pub fn load_a1_token(&mut self, token: A1Token) {
+        self.uin.store(token.uin, Ordering::Relaxed);
+        self.transport.sig.encrypted_a1 = Bytes::from(token.encrypted_a1);
+        self.transport.sig.srm_token = Bytes::from(token.no_pic_sig);
+        self.transport.sig.tgtgt_key = Bytes::from(token.tgtgt_key);
+    }
+
     pub fn load_token(&mut self, token: Token) {
         self.uin.store(token.uin, Ordering::Relaxed);
         self.transport.sig.d2 = Bytes::from(token.d2);
@@ -116,5 +134,7 @@ impl Engine {
         self.transport.sig.out_packet_session_id = Bytes::from(token.out_packet_session_id);
         self.transport.sig.tgtgt_key = Bytes::from(token.tgtgt_key);
         self.transport.oicq_codec.wt_session_ticket_key = Bytes::from(token.wt_session_ticket_key);
+        self.transport.sig.sync_cookie = Bytes::from(token.sync_cookie);
+        self.transport.sig.pub_account_cookie = Bytes::from(token.pub_account_cookie);
     }
 }
@@ -2,7 +2,7 @@ use std::fmt;
 
 use bytes::{Buf, BufMut};
 
-use crate::msg::{MessageChainBuilder, PushBuilder};
+use crate::msg::{MessageChain, MessageChainBuilder, PushBuilder};
 use crate::msg::{MessageElem, PushElem};
 use crate::pb::msg;
 use crate::{push_builder_impl, to_elem_vec_impl};
@@ -20,6 +20,63 @@ impl At {
             display: format!("@{target}"),
         }
     }
+
+    /// 去掉开头 `@` 的显示名称，通常为群名片或昵称
+    pub fn name(&self) -> &str {
+        self.display.strip_prefix('@').unwrap_or(&self.display)
+    }
+
+    /// 显示名称为空或者是 `At::new` 生成的 `@QQ号` 时需要补全
+    pub fn needs_display(&self) -> bool {
+        self.display.is_empty() || (self.target != 0 && self.display == format!("@{}", self.target))
+    }
+}
+
+impl MessageChain {
+    /// 是否有需要补全显示名称的 At（不包括 @全体成员）
+    pub fn has_at_without_display(&self) -> bool {
+        self.0.iter().any(|elem| match elem {
+            MessageElem::Text(e) if !e.attr6_buf().is_empty() => {
+                let at = At::from(e.clone());
+                at.target != 0 && at.needs_display()
+            }
+            _ => false,
+        })
+    }
+
+    /// 补全没有显示名称的 At，resolve 返回群名片或昵称，找不到时显示为 `@QQ号`
+    pub fn fill_at_display<F>(&mut self, mut resolve: F)
+    where
+        F: FnMut(i64) -> Option<String>,
+    {
+        for elem in self.0.iter_mut() {
+            let at = match elem {
+                MessageElem::Text(e) if !e.attr6_buf().is_empty() => At::from(e.clone()),
+                _ => continue,
+            };
+            if !at.needs_display() {
+                continue;
+            }
+            let display = match at.target {
+                0 => "@全体成员".to_string(),
+                target => match resolve(target) {
+                    Some(name) if !name.is_empty() => format!("@{name}"),
+                    _ => format!("@{target}"),
+                },
+            };
+            let mut elems = Vec::new();
+            At::push_to(
+                At {
+                    target: at.target,
+                    display,
+                },
+                &mut elems,
+            );
+            if let Some(new) = elems.pop() {
+                *elem = new;
+            }
+        }
+    }
 }
 
 impl PushElem for At {
@@ -60,3 +117,32 @@ impl fmt::Display for At {
 
 to_elem_vec_impl!(At);
 push_builder_impl!(At);
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_fill_at_display() {
+        let mut chain = MessageChain::default();
+        chain.push(At::new(10000));
+        chain.push(At {
+            target: 20000,
+            display: "@bot".into(),
+        });
+        chain.push(At {
+            target: 30000,
+            display: String::new(),
+        });
+        assert!(chain.has_at_without_display());
+        chain.fill_at_display(|uin| (uin == 10000).then(|| "群名片".to_string()));
+        let ats: Vec<String> = chain
+            .into_iter()
+            .filter_map(|e| match e {
+                crate::msg::elem::RQElem::At(at) => Some(at.display),
+                _ => None,
+            })
+            .collect();
+        assert_eq!(ats, ["@群名片", "@bot", "@30000"]);
+    }
+}
@@ -26,6 +26,7 @@ impl FriendImage {
         FlashImage::from(self)
     }
 
+    /// 没有 orig_url 时按 res_id 拼接，部分图片无法访问，此时使用 `Client::get_friend_image_url`
     pub fn url(&self) -> String {
         if !self.orig_url.is_empty() {
             return format!("https://c2cpicdw.qpic.cn{}", self.orig_url);
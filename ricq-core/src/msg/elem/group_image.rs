@@ -32,6 +32,7 @@ impl GroupImage {
         FlashImage::from(self)
     }
 
+    /// 没有 orig_url 时按 md5 拼接，部分图片无法访问，此时使用 `Client::get_group_image_url`
     pub fn url(&self) -> String {
         if let Some(orig_url) = &self.orig_url {
             format!("https://gchat.qpic.cn{orig_url}")
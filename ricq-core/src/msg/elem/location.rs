@@ -0,0 +1,114 @@
+use std::fmt;
+
+use serde_json::{json, Value};
+
+use crate::msg::elem::LightApp;
+use crate::msg::{MessageChainBuilder, PushBuilder};
+use crate::msg::{MessageElem, PushElem};
+use crate::{push_builder_impl, to_elem_vec_impl};
+
+/// 位置分享，发送时转为 com.tencent.map 小程序
+#[derive(Default, Debug, Clone)]
+pub struct Location {
+    pub lat: f64,
+    pub lon: f64,
+    pub name: String,
+    pub address: String,
+}
+
+impl Location {
+    pub fn new(lat: f64, lon: f64, name: String, address: String) -> Self {
+        Self {
+            lat,
+            lon,
+            name,
+            address,
+        }
+    }
+
+    pub fn to_light_app(&self) -> LightApp {
+        let content = json!({
+            "app": "com.tencent.map",
+            "desc": "地图",
+            "view": "LocationShare",
+            "ver": "0.0.0.1",
+            "prompt": format!("[应用]{}", self.name),
+            "from": 1,
+            "meta": {
+                "Location.Search": {
+                    "id": "",
+                    "name": self.name,
+                    "address": self.address,
+                    "lat": self.lat.to_string(),
+                    "lng": self.lon.to_string(),
+                    "from": "plusPanel",
+                }
+            },
+            "config": { "forward": 1, "autosize": 1, "type": "card" },
+        });
+        LightApp::new(content.to_string())
+    }
+
+    /// 从位置分享小程序解析，不是位置分享时返回 None
+    pub fn from_light_app(app: &LightApp) -> Option<Self> {
+        let content: Value = serde_json::from_str(&app.content).ok()?;
+        if content.get("app")?.as_str()? != "com.tencent.map" {
+            return None;
+        }
+        let search = content.get("meta")?.get("Location.Search")?;
+        let coordinate = |key: &str| -> Option<f64> {
+            match search.get(key)? {
+                Value::String(s) => s.parse().ok(),
+                v => v.as_f64(),
+            }
+        };
+        let text = |key: &str| {
+            search
+                .get(key)
+                .and_then(Value::as_str)
+                .unwrap_or_default()
+                .to_string()
+        };
+        Some(Self {
+            lat: coordinate("lat")?,
+            lon: coordinate("lng")?,
+            name: text("name"),
+            address: text("address"),
+        })
+    }
+}
+
+impl PushElem for Location {
+    fn push_to(elem: Self, vec: &mut Vec<MessageElem>) {
+        LightApp::push_to(elem.to_light_app(), vec)
+    }
+}
+
+impl fmt::Display for Location {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "[Location: name='{}' address='{}' lat={} lon={}]",
+            self.name, self.address, self.lat, self.lon
+        )
+    }
+}
+
+to_elem_vec_impl!(Location);
+push_builder_impl!(Location);
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_location_light_app() {
+        let location = Location::new(39.9087, 116.3975, "天安门".into(), "北京市东城区".into());
+        let parsed = Location::from_light_app(&location.to_light_app()).unwrap();
+        assert_eq!(parsed.lat, location.lat);
+        assert_eq!(parsed.lon, location.lon);
+        assert_eq!(parsed.name, location.name);
+        assert_eq!(parsed.address, location.address);
+        assert!(Location::from_light_app(&LightApp::new("{}".into())).is_none());
+    }
+}
@@ -13,10 +13,15 @@ pub use crate::msg::elem::{
     friend_image::FriendImage,
     group_image::GroupImage,
     light_app::LightApp,
+    location::Location,
     market_face::{Dice, FingerGuessing, MarketFace},
+    poke::Poke,
     reply::Reply,
     rich_msg::RichMsg,
+    super_face::SuperFace,
     text::Text,
+    tips::Tips,
+    unknown::UnknownElem,
     video_file::VideoFile,
 };
 use crate::pb::msg;
@@ -28,10 +33,15 @@ mod flash_image;
 mod friend_image;
 mod group_image;
 mod light_app;
+mod location;
 mod market_face;
+mod poke;
 mod reply;
 mod rich_msg;
+mod super_face;
 mod text;
+mod tips;
+mod unknown;
 mod video_file;
 
 #[derive(Debug, Clone)]
@@ -43,11 +53,18 @@ pub enum RQElem {
     Dice(market_face::Dice),
     FingerGuessing(market_face::FingerGuessing),
     LightApp(light_app::LightApp),
+    Location(location::Location),
+    Poke(poke::Poke),
     RichMsg(rich_msg::RichMsg),
     FriendImage(friend_image::FriendImage),
     GroupImage(group_image::GroupImage),
     FlashImage(flash_image::FlashImage),
     VideoFile(video_file::VideoFile),
+    SuperFace(super_face::SuperFace),
+    Tips(tips::Tips),
+    /// 未解析的元素，保留原始数据
+    Unknown(unknown::UnknownElem),
+    /// 消息标记、发送者信息等非内容元素
     Other(Box<msg::elem::Elem>),
 }
 
@@ -64,6 +81,13 @@ impl From<msg::elem::Elem> for RQElem {
             }
             msg::elem::Elem::Face(e) => RQElem::Face(face::Face::from(e)),
             msg::elem::Elem::CommonElem(ref e) => match e.service_type() {
+                2 => {
+                    if let Ok(poke) = msg::MsgElemInfoServtype2::decode(e.pb_elem()) {
+                        RQElem::Poke(poke::Poke::from(poke))
+                    } else {
+                        RQElem::Other(Box::new(elem))
+                    }
+                }
                 // TODO image
                 3 => {
                     if let Ok(flash) = msg::MsgElemInfoServtype3::decode(e.pb_elem()) {
@@ -85,7 +109,14 @@ impl From<msg::elem::Elem> for RQElem {
                         RQElem::Other(Box::new(elem))
                     }
                 }
-                _ => RQElem::Other(Box::new(elem)),
+                37 => {
+                    if let Ok(face) = msg::MsgElemInfoServtype37::decode(e.pb_elem()) {
+                        RQElem::SuperFace(super_face::SuperFace::from(face))
+                    } else {
+                        RQElem::Other(Box::new(elem))
+                    }
+                }
+                _ => RQElem::Unknown(unknown::UnknownElem::from(e.clone())),
             },
             msg::elem::Elem::MarketFace(e) => {
                 let face = MarketFace::from(e);
@@ -96,13 +127,30 @@ impl From<msg::elem::Elem> for RQElem {
                     _ => RQElem::MarketFace(face),
                 }
             }
-            msg::elem::Elem::LightApp(e) => RQElem::LightApp(light_app::LightApp::from(e)),
+            msg::elem::Elem::LightApp(e) => {
+                let app = light_app::LightApp::from(e);
+                match location::Location::from_light_app(&app) {
+                    Some(location) => RQElem::Location(location),
+                    None => RQElem::LightApp(app),
+                }
+            }
             msg::elem::Elem::RichMsg(e) => RQElem::RichMsg(rich_msg::RichMsg::from(e)),
             msg::elem::Elem::VideoFile(e) => RQElem::VideoFile(video_file::VideoFile::from(e)),
             msg::elem::Elem::NotOnlineImage(e) => {
                 RQElem::FriendImage(friend_image::FriendImage::from(e))
             }
             msg::elem::Elem::CustomFace(e) => RQElem::GroupImage(group_image::GroupImage::from(e)),
+            msg::elem::Elem::TipsInfo(e) => RQElem::Tips(tips::Tips::from(e)),
+            msg::elem::Elem::TransElemInfo(e) => RQElem::Unknown(unknown::UnknownElem::from(e)),
+            msg::elem::Elem::CustomElem(e) => RQElem::Unknown(unknown::UnknownElem::from(e)),
+            msg::elem::Elem::QqWalletMsg(e) => {
+                RQElem::Unknown(unknown::UnknownElem::new(24, e.encode_to_vec()))
+            }
+            msg::elem::Elem::RedbagInfo(b) => RQElem::Unknown(unknown::UnknownElem::new(27, b)),
+            msg::elem::Elem::LowVersionTips(b) => RQElem::Unknown(unknown::UnknownElem::new(28, b)),
+            msg::elem::Elem::ArkApp(b) => RQElem::Unknown(unknown::UnknownElem::new(36, b)),
+            msg::elem::Elem::PatElem(b) => RQElem::Unknown(unknown::UnknownElem::new(49, b)),
+            msg::elem::Elem::GroupPostElem(b) => RQElem::Unknown(unknown::UnknownElem::new(50, b)),
             _ => RQElem::Other(Box::new(elem)),
         }
     }
@@ -118,7 +166,11 @@ impl fmt::Display for RQElem {
             RQElem::FriendImage(e) => fmt::Display::fmt(e, f),
             RQElem::FlashImage(e) => fmt::Display::fmt(e, f),
             RQElem::LightApp(e) => fmt::Display::fmt(e, f),
+            RQElem::Location(e) => fmt::Display::fmt(e, f),
+            RQElem::Poke(e) => fmt::Display::fmt(e, f),
             RQElem::RichMsg(e) => fmt::Display::fmt(e, f),
+            RQElem::SuperFace(e) => fmt::Display::fmt(e, f),
+            RQElem::Tips(e) => fmt::Display::fmt(e, f),
             _ => return Ok(()),
         }?;
         f.write_str(" ")
@@ -160,6 +212,8 @@ impl_from!(MarketFace, market_face::MarketFace);
 impl_from!(Dice, market_face::Dice);
 impl_from!(FingerGuessing, market_face::FingerGuessing);
 impl_from!(LightApp, light_app::LightApp);
+impl_from!(Location, location::Location);
+impl_from!(Poke, poke::Poke);
 impl_from!(RichMsg, rich_msg::RichMsg);
 impl_from!(FriendImage, friend_image::FriendImage);
 impl_from!(GroupImage, group_image::GroupImage);
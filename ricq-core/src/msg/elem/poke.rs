@@ -0,0 +1,96 @@
+use std::fmt;
+
+use prost::Message;
+
+use crate::msg::{MessageChainBuilder, PushBuilder};
+use crate::msg::{MessageElem, PushElem};
+use crate::pb::msg;
+use crate::{push_builder_impl, to_elem_vec_impl};
+
+/// 私聊中的戳一戳消息（戳一戳、比心、放大招等），仅好友消息可用
+#[derive(Default, Debug, Clone)]
+pub struct Poke {
+    pub poke_type: u32,
+    /// 超级戳一戳 id，普通戳一戳为 -1
+    pub id: i32,
+    pub name: String,
+}
+
+impl Poke {
+    pub fn new(poke_type: u32, id: i32, name: String) -> Self {
+        Self {
+            poke_type,
+            id,
+            name,
+        }
+    }
+
+    pub fn chuo_yi_chuo() -> Self {
+        Self::new(1, -1, "戳一戳".into())
+    }
+
+    pub fn bi_xin() -> Self {
+        Self::new(2, -1, "比心".into())
+    }
+
+    pub fn dian_zan() -> Self {
+        Self::new(3, -1, "点赞".into())
+    }
+
+    pub fn xin_sui() -> Self {
+        Self::new(4, -1, "心碎".into())
+    }
+
+    pub fn liu_liu_liu() -> Self {
+        Self::new(5, -1, "666".into())
+    }
+
+    pub fn fang_da_zhao() -> Self {
+        Self::new(6, -1, "放大招".into())
+    }
+}
+
+impl PushElem for Poke {
+    fn push_to(elem: Self, vec: &mut Vec<MessageElem>) {
+        let pb_elem = msg::MsgElemInfoServtype2 {
+            poke_type: Some(elem.poke_type),
+            vaspoke_id: Some(elem.id as u32),
+            vaspoke_name: Some(elem.name.as_bytes().to_vec()),
+            vaspoke_minver: Some(b"7.2.0".to_vec()),
+            poke_strength: Some(0),
+            msg_type: Some(0),
+            face_bubble_count: Some(0),
+            poke_flag: Some(0),
+            ..Default::default()
+        }
+        .encode_to_vec();
+        vec.push(msg::elem::Elem::CommonElem(msg::CommonElem {
+            service_type: Some(2),
+            pb_elem: Some(pb_elem),
+            business_type: Some(elem.poke_type as i32),
+        }));
+        vec.push(msg::elem::Elem::Text(msg::Text {
+            str: Some(format!("[{}]请使用最新版手机QQ体验新功能。", elem.name)),
+            ..Default::default()
+        }));
+    }
+}
+
+impl From<msg::MsgElemInfoServtype2> for Poke {
+    fn from(e: msg::MsgElemInfoServtype2) -> Self {
+        Self {
+            poke_type: e.poke_type(),
+            id: e.vaspoke_id() as i32,
+            name: String::from_utf8_lossy(e.vaspoke_name()).into_owned(),
+        }
+    }
+}
+
+impl fmt::Display for Poke {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "[Poke:{}]", self.name)
+    }
+}
+
+to_elem_vec_impl!(Poke);
+push_builder_impl!(Poke);
@@ -17,6 +17,41 @@ pub struct RichMsg {
     pub template1: String,
 }
 
+impl RichMsg {
+    /// 推荐联系人名片
+    pub fn friend_card(uin: i64, nickname: &str) -> Self {
+        let nickname = xml_escape(nickname);
+        let card_url = format!(
+            "mqqapi://card/show_pslcard?src_type=internal&amp;source=sharecard&amp;version=1&amp;uin={uin}"
+        );
+        Self {
+            service_id: 14,
+            template1: format!(
+                r#"<?xml version='1.0' encoding='UTF-8' standalone='yes' ?><msg serviceID="14" templateID="1" action="plugin" actionData="AppCmd://OpenContactInfo/?uin={uin}" a_actionData="{card_url}" i_actionData="{card_url}" brief="推荐了{nickname}" sourceMsgId="0" url="" flag="1" adverSign="0" multiMsgFlag="0"><item layout="0" mode="1" advertiser_id="0" aid="0"><summary>推荐联系人</summary><hr hidden="false" style="0" /></item><item layout="2" mode="1" advertiser_id="0" aid="0"><picture cover="https://q1.qlogo.cn/g?b=qq&amp;nk={uin}&amp;s=640" w="0" h="0" /><title>{nickname}</title><summary>帐号：{uin}</summary></item><source name="" icon="" action="" appid="-1" /></msg>"#
+            ),
+        }
+    }
+
+    /// 推荐群聊名片
+    pub fn group_card(group_code: i64, group_name: &str) -> Self {
+        let group_name = xml_escape(group_name);
+        Self {
+            service_id: 15,
+            template1: format!(
+                r#"<?xml version='1.0' encoding='UTF-8' standalone='yes' ?><msg serviceID="15" templateID="1" action="web" actionData="group:{group_code}" a_actionData="group:{group_code}" i_actionData="group:{group_code}" brief="推荐群聊：{group_name}" m_resid="" m_fileName="{group_code}" sourceMsgId="0" url="" flag="0" adverSign="0" multiMsgFlag="0"><item layout="0" mode="1" advertiser_id="0" aid="0"><summary>推荐群聊</summary><hr hidden="false" style="0" /></item><item layout="2" mode="1" advertiser_id="0" aid="0"><picture cover="https://p.qlogo.cn/gh/{group_code}/{group_code}/100" w="0" h="0" needRoundView="0" /><title>{group_name}</title><summary>群号：{group_code}</summary></item><source name="" icon="" action="" appid="-1" /></msg>"#
+            ),
+        }
+    }
+}
+
+fn xml_escape(s: &str) -> String {
+    s.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+        .replace('\'', "&apos;")
+}
+
 impl From<msg::RichMsg> for RichMsg {
     fn from(e: msg::RichMsg) -> Self {
         let data = e.template1.unwrap_or_default();
@@ -0,0 +1,64 @@
+use std::fmt;
+
+use prost::Message;
+
+use crate::msg::{MessageChainBuilder, PushBuilder};
+use crate::msg::{MessageElem, PushElem};
+use crate::pb::msg;
+use crate::{push_builder_impl, to_elem_vec_impl};
+
+/// 超级表情（大表情），CommonElem service_type 37
+#[derive(Default, Debug, Clone)]
+pub struct SuperFace {
+    /// 对应的小表情 id
+    pub index: u32,
+    pub name: String,
+    pub pack_id: String,
+    pub sticker_id: String,
+    pub sticker_type: u32,
+}
+
+impl From<msg::MsgElemInfoServtype37> for SuperFace {
+    fn from(e: msg::MsgElemInfoServtype37) -> Self {
+        let text = String::from_utf8_lossy(e.text()).into_owned();
+        Self {
+            index: e.qsid(),
+            name: text.trim_start_matches('/').into(),
+            pack_id: String::from_utf8_lossy(e.packid()).into_owned(),
+            sticker_id: String::from_utf8_lossy(e.stickerid()).into_owned(),
+            sticker_type: e.stickertype(),
+        }
+    }
+}
+
+impl PushElem for SuperFace {
+    fn push_to(e: Self, vec: &mut Vec<MessageElem>) {
+        let text = format!("/{}", e.name).into_bytes();
+        let elem = msg::MsgElemInfoServtype37 {
+            packid: Some(e.pack_id.into_bytes()),
+            stickerid: Some(e.sticker_id.into_bytes()),
+            qsid: Some(e.index),
+            sourcetype: Some(1),
+            stickertype: Some(e.sticker_type),
+            resultid: Some(vec![]),
+            text: Some(text),
+            surpriseid: Some(vec![]),
+            randomtype: Some(1),
+        }
+        .encode_to_vec();
+        vec.push(MessageElem::CommonElem(msg::CommonElem {
+            service_type: Some(37),
+            pb_elem: Some(elem),
+            business_type: Some(1),
+        }));
+    }
+}
+
+impl fmt::Display for SuperFace {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "[{}]", self.name)
+    }
+}
+
+to_elem_vec_impl!(SuperFace);
+push_builder_impl!(SuperFace);
@@ -0,0 +1,23 @@
+use std::fmt;
+
+use crate::pb::msg;
+
+/// 消息中的灰色提示，只能接收
+#[derive(Default, Debug, Clone)]
+pub struct Tips {
+    pub text: String,
+}
+
+impl From<msg::TipsInfo> for Tips {
+    fn from(e: msg::TipsInfo) -> Self {
+        Self {
+            text: String::from_utf8_lossy(e.text()).into_owned(),
+        }
+    }
+}
+
+impl fmt::Display for Tips {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "[Tips: {}]", self.text)
+    }
+}
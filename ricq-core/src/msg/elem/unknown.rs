@@ -0,0 +1,56 @@
+use crate::pb::msg;
+
+/// 未解析的消息元素，保留原始数据
+#[derive(Default, Debug, Clone)]
+pub struct UnknownElem {
+    /// Elem 中的字段号，如 53 为 CommonElem
+    pub elem_type: u32,
+    /// CommonElem 的 service_type、TransElem 的 elem_type、CustomElem 的 enum_type
+    pub service_type: Option<i32>,
+    /// CommonElem 的 business_type
+    pub business_type: Option<i32>,
+    pub bytes: Vec<u8>,
+}
+
+impl UnknownElem {
+    pub fn new(elem_type: u32, bytes: Vec<u8>) -> Self {
+        Self {
+            elem_type,
+            bytes,
+            ..Default::default()
+        }
+    }
+}
+
+impl From<msg::CommonElem> for UnknownElem {
+    fn from(e: msg::CommonElem) -> Self {
+        Self {
+            elem_type: 53,
+            service_type: e.service_type,
+            business_type: e.business_type,
+            bytes: e.pb_elem.unwrap_or_default(),
+        }
+    }
+}
+
+impl From<msg::TransElem> for UnknownElem {
+    fn from(e: msg::TransElem) -> Self {
+        Self {
+            elem_type: 5,
+            service_type: e.elem_type,
+            business_type: None,
+            bytes: e.elem_value.unwrap_or_default(),
+        }
+    }
+}
+
+impl From<msg::CustomElem> for UnknownElem {
+    fn from(e: msg::CustomElem) -> Self {
+        Self {
+            elem_type: 31,
+            service_type: e.enum_type,
+            business_type: None,
+            bytes: e.data.unwrap_or_default(),
+        }
+    }
+}
@@ -0,0 +1,177 @@
+use serde_json::{json, Value};
+
+use crate::msg::elem::{At, Dice, Face, LightApp, Poke, RQElem, Reply, RichMsg, Text};
+use crate::msg::MessageChain;
+use crate::{RQError, RQResult};
+
+// mirai-api-http 中戳一戳的名称，下标对应 poke_type - 1
+const POKE_NAMES: [&str; 6] = [
+    "ChuoYiChuo",
+    "BiXin",
+    "DianZan",
+    "XinSui",
+    "LiuLiuLiu",
+    "FangDaZhao",
+];
+
+/// mirai 的 messageChain JSON 格式，用于兼容 mirai-api-http 生态
+impl MessageChain {
+    /// 转为 mirai messageChain 数组，无法表示的元素会被忽略
+    pub fn to_mirai_json(&self) -> Value {
+        let mut chain = Vec::new();
+        if let Some(reply) = self.reply() {
+            chain.push(json!({
+                "type": "Quote",
+                "id": reply.reply_seq,
+                "senderId": reply.sender,
+                "origin": reply.elements.to_mirai_json(),
+            }));
+        }
+        for elem in self.clone() {
+            let value = match elem {
+                RQElem::Text(e) => json!({ "type": "Plain", "text": e.content }),
+                RQElem::At(e) if e.target == 0 => json!({ "type": "AtAll" }),
+                RQElem::At(e) => json!({ "type": "At", "target": e.target, "display": e.display }),
+                RQElem::Face(e) => json!({ "type": "Face", "faceId": e.index, "name": e.name }),
+                RQElem::Dice(e) => json!({ "type": "Dice", "value": e.value }),
+                RQElem::MarketFace(e) => {
+                    json!({ "type": "MarketFace", "id": e.tab_id, "name": e.name })
+                }
+                RQElem::Poke(e) => {
+                    let name = POKE_NAMES
+                        .get((e.poke_type as usize).wrapping_sub(1))
+                        .copied()
+                        .unwrap_or(POKE_NAMES[0]);
+                    json!({ "type": "Poke", "name": name })
+                }
+                RQElem::GroupImage(e) => {
+                    json!({ "type": "Image", "imageId": e.file_path, "url": e.url() })
+                }
+                RQElem::FriendImage(e) => {
+                    json!({ "type": "Image", "imageId": e.file_path, "url": e.url() })
+                }
+                RQElem::FlashImage(e) => json!({ "type": "FlashImage", "url": e.url() }),
+                RQElem::LightApp(e) => json!({ "type": "App", "content": e.content }),
+                RQElem::Location(e) => {
+                    json!({ "type": "App", "content": e.to_light_app().content })
+                }
+                RQElem::RichMsg(e) => json!({ "type": "Xml", "xml": e.template1 }),
+                _ => continue,
+            };
+            chain.push(value);
+        }
+        Value::Array(chain)
+    }
+
+    /// 从 mirai messageChain 数组解析，Source 会被忽略；图片等需要上传的元素返回错误
+    pub fn from_mirai_json(value: &Value) -> RQResult<Self> {
+        let items = value
+            .as_array()
+            .ok_or_else(|| RQError::Decode("messageChain must be an array".into()))?;
+        let mut chain = MessageChain::default();
+        let mut reply = None;
+        for item in items {
+            let kind = item.get("type").and_then(Value::as_str).unwrap_or_default();
+            let str_field = |key: &str| {
+                item.get(key)
+                    .and_then(Value::as_str)
+                    .map(ToString::to_string)
+                    .ok_or_else(|| RQError::Decode(format!("{kind}.{key} required")))
+            };
+            let int_field = |key: &str| {
+                item.get(key)
+                    .and_then(Value::as_i64)
+                    .ok_or_else(|| RQError::Decode(format!("{kind}.{key} required")))
+            };
+            match kind {
+                "Source" => {}
+                "Plain" => chain.push(Text::new(str_field("text")?)),
+                "At" => chain.push(At {
+                    target: int_field("target")?,
+                    display: str_field("display").unwrap_or_default(),
+                }),
+                "AtAll" => chain.push(At::new(0)),
+                "Face" => match (int_field("faceId"), str_field("name")) {
+                    (Ok(id), _) => chain.push(Face::new(id as i32)),
+                    (_, Ok(name)) => chain.push(
+                        Face::new_from_name(&name)
+                            .ok_or_else(|| RQError::Decode(format!("unknown face: {name}")))?,
+                    ),
+                    (Err(e), _) => return Err(e),
+                },
+                "Dice" => chain.push(Dice::new(int_field("value")? as i32)),
+                "Poke" => {
+                    let name = str_field("name")?;
+                    let index = POKE_NAMES
+                        .iter()
+                        .position(|n| *n == name)
+                        .ok_or_else(|| RQError::Decode(format!("unknown poke: {name}")))?;
+                    chain.push(Poke::new(index as u32 + 1, -1, name));
+                }
+                "App" => chain.push(LightApp::new(str_field("content")?)),
+                "Json" => chain.push(LightApp::new(str_field("json")?)),
+                "Xml" => chain.push(RichMsg {
+                    service_id: 1,
+                    template1: str_field("xml")?,
+                }),
+                "Quote" => {
+                    let origin = match item.get("origin") {
+                        Some(origin) => MessageChain::from_mirai_json(origin)?,
+                        None => MessageChain::default(),
+                    };
+                    reply = Some(Reply {
+                        reply_seq: int_field("id")? as i32,
+                        sender: int_field("senderId").unwrap_or_default(),
+                        time: 0,
+                        elements: origin,
+                    });
+                }
+                _ => {
+                    return Err(RQError::Decode(format!(
+                        "unsupported mirai message type: {kind}"
+                    )))
+                }
+            }
+        }
+        if let Some(reply) = reply {
+            chain.with_reply(reply);
+        }
+        Ok(chain)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_mirai_json() {
+        let value = json!([
+            { "type": "Source", "id": 1, "time": 0 },
+            { "type": "Quote", "id": 10, "senderId": 2, "origin": [{ "type": "Plain", "text": "hi" }] },
+            { "type": "At", "target": 3, "display": "@3" },
+            { "type": "Plain", "text": "hello" },
+            { "type": "Face", "faceId": 1 },
+            { "type": "Poke", "name": "BiXin" },
+        ]);
+        let chain = MessageChain::from_mirai_json(&value).unwrap();
+        let reply = chain.reply().unwrap();
+        assert_eq!(reply.reply_seq, 10);
+        assert_eq!(reply.sender, 2);
+
+        let output = chain.to_mirai_json();
+        let types: Vec<&str> = output
+            .as_array()
+            .unwrap()
+            .iter()
+            .map(|v| v["type"].as_str().unwrap())
+            .collect();
+        // 戳一戳发送时会附带一段提示文本
+        assert_eq!(types[..5], ["Quote", "At", "Plain", "Face", "Poke"]);
+        assert_eq!(output[0]["origin"][0]["text"], "hi");
+        assert_eq!(output[4]["name"], "BiXin");
+
+        let image = json!([{ "type": "Image", "imageId": "{0}.jpg" }]);
+        assert!(MessageChain::from_mirai_json(&image).is_err());
+    }
+}
@@ -1,5 +1,9 @@
 use std::fmt;
 
+use prost::Message;
+use serde::ser::SerializeStruct;
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+
 use elem::RQElem;
 use elem::*;
 
@@ -8,6 +12,9 @@ use crate::pb::msg;
 pub mod elem;
 mod fragment;
 mod macros;
+mod mirai;
+pub mod render;
+pub mod template;
 
 pub type MessageElem = msg::elem::Elem;
 
@@ -121,6 +128,47 @@ impl From<MessageChain> for Vec<msg::Elem> {
     }
 }
 
+// 序列化为 protobuf RichText 字节，不丢失任何元素；
+// JSON 等可读格式另外带有渲染后的 text 和 mirai 格式的 elements，反序列化时只读取 raw
+impl Serialize for MessageChain {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        let rich_text = msg::RichText {
+            elems: self.clone().into(),
+            ..Default::default()
+        };
+        let raw = rich_text.encode_to_vec();
+        if !serializer.is_human_readable() {
+            return serializer.serialize_bytes(&raw);
+        }
+        let mut s = serializer.serialize_struct("MessageChain", 3)?;
+        s.serialize_field("text", &self.to_string())?;
+        s.serialize_field("elements", &self.to_mirai_json())?;
+        s.serialize_field("raw", &raw)?;
+        s.end()
+    }
+}
+
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum ReadableChain {
+    Raw(Vec<u8>),
+    Struct { raw: Vec<u8> },
+}
+
+impl<'de> Deserialize<'de> for MessageChain {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let buf: Vec<u8> = if deserializer.is_human_readable() {
+            match ReadableChain::deserialize(deserializer)? {
+                ReadableChain::Raw(raw) | ReadableChain::Struct { raw } => raw,
+            }
+        } else {
+            Deserialize::deserialize(deserializer)?
+        };
+        let rich_text = msg::RichText::decode(buf.as_slice()).map_err(serde::de::Error::custom)?;
+        Ok(MessageChain::from(rich_text.elems))
+    }
+}
+
 impl fmt::Display for MessageChain {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         for x in self.clone().into_iter() {
@@ -260,6 +308,23 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_serde() {
+        let mut chain = MessageChain::default();
+        chain.with_reply(Reply::default());
+        chain.push(Text::new("hello".into()));
+        chain.push(At::new(12345));
+        let json = serde_json::to_value(&chain).unwrap();
+        assert_eq!(json["text"], chain.to_string());
+        assert_eq!(json["elements"], chain.to_mirai_json());
+        let parsed: MessageChain = serde_json::from_value(json.clone()).unwrap();
+        assert!(parsed.reply().is_some());
+        assert_eq!(parsed.to_string(), chain.to_string());
+        // 只有 raw 字节的旧格式
+        let parsed: MessageChain = serde_json::from_value(json["raw"].clone()).unwrap();
+        assert_eq!(parsed.to_string(), chain.to_string());
+    }
+
     #[test]
     fn test_builder() {
         let mut builder = MessageChainBuilder::new();
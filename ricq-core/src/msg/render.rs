@@ -0,0 +1,235 @@
+use crate::msg::elem::{FingerGuessing, RQElem};
+use crate::msg::MessageChain;
+
+/// 把 [`MessageChain`] 渲染为文本，用于日志、关键词匹配等
+pub trait ChainRenderer {
+    fn render(&self, chain: &MessageChain) -> String;
+}
+
+impl<F> ChainRenderer for F
+where
+    F: Fn(&MessageChain) -> String,
+{
+    fn render(&self, chain: &MessageChain) -> String {
+        self(chain)
+    }
+}
+
+/// At 的显示方式
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum AtStyle {
+    /// 使用 At 中的 display，通常为 `@昵称`
+    Display,
+    /// `@QQ号`，全体成员为 `@全体成员`
+    Uin,
+    /// 不显示
+    Skip,
+}
+
+/// 默认的文本渲染器
+///
+/// ```rust
+/// use ricq_core::msg::render::{AtStyle, TextRenderer};
+/// let renderer = TextRenderer::new().image("[img]").at_style(AtStyle::Uin);
+/// ```
+#[derive(Debug, Clone)]
+pub struct TextRenderer {
+    image: Option<String>,
+    at_style: AtStyle,
+    face: bool,
+    others: bool,
+    include_reply: bool,
+}
+
+impl Default for TextRenderer {
+    fn default() -> Self {
+        Self {
+            image: Some("[图片]".into()),
+            at_style: AtStyle::Display,
+            face: true,
+            others: true,
+            include_reply: false,
+        }
+    }
+}
+
+impl TextRenderer {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// 只保留文本，用于关键词匹配
+    pub fn plain() -> Self {
+        Self {
+            image: None,
+            at_style: AtStyle::Skip,
+            face: false,
+            others: false,
+            include_reply: false,
+        }
+    }
+
+    /// 图片（含闪照）的占位文本
+    pub fn image(mut self, placeholder: impl Into<String>) -> Self {
+        self.image = Some(placeholder.into());
+        self
+    }
+
+    /// 不显示图片
+    pub fn skip_image(mut self) -> Self {
+        self.image = None;
+        self
+    }
+
+    pub fn at_style(mut self, style: AtStyle) -> Self {
+        self.at_style = style;
+        self
+    }
+
+    /// 是否显示表情，显示为 `[表情名]`
+    pub fn face(mut self, face: bool) -> Self {
+        self.face = face;
+        self
+    }
+
+    /// 是否显示骰子、视频、小程序等其他元素的占位文本
+    pub fn others(mut self, others: bool) -> Self {
+        self.others = others;
+        self
+    }
+
+    /// 是否在开头显示引用回复，显示为 `[回复 QQ号: 内容]`
+    pub fn include_reply(mut self, include_reply: bool) -> Self {
+        self.include_reply = include_reply;
+        self
+    }
+
+    fn render_elem(&self, elem: RQElem, out: &mut String) {
+        match elem {
+            RQElem::Text(e) => out.push_str(&e.content),
+            RQElem::At(e) => match self.at_style {
+                AtStyle::Display => out.push_str(&e.display),
+                AtStyle::Uin if e.target == 0 => out.push_str("@全体成员"),
+                AtStyle::Uin => out.push_str(&format!("@{}", e.target)),
+                AtStyle::Skip => {}
+            },
+            RQElem::Face(e) if self.face => out.push_str(&format!("[{}]", e.name)),
+            RQElem::SuperFace(e) if self.face => out.push_str(&format!("[{}]", e.name)),
+            RQElem::GroupImage(_) | RQElem::FriendImage(_) | RQElem::FlashImage(_) => {
+                if let Some(image) = &self.image {
+                    out.push_str(image)
+                }
+            }
+            elem if self.others => match elem {
+                // 商城表情的名称本身带有方括号
+                RQElem::MarketFace(e) => out.push_str(&e.name),
+                RQElem::Dice(e) => out.push_str(&format!("[骰子:{}]", e.value)),
+                RQElem::FingerGuessing(e) => out.push_str(match e {
+                    FingerGuessing::Rock => "[猜拳:石头]",
+                    FingerGuessing::Scissors => "[猜拳:剪刀]",
+                    FingerGuessing::Paper => "[猜拳:布]",
+                }),
+                RQElem::Poke(e) => out.push_str(&format!("[戳一戳:{}]", e.name)),
+                RQElem::VideoFile(_) => out.push_str("[视频]"),
+                RQElem::Location(e) => out.push_str(&format!("[位置:{}]", e.name)),
+                RQElem::LightApp(_) => out.push_str("[小程序]"),
+                RQElem::RichMsg(_) => out.push_str("[卡片消息]"),
+                RQElem::Tips(e) => out.push_str(&e.text),
+                _ => {}
+            },
+            _ => {}
+        }
+    }
+}
+
+impl ChainRenderer for TextRenderer {
+    fn render(&self, chain: &MessageChain) -> String {
+        let mut out = String::new();
+        if self.include_reply {
+            if let Some(reply) = chain.reply() {
+                // 被引用的消息不再展开它自己的引用
+                let inner = Self {
+                    include_reply: false,
+                    ..self.clone()
+                };
+                out.push_str(&format!(
+                    "[回复 {}: {}]",
+                    reply.sender,
+                    inner.render(&reply.elements)
+                ));
+            }
+        }
+        let mut market_face = false;
+        for elem in chain.clone() {
+            // 商城表情后面会跟一段 `[名称]` 文本，已经由表情本身渲染
+            if market_face {
+                market_face = false;
+                if let RQElem::Text(text) = &elem {
+                    if text.content.starts_with('[') && text.content.ends_with(']') {
+                        continue;
+                    }
+                }
+            }
+            market_face = matches!(
+                elem,
+                RQElem::MarketFace(_) | RQElem::Dice(_) | RQElem::FingerGuessing(_)
+            );
+            self.render_elem(elem, &mut out);
+        }
+        out
+    }
+}
+
+impl MessageChain {
+    /// 使用指定的渲染器转为文本
+    pub fn render<R: ChainRenderer + ?Sized>(&self, renderer: &R) -> String {
+        renderer.render(self)
+    }
+
+    /// 使用默认的 [`TextRenderer`] 转为文本，图片显示为 `[图片]`
+    pub fn to_text(&self) -> String {
+        self.render(&TextRenderer::default())
+    }
+
+    /// 只保留文本内容，用于关键词匹配
+    pub fn plain_text(&self) -> String {
+        self.render(&TextRenderer::plain())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::msg::elem::{At, Dice, Reply, Text};
+
+    #[test]
+    fn test_text_renderer() {
+        let mut chain = MessageChain::default();
+        chain.push(At {
+            target: 10000,
+            display: "@bot".into(),
+        });
+        chain.push(Text::new(" hello".into()));
+        chain.push(Dice::new(3));
+        chain.with_reply(Reply {
+            reply_seq: 1,
+            sender: 20000,
+            time: 0,
+            elements: MessageChain::new(Text::new("hi".into())),
+        });
+
+        assert_eq!(chain.to_text(), "@bot hello[骰子:3]");
+        assert_eq!(chain.plain_text(), " hello");
+        assert_eq!(
+            chain.render(
+                &TextRenderer::new()
+                    .at_style(AtStyle::Uin)
+                    .others(false)
+                    .include_reply(true)
+            ),
+            "[回复 20000: hi]@10000 hello"
+        );
+        let count = |c: &MessageChain| c.clone().into_iter().count().to_string();
+        assert_eq!(chain.render(&count), "4");
+    }
+}
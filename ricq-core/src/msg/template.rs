@@ -0,0 +1,136 @@
+use crate::msg::elem::{At, Dice, Face, Text};
+use crate::msg::MessageChain;
+use crate::{RQError, RQResult};
+
+/// 模板片段，占位符格式为 `{kind:arg}` 或 `{kind}`
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum TemplateToken {
+    Text(String),
+    Placeholder { kind: String, arg: String },
+}
+
+/// 拆分模板字符串，`{{` 和 `}}` 转义为花括号
+pub fn parse_template(s: &str) -> RQResult<Vec<TemplateToken>> {
+    let mut tokens = Vec::new();
+    let mut text = String::new();
+    let mut chars = s.chars().peekable();
+    while let Some(c) = chars.next() {
+        match c {
+            '{' if chars.peek() == Some(&'{') => {
+                chars.next();
+                text.push('{');
+            }
+            '}' if chars.peek() == Some(&'}') => {
+                chars.next();
+                text.push('}');
+            }
+            '{' => {
+                let mut inner = String::new();
+                loop {
+                    match chars.next() {
+                        Some('}') => break,
+                        Some(c) => inner.push(c),
+                        None => {
+                            return Err(RQError::Decode(format!(
+                                "unclosed template placeholder: {{{inner}"
+                            )))
+                        }
+                    }
+                }
+                if !text.is_empty() {
+                    tokens.push(TemplateToken::Text(std::mem::take(&mut text)));
+                }
+                let (kind, arg) = inner.split_once(':').unwrap_or((&inner, ""));
+                tokens.push(TemplateToken::Placeholder {
+                    kind: kind.trim().into(),
+                    arg: arg.trim().into(),
+                });
+            }
+            '}' => return Err(RQError::Decode("unmatched '}' in template".into())),
+            c => text.push(c),
+        }
+    }
+    if !text.is_empty() {
+        tokens.push(TemplateToken::Text(text));
+    }
+    Ok(tokens)
+}
+
+impl MessageChain {
+    /// 从模板构造消息链，如 `"hi {at:12345} {face:微笑}"`
+    ///
+    /// 支持 `at`（`at:all` 为全体成员）、`face`（id 或名称）、`dice`，
+    /// 图片等需要上传的占位符使用 `Client::group_chain_from_template` 等方法
+    pub fn from_template(s: &str) -> RQResult<Self> {
+        let mut chain = Self::default();
+        for token in parse_template(s)? {
+            chain.push_template_token(token)?;
+        }
+        Ok(chain)
+    }
+
+    /// 添加不需要上传的模板片段，不支持的占位符返回错误
+    pub fn push_template_token(&mut self, token: TemplateToken) -> RQResult<()> {
+        let (kind, arg) = match token {
+            TemplateToken::Text(text) => {
+                self.push(Text::new(text));
+                return Ok(());
+            }
+            TemplateToken::Placeholder { kind, arg } => (kind, arg),
+        };
+        let invalid = || RQError::Decode(format!("invalid template placeholder: {{{kind}:{arg}}}"));
+        match kind.as_str() {
+            "at" if arg == "all" => self.push(At {
+                target: 0,
+                display: "@全体成员".into(),
+            }),
+            "at" => self.push(At::new(arg.parse().map_err(|_| invalid())?)),
+            "face" => match arg.parse() {
+                Ok(id) => self.push(Face::new(id)),
+                Err(_) => self.push(Face::new_from_name(&arg).ok_or_else(invalid)?),
+            },
+            "dice" => self.push(Dice::new(arg.parse().map_err(|_| invalid())?)),
+            _ => {
+                return Err(RQError::Decode(format!(
+                    "unsupported template placeholder: {kind}"
+                )))
+            }
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::msg::elem::RQElem;
+
+    #[test]
+    fn test_parse_template() {
+        let tokens = parse_template("hi {at:12345} look {image: a.png}{{x}}").unwrap();
+        assert_eq!(
+            tokens,
+            vec![
+                TemplateToken::Text("hi ".into()),
+                TemplateToken::Placeholder {
+                    kind: "at".into(),
+                    arg: "12345".into()
+                },
+                TemplateToken::Text(" look ".into()),
+                TemplateToken::Placeholder {
+                    kind: "image".into(),
+                    arg: "a.png".into()
+                },
+                TemplateToken::Text("{x}".into()),
+            ]
+        );
+        assert!(parse_template("{at:1").is_err());
+        assert!(parse_template("a}b").is_err());
+
+        let chain = MessageChain::from_template("{at:all} hi {face:1}").unwrap();
+        let elems: Vec<RQElem> = chain.into_iter().collect();
+        assert!(matches!(&elems[0], RQElem::At(at) if at.target == 0));
+        assert!(matches!(&elems[2], RQElem::Face(face) if face.index == 1));
+        assert!(MessageChain::from_template("{image:a.png}").is_err());
+    }
+}
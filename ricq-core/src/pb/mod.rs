@@ -28,6 +28,7 @@ add_includes!(
     oidb,
     online_status,
     profilecard,
+    ptt_trans,
     sig_act,
     structmsg,
     short_video,
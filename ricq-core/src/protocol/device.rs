@@ -1,7 +1,9 @@
 use bytes::Bytes;
 use rand::distributions::DistString;
-use rand::{distributions::Alphanumeric, Rng, RngCore};
+use rand::rngs::StdRng;
+use rand::{distributions::Alphanumeric, Rng, RngCore, SeedableRng};
 use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
 
 use crate::hex::encode_hex;
 use crate::protocol::qimei::Qimei;
@@ -26,6 +28,26 @@ impl Default for OSVersion {
     }
 }
 
+/// Device::randomize 重新生成的字段
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct RandomFields {
+    pub imei: bool,
+    pub android_id: bool,
+    pub mac_address: bool,
+    pub finger_print: bool,
+}
+
+impl Default for RandomFields {
+    fn default() -> Self {
+        Self {
+            imei: true,
+            android_id: true,
+            mac_address: true,
+            finger_print: true,
+        }
+    }
+}
+
 //手机设备信息
 #[derive(Default, Serialize, Deserialize, Debug, Clone)]
 pub struct Device {
@@ -97,6 +119,48 @@ impl Device {
         }
     }
 
+    /// 相同的 seed 生成相同的设备，可用于测试
+    ///
+    /// 依赖 rand 的 StdRng 算法，升级 rand 后结果可能改变，需要长期保持的设备应序列化保存
+    pub fn random_with_seed(seed: u64) -> Self {
+        Self::random_with_rng(&mut StdRng::seed_from_u64(seed))
+    }
+
+    /// 从密钥和账号派生设备，同一个账号每次得到相同的设备，不同账号的设备互不相关
+    pub fn derive(secret: &[u8], uin: i64) -> Self {
+        let seed: [u8; 32] = Sha256::new()
+            .chain_update(secret)
+            .chain_update(uin.to_be_bytes())
+            .finalize()
+            .into();
+        Self::random_with_rng(&mut StdRng::from_seed(seed))
+    }
+
+    /// 重新随机生成指定字段，其他字段保持不变
+    pub fn randomize<RNG: RngCore>(&mut self, rng: &mut RNG, fields: RandomFields) {
+        if fields.imei {
+            self.imei = random_imei(rng);
+        }
+        if fields.android_id {
+            self.android_id = encode_hex(&rng.gen::<[u8; 8]>());
+        }
+        if fields.mac_address {
+            self.mac_address = random_mac(rng);
+            self.wifi_bssid = self.mac_address.clone();
+        }
+        if fields.finger_print {
+            self.finger_print = format!(
+                "{}/{}/{}:{}/{}.200122.001/{}:user/release-keys",
+                self.brand.to_lowercase(),
+                self.product,
+                self.device,
+                self.version.release,
+                self.board,
+                rng.gen_range(1000000..9999999)
+            );
+        }
+    }
+
     pub fn ksid(&self) -> Bytes {
         Bytes::from(
             format!("|{}|A8.2.7.27f6ea96", self.imei)
@@ -108,6 +172,14 @@ impl Device {
     pub fn set_qimei(&mut self, qimei: Qimei) {
         self.qimei = Some(qimei)
     }
+
+    /// 登录 tlv 0x545 使用的 qimei16，没有获取 qimei 时使用 imei
+    pub fn qimei16(&self) -> &str {
+        match self.qimei {
+            Some(ref qimei) => &qimei.q16,
+            None => &self.imei,
+        }
+    }
 }
 
 pub fn random_string(len: usize) -> String {
@@ -126,6 +198,16 @@ pub fn random_uuid<RNG: RngCore>(rng: &mut RNG) -> String {
     )
 }
 
+pub fn random_mac<RNG: RngCore>(rng: &mut RNG) -> String {
+    let mut mac = rng.gen::<[u8; 6]>();
+    // 单播、全局唯一
+    mac[0] &= 0xfc;
+    mac.iter()
+        .map(|b| format!("{b:02X}"))
+        .collect::<Vec<_>>()
+        .join(":")
+}
+
 pub fn random_imei<RNG: RngCore>(rng: &mut RNG) -> String {
     let mut sum = 0;
     let mut str = String::new();
@@ -144,3 +226,33 @@ pub fn random_imei<RNG: RngCore>(rng: &mut RNG) -> String {
     str.push_str(&ctrl_digit.to_string());
     str
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_derive() {
+        let a = Device::derive(b"secret", 10000);
+        let b = Device::derive(b"secret", 10000);
+        let c = Device::derive(b"secret", 10001);
+        assert_eq!(a.imei, b.imei);
+        assert_eq!(a.android_id, b.android_id);
+        assert_ne!(a.imei, c.imei);
+
+        let mut d = a.clone();
+        d.randomize(
+            &mut StdRng::seed_from_u64(1),
+            RandomFields {
+                imei: false,
+                android_id: true,
+                mac_address: true,
+                finger_print: false,
+            },
+        );
+        assert_eq!(d.imei, a.imei);
+        assert_eq!(d.finger_print, a.finger_print);
+        assert_ne!(d.android_id, a.android_id);
+        assert_eq!(d.mac_address.len(), 17);
+    }
+}
@@ -1,19 +1,34 @@
 use bytes::Bytes;
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, HashSet};
 use std::time::Duration;
 
 pub use crate::command::multi_msg::{ForwardMessage, ForwardNode, MessageNode};
 pub use crate::command::oidb_svc::{
     LinkShare, MusicShare, MusicVersion, ProfileDetailUpdate, ShareTarget,
 };
-pub use crate::command::stat_svc::{CustomOnlineStatus, ExtOnlineStatus, OnlineStatus, Status};
+pub use crate::command::stat_svc::{
+    Battery, CustomOnlineStatus, ExtOnlineStatus, OnlineStatus, Status,
+};
 use crate::msg::MessageChain;
 use crate::{jce, pb};
 
-#[derive(Default, Debug)]
+#[derive(Default, Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
 pub struct AccountInfo {
     pub nickname: String,
     pub age: u8,
     pub gender: u8,
+    /// 在线状态，见 `OnlineStatus`，注册客户端后更新
+    pub online_status: i32,
+    /// 扩展在线状态，见 `ExtOnlineStatus`
+    pub ext_online_status: i64,
+}
+
+/// 账号信息变化，由 `Client::refresh_account_info` 比对得出
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct AccountInfoChange {
+    pub old: AccountInfo,
+    pub new: AccountInfo,
 }
 
 #[derive(Default, Debug)]
@@ -31,6 +46,37 @@ pub struct OtherClientInfo {
     pub device_kind: String,
 }
 
+/// 当前账号在线的设备
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct OnlineDevice {
+    pub app_id: i64,
+    pub guid: Vec<u8>,
+    pub device_name: String,
+    /// 设备型号
+    pub device_kind: String,
+    pub login_platform: i64,
+    pub login_location: String,
+    /// 登录时间戳（秒）
+    pub login_time: i64,
+    /// 是否可以被踢下线
+    pub can_be_kicked: bool,
+}
+
+impl From<jce::SvcDevLoginInfo> for OnlineDevice {
+    fn from(info: jce::SvcDevLoginInfo) -> Self {
+        Self {
+            app_id: info.app_id,
+            guid: info.guid.to_vec(),
+            device_name: info.device_name,
+            device_kind: info.device_type_info,
+            login_platform: info.login_platform,
+            login_location: info.login_location,
+            login_time: info.login_time,
+            can_be_kicked: info.can_be_kicked != 0,
+        }
+    }
+}
+
 pub struct QiDianAccountInfo {
     pub master_uin: i64,
     pub ext_name: String,
@@ -63,6 +109,98 @@ pub struct GroupInfo {
     pub my_shut_up_timestamp: i64,
     // 最后一条信息的SEQ,只有通过 GetGroupInfo 函数获取的 GroupInfo 才会有
     pub last_msg_seq: i64,
+    pub settings: GroupSettings,
+}
+
+// groupFlagExt
+const GROUP_FLAG_EXT_MEMBER_INVITE: u32 = 0x000000c0;
+// groupFlagext3，置位表示关闭
+const GROUP_FLAG_EXT3_NO_TEMP_CHAT: u32 = 0x00000100;
+const GROUP_FLAG_EXT3_CONFESS_TALK: u32 = 0x00002000;
+const GROUP_FLAG_EXT3_AUTO_APPROVE: u32 = 0x00100000;
+
+/// 群设置，通过 `Client::update_group_settings` 修改
+#[derive(Debug, Default, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct GroupSettings {
+    /// 允许群成员邀请好友入群
+    pub allow_member_invite: bool,
+    /// 允许通过群发起临时会话
+    pub allow_temp_chat: bool,
+    /// 群成员邀请好友入群时无需审核
+    pub auto_approve: bool,
+    /// 开启坦白说
+    pub confess_talk: bool,
+    /// 可以通过群号搜索到
+    pub searchable: bool,
+}
+
+impl GroupSettings {
+    pub fn from_flags(flag_ext: u32, flag_ext3: u32, no_finger_open: u32) -> Self {
+        Self {
+            allow_member_invite: flag_ext & GROUP_FLAG_EXT_MEMBER_INVITE != 0,
+            allow_temp_chat: flag_ext3 & GROUP_FLAG_EXT3_NO_TEMP_CHAT == 0,
+            auto_approve: flag_ext3 & GROUP_FLAG_EXT3_AUTO_APPROVE == 0,
+            confess_talk: flag_ext3 & GROUP_FLAG_EXT3_CONFESS_TALK == 0,
+            searchable: no_finger_open == 0,
+        }
+    }
+
+    /// 返回 (groupFlagext3, groupFlagext3Mask)
+    pub fn flag_ext3(&self) -> (u32, u32) {
+        let mut flag = 0;
+        if !self.allow_temp_chat {
+            flag |= GROUP_FLAG_EXT3_NO_TEMP_CHAT;
+        }
+        if !self.auto_approve {
+            flag |= GROUP_FLAG_EXT3_AUTO_APPROVE;
+        }
+        if !self.confess_talk {
+            flag |= GROUP_FLAG_EXT3_CONFESS_TALK;
+        }
+        let mask = GROUP_FLAG_EXT3_NO_TEMP_CHAT
+            | GROUP_FLAG_EXT3_AUTO_APPROVE
+            | GROUP_FLAG_EXT3_CONFESS_TALK;
+        (flag, mask)
+    }
+}
+
+/// 加群方式
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub enum GroupJoinMode {
+    /// 允许任何人加群
+    Anyone,
+    /// 需要管理员审核
+    NeedApproval,
+    /// 不允许任何人加群
+    Forbidden,
+    /// 回答问题并由管理员审核
+    Question { question: String },
+    /// 正确回答问题后自动加群
+    QuestionAnswer { question: String, answer: String },
+}
+
+impl GroupJoinMode {
+    /// 0x88d 的 groupOption / 0x89a 的 addOption
+    pub fn from_option(option: u32, question: String, answer: String) -> Option<Self> {
+        Some(match option {
+            1 => Self::Anyone,
+            2 => Self::NeedApproval,
+            3 => Self::Forbidden,
+            4 => Self::QuestionAnswer { question, answer },
+            5 => Self::Question { question },
+            _ => return None,
+        })
+    }
+
+    pub fn option(&self) -> u32 {
+        match self {
+            Self::Anyone => 1,
+            Self::NeedApproval => 2,
+            Self::Forbidden => 3,
+            Self::QuestionAnswer { .. } => 4,
+            Self::Question { .. } => 5,
+        }
+    }
 }
 
 #[derive(Debug, Default, Clone)]
@@ -81,7 +219,7 @@ pub struct GroupMemberInfo {
     pub permission: GroupMemberPermission,
 }
 
-#[derive(Debug, Clone, derivative::Derivative)]
+#[derive(Debug, Clone, PartialEq, Eq, derivative::Derivative, Serialize, Deserialize)]
 #[derivative(Default)]
 pub enum GroupMemberPermission {
     Owner = 1,
@@ -91,7 +229,7 @@ pub enum GroupMemberPermission {
 }
 
 /// 好友信息
-#[derive(Debug, Default, Clone)]
+#[derive(Debug, Default, Clone, Serialize, Deserialize)]
 pub struct FriendInfo {
     pub uin: i64,
     pub nick: String,
@@ -121,11 +259,37 @@ pub struct SummaryCardInfo {
     pub sign: String,
     pub mobile: String,
     pub login_days: i64,
+    /// QQ 号别名，没有设置时为空
+    pub qid: String,
     /// 用于点赞
     pub cookie: Bytes,
 }
 
-#[derive(Debug, Clone, Default)]
+/// 陌生人信息，非好友也可以查询
+#[derive(Debug, Default, Clone)]
+pub struct StrangerInfo {
+    pub uin: i64,
+    pub nickname: String,
+    pub sex: u8,
+    pub age: u8,
+    pub level: i32,
+    pub qid: String,
+}
+
+impl From<SummaryCardInfo> for StrangerInfo {
+    fn from(info: SummaryCardInfo) -> Self {
+        Self {
+            uin: info.uin,
+            nickname: info.nickname,
+            sex: info.sex,
+            age: info.age,
+            level: info.level,
+            qid: info.qid,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
 pub struct FriendMessage {
     pub seqs: Vec<i32>,
     pub rands: Vec<i32>,
@@ -134,9 +298,15 @@ pub struct FriendMessage {
     pub from_uin: i64,
     pub from_nick: String,
     pub elements: MessageChain,
+    /// 登录前收到、登录后同步拉取的消息
+    #[serde(default)]
+    pub offline: bool,
+    /// 引用回复的原消息，开启 `resolve_quoted_messages` 时填充
+    #[serde(default)]
+    pub quoted: Option<MessageChain>,
 }
 
-#[derive(Debug, Clone, Default)]
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
 pub struct GroupMessage {
     pub seqs: Vec<i32>,
     pub rands: Vec<i32>,
@@ -146,9 +316,12 @@ pub struct GroupMessage {
     pub from_uin: i64,
     pub time: i32,
     pub elements: MessageChain,
+    /// 引用回复的原消息，开启 `resolve_quoted_messages` 时填充
+    #[serde(default)]
+    pub quoted: Option<MessageChain>,
 }
 
-#[derive(Debug, Clone, Default)]
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
 pub struct GroupTempMessage {
     pub seqs: Vec<i32>,
     pub rands: Vec<i32>,
@@ -157,15 +330,126 @@ pub struct GroupTempMessage {
     pub time: i32,
     pub elements: MessageChain,
     pub group_code: i64,
+    /// 路由信息，回复时通过 `TempSession::routing_head` 使用
+    #[serde(default)]
+    pub session: TempSession,
+    /// 登录前收到、登录后同步拉取的消息
+    #[serde(default)]
+    pub offline: bool,
 }
 
-#[derive(Debug, Clone, Default)]
+/// 好友发送的离线文件，通过 `download_friend_file(file_uuid)` 获取下载链接
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct FriendFile {
+    pub sender: i64,
+    pub file_name: String,
+    pub file_size: i64,
+    pub file_uuid: Bytes,
+    pub file_md5: Bytes,
+    /// 过期时间戳，为 0 时未知
+    pub expire_time: i32,
+}
+
+/// 临时会话来源
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+pub enum TempSessionScene {
+    /// 群聊
+    #[default]
+    Group,
+    /// QQ 咨询 (WPA)
+    Consulting,
+    /// 通讯录
+    AddressBook,
+    /// 热聊
+    HotChat,
+    /// 验证消息
+    SystemMessage,
+    /// 约会 / 附近的人
+    Dating,
+    /// 其他来源（查找、QQ 运动等），值为 service_type
+    Other(i32),
+}
+
+impl From<i32> for TempSessionScene {
+    fn from(service_type: i32) -> Self {
+        match service_type {
+            0 => Self::Group,
+            1 => Self::Consulting,
+            130 => Self::AddressBook,
+            132 => Self::HotChat,
+            134 => Self::SystemMessage,
+            201 => Self::Dating,
+            v => Self::Other(v),
+        }
+    }
+}
+
+/// 临时会话路由信息，回复时需要原样带回 sig
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct TempSession {
+    pub scene: TempSessionScene,
+    pub uin: i64,
+    pub c2c_type: i32,
+    pub service_type: i32,
+    pub group_code: i64,
+    pub sig: Bytes,
+    pub from_phone: String,
+    pub to_phone: String,
+}
+
+impl TempSession {
+    pub fn routing_head(&self) -> pb::msg::routing_head::RoutingHead {
+        use pb::msg::routing_head::RoutingHead;
+        match self.scene {
+            TempSessionScene::Group => RoutingHead::GrpTmp(pb::msg::GrpTmp {
+                group_uin: Some(crate::common::group_code2uin(self.group_code)),
+                to_uin: Some(self.uin),
+            }),
+            TempSessionScene::Consulting => RoutingHead::WpaTmp(pb::msg::WpaTmp {
+                to_uin: Some(self.uin as u64),
+                sig: Some(self.sig.to_vec()),
+            }),
+            TempSessionScene::AddressBook => RoutingHead::AddressList(pb::msg::AddressListTmp {
+                // 回复时收发方互换
+                from_phone: Some(self.to_phone.clone()),
+                to_phone: Some(self.from_phone.clone()),
+                to_uin: Some(self.uin as u64),
+                sig: Some(self.sig.to_vec()),
+                from_contact_size: None,
+            }),
+            _ => RoutingHead::CommTmp(pb::msg::CommTmp {
+                to_uin: Some(self.uin as u64),
+                c2c_type: Some(self.c2c_type as u32),
+                svr_type: Some(self.service_type as u32),
+                sig: Some(self.sig.to_vec()),
+                reserved: None,
+            }),
+        }
+    }
+}
+
+/// 非群聊来源的临时会话消息
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct TempMessage {
+    pub seqs: Vec<i32>,
+    pub rands: Vec<i32>,
+    pub from_uin: i64,
+    pub from_nick: String,
+    pub time: i32,
+    pub elements: MessageChain,
+    pub session: TempSession,
+    /// 登录前收到、登录后同步拉取的消息
+    #[serde(default)]
+    pub offline: bool,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
 pub struct NewMember {
     pub group_code: i64,
     pub member_uin: i64,
 }
 
-#[derive(Debug, Clone, Default)]
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
 pub struct GroupMute {
     pub group_code: i64,
     pub operator_uin: i64,
@@ -173,79 +457,342 @@ pub struct GroupMute {
     pub duration: Duration,
 }
 
-#[derive(Debug, Clone, Default)]
+/// 机器人自己被禁言，duration 为 0 时表示解除禁言
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct BotMute {
+    pub group_code: i64,
+    pub operator_uin: i64,
+    pub duration: Duration,
+}
+
+impl BotMute {
+    pub fn is_unmute(&self) -> bool {
+        self.duration.is_zero()
+    }
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
 pub struct FriendMessageRecall {
     pub msg_seq: i32,
     pub friend_uin: i64,
     pub time: i64,
+    /// 被撤回的原消息，设置 MessageStore 且存储中有这条消息时填充
+    #[serde(default)]
+    pub message: Option<FriendMessage>,
 }
 
-#[derive(Debug, Clone, Default)]
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
 pub struct GroupMessageRecall {
     pub msg_seq: i32,
     pub group_code: i64,
     pub operator_uin: i64,
     pub author_uin: i64,
     pub time: i32,
+    /// 被撤回的原消息，设置 MessageStore 且存储中有这条消息时填充
+    #[serde(default)]
+    pub message: Option<GroupMessage>,
 }
 
-#[derive(Debug, Clone, Default)]
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
 pub struct GroupLeave {
     pub group_code: i64,
     pub member_uin: i64,
     pub operator_uin: Option<i64>,
 }
 
-#[derive(Debug, Clone, Default)]
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
 pub struct FriendPoke {
     pub sender: i64,
     pub receiver: i64,
+    /// 动作文本，如 "拍了拍"
+    #[serde(default)]
+    pub action: String,
+    /// 自定义后缀，如 "的脑袋"
+    #[serde(default)]
+    pub suffix: String,
+    /// 灰条模板 id，用于区分特殊戳一戳
+    #[serde(default)]
+    pub item_id: u64,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct GroupPoke {
+    pub group_code: i64,
+    pub sender: i64,
+    pub receiver: i64,
 }
 
-#[derive(Debug, Clone, Default)]
-pub struct GroupPoke {
+/// 群礼物灰条，templ_params 保留原始模板参数
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct GroupGift {
     pub group_code: i64,
     pub sender: i64,
     pub receiver: i64,
+    pub gift_name: String,
+    pub gift_count: i32,
+    pub templ_params: Vec<(String, String)>,
+}
+
+/// 服务器通知类型
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+pub enum ServerNoticeKind {
+    /// 昵称 / 群名片被系统重置
+    NicknameReset,
+    /// 账号安全提醒
+    SecurityWarning,
+    /// 服务器地址变更，建议重新连接
+    ServerChange,
+    #[default]
+    Other,
+}
+
+/// 系统灰条、ConfigPush 等服务器通知
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct ServerNotice {
+    pub kind: ServerNoticeKind,
+    /// 群内通知时为群号，否则为 0
+    pub group_code: i64,
+    /// 替换模板参数后的通知内容
+    pub content: String,
+    /// 灰条模板 id，其他通知为 0
+    pub templ_id: u64,
 }
 
-#[derive(Debug, Clone, Default)]
+/// 未识别的群灰条，保留原始模板参数，由使用者按 templ_id 自行解析
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct RawGrayTip {
+    pub group_code: i64,
+    pub templ_id: u64,
+    pub busi_type: u64,
+    pub busi_id: u64,
+    /// 未替换模板参数的原始内容
+    pub content: String,
+    pub templ_params: Vec<(String, String)>,
+}
+
+impl RawGrayTip {
+    /// 按名称查找模板参数
+    pub fn param(&self, name: &str) -> Option<&str> {
+        self.templ_params
+            .iter()
+            .find(|(n, _)| n == name)
+            .map(|(_, v)| v.as_str())
+    }
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
 pub struct GroupNameUpdate {
     pub group_code: i64,
     pub operator_uin: i64,
     pub group_name: String,
 }
 
-#[derive(Debug, Clone, Default)]
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
 pub struct DeleteFriend {
     pub uin: i64,
 }
 
-#[derive(Debug, Clone, Default)]
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
 pub struct MemberPermissionChange {
     pub group_code: i64,
     pub member_uin: i64,
     pub new_permission: GroupMemberPermission,
 }
 
-#[derive(Debug, Clone, Default)]
+/// 群成员信息变化，由前后两次拉取的成员列表比对得出
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct MemberInfoChange {
+    pub group_code: i64,
+    pub member_uin: i64,
+    pub change: MemberChange,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub enum MemberChange {
+    CardName {
+        old: String,
+        new: String,
+    },
+    SpecialTitle {
+        old: String,
+        new: String,
+    },
+    Permission {
+        old: GroupMemberPermission,
+        new: GroupMemberPermission,
+    },
+    Join,
+    /// 退群或被踢，没有收到推送时也能发现
+    Leave,
+}
+
+impl MemberInfoChange {
+    /// 比对同一个群前后两次的成员列表
+    pub fn diff(old: &[GroupMemberInfo], new: &[GroupMemberInfo]) -> Vec<Self> {
+        let old_members: HashMap<i64, &GroupMemberInfo> = old.iter().map(|m| (m.uin, m)).collect();
+        let new_uins: HashSet<i64> = new.iter().map(|m| m.uin).collect();
+        let mut changes = Vec::new();
+        let mut push = |m: &GroupMemberInfo, change| {
+            changes.push(Self {
+                group_code: m.group_code,
+                member_uin: m.uin,
+                change,
+            })
+        };
+        for m in new {
+            let o = match old_members.get(&m.uin) {
+                Some(o) => o,
+                None => {
+                    push(m, MemberChange::Join);
+                    continue;
+                }
+            };
+            if o.card_name != m.card_name {
+                push(
+                    m,
+                    MemberChange::CardName {
+                        old: o.card_name.clone(),
+                        new: m.card_name.clone(),
+                    },
+                );
+            }
+            if o.special_title != m.special_title {
+                push(
+                    m,
+                    MemberChange::SpecialTitle {
+                        old: o.special_title.clone(),
+                        new: m.special_title.clone(),
+                    },
+                );
+            }
+            if o.permission != m.permission {
+                push(
+                    m,
+                    MemberChange::Permission {
+                        old: o.permission.clone(),
+                        new: m.permission.clone(),
+                    },
+                );
+            }
+        }
+        for o in old.iter().filter(|o| !new_uins.contains(&o.uin)) {
+            push(o, MemberChange::Leave);
+        }
+        changes
+    }
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
 pub struct GroupDisband {
     pub group_code: i64,
     pub operator_uin: i64,
 }
 
-// 用于撤回
-#[derive(Debug, Clone, Default)]
+/// 消息类型
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum MessageKind {
+    Friend,
+    Group,
+    Temp,
+    Guild,
+}
+
+/// 消息发送的目标
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum MessageTarget {
+    Friend(i64),
+    Group(i64),
+    /// 临时会话，不是群临时会话时 group_code 为 0
+    Temp {
+        group_code: i64,
+        uin: i64,
+    },
+    Guild {
+        guild_id: u64,
+        channel_id: u64,
+    },
+}
+
+impl Default for MessageTarget {
+    fn default() -> Self {
+        Self::Friend(0)
+    }
+}
+
+impl MessageTarget {
+    pub fn kind(&self) -> MessageKind {
+        match self {
+            Self::Friend(_) => MessageKind::Friend,
+            Self::Group(_) => MessageKind::Group,
+            Self::Temp { .. } => MessageKind::Temp,
+            Self::Guild { .. } => MessageKind::Guild,
+        }
+    }
+
+    pub fn from_routing_head(head: &pb::msg::routing_head::RoutingHead) -> Self {
+        use pb::msg::routing_head::RoutingHead;
+        let temp = |uin: Option<u64>| Self::Temp {
+            group_code: 0,
+            uin: uin.unwrap_or_default() as i64,
+        };
+        match head {
+            RoutingHead::C2c(c2c) => Self::Friend(c2c.to_uin.unwrap_or_default()),
+            RoutingHead::Grp(grp) => Self::Group(grp.group_code.unwrap_or_default()),
+            RoutingHead::GrpTmp(tmp) => Self::Temp {
+                group_code: crate::common::group_uin2code(tmp.group_uin.unwrap_or_default()),
+                uin: tmp.to_uin.unwrap_or_default(),
+            },
+            RoutingHead::WpaTmp(tmp) => temp(tmp.to_uin),
+            RoutingHead::AddressList(tmp) => temp(tmp.to_uin),
+            RoutingHead::CommTmp(tmp) => temp(tmp.to_uin),
+        }
+    }
+}
+
+/// 发送消息的回执，用于撤回、引用回复、设为精华，长消息分片时 seqs/rands 有多个
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
 pub struct MessageReceipt {
+    pub target: MessageTarget,
+    /// 服务器分配的 seq，群消息未收到回执时为 0
     pub seqs: Vec<i32>,
     pub rands: Vec<i32>,
     pub time: i64,
 }
 
-#[derive(Debug, Clone, Default)]
-pub struct GroupAudio(pub pb::msg::Ptt);
+impl MessageReceipt {
+    pub fn kind(&self) -> MessageKind {
+        self.target.kind()
+    }
 
-#[derive(Debug, Clone, Default)]
+    /// 第一个分片的 seq，引用回复时使用
+    pub fn seq(&self) -> i32 {
+        self.seqs.first().copied().unwrap_or_default()
+    }
+
+    pub fn rand(&self) -> i32 {
+        self.rands.first().copied().unwrap_or_default()
+    }
+
+    /// 是否已经拿到服务器分配的 seq
+    pub fn is_seq_resolved(&self) -> bool {
+        !self.seqs.is_empty() && self.seqs.iter().all(|seq| *seq != 0)
+    }
+
+    /// 引用这条消息，sender 为自己，elements 为发送的消息内容
+    pub fn reply(&self, sender: i64, elements: MessageChain) -> crate::msg::elem::Reply {
+        crate::msg::elem::Reply {
+            reply_seq: self.seq(),
+            sender,
+            time: self.time as i32,
+            elements,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct GroupAudio(#[serde(with = "ptt_bytes")] pub pb::msg::Ptt);
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
 pub struct GroupAudioMessage {
     pub seqs: Vec<i32>,
     pub rands: Vec<i32>,
@@ -257,10 +804,10 @@ pub struct GroupAudioMessage {
     pub audio: GroupAudio,
 }
 
-#[derive(Debug, Clone, Default)]
-pub struct FriendAudio(pub pb::msg::Ptt);
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct FriendAudio(#[serde(with = "ptt_bytes")] pub pb::msg::Ptt);
 
-#[derive(Debug, Clone, Default)]
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
 pub struct FriendAudioMessage {
     pub seqs: Vec<i32>,
     pub rands: Vec<i32>,
@@ -270,6 +817,23 @@ pub struct FriendAudioMessage {
     pub from_nick: String,
     pub audio: FriendAudio,
 }
+
+// 语音序列化为 protobuf 字节
+mod ptt_bytes {
+    use prost::Message;
+    use serde::{Deserialize, Deserializer, Serializer};
+
+    use crate::pb::msg::Ptt;
+
+    pub fn serialize<S: Serializer>(ptt: &Ptt, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_bytes(&ptt.encode_to_vec())
+    }
+
+    pub fn deserialize<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Ptt, D::Error> {
+        let buf: Vec<u8> = Deserialize::deserialize(deserializer)?;
+        Ptt::decode(buf.as_slice()).map_err(serde::de::Error::custom)
+    }
+}
 // 群文件总数
 #[derive(Debug, Clone, Default)]
 pub struct GroupFileCount {
@@ -328,3 +892,73 @@ pub struct GroupFileInfo {
     pub uploader_uin: u64,
     pub parent_folder_id: String,
 }
+
+/// 推送包解析失败，payload 为原始数据，可用于排查或上报
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct PacketDecodeError {
+    pub command: String,
+    pub reason: String,
+    pub payload: Bytes,
+}
+
+/// 没有对应处理的推送包，可用于实现尚未支持的推送
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct RawPacket {
+    pub command: String,
+    pub seq: i32,
+    pub body: Bytes,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_group_settings_flags() {
+        let settings = GroupSettings {
+            allow_member_invite: true,
+            allow_temp_chat: false,
+            auto_approve: true,
+            confess_talk: false,
+            searchable: true,
+        };
+        let (flag_ext3, mask) = settings.flag_ext3();
+        assert_eq!(flag_ext3 & !mask, 0);
+        assert_eq!(
+            GroupSettings::from_flags(GROUP_FLAG_EXT_MEMBER_INVITE, flag_ext3, 0),
+            settings
+        );
+    }
+
+    #[test]
+    fn test_member_info_diff() {
+        let member = |uin, card: &str| GroupMemberInfo {
+            group_code: 1,
+            uin,
+            card_name: card.into(),
+            ..Default::default()
+        };
+        let old = vec![member(10, "a"), member(11, "b")];
+        let mut new = vec![member(10, "c"), member(12, "d")];
+        new[1].permission = GroupMemberPermission::Administrator;
+
+        let changes: Vec<(i64, MemberChange)> = MemberInfoChange::diff(&old, &new)
+            .into_iter()
+            .map(|c| (c.member_uin, c.change))
+            .collect();
+        assert_eq!(
+            changes,
+            vec![
+                (
+                    10,
+                    MemberChange::CardName {
+                        old: "a".into(),
+                        new: "c".into()
+                    }
+                ),
+                (12, MemberChange::Join),
+                (11, MemberChange::Leave),
+            ]
+        );
+    }
+}
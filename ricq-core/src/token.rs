@@ -12,4 +12,19 @@ pub struct Token {
     pub out_packet_session_id: Vec<u8>,
     pub tgtgt_key: Vec<u8>,
     pub wt_session_ticket_key: Vec<u8>, // oicq
+    /// 消息同步进度，旧版本 token 没有这两个字段
+    #[serde(default)]
+    pub sync_cookie: Vec<u8>,
+    #[serde(default)]
+    pub pub_account_cookie: Vec<u8>,
+}
+
+/// 快速登录凭证，登录成功后保存，之后可以不用密码换取新的登录凭证
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct A1Token {
+    pub uin: i64,
+    pub encrypted_a1: Vec<u8>,
+    pub no_pic_sig: Vec<u8>,
+    /// 解密换取结果需要和签发 A1 时相同的 tgtgt_key
+    pub tgtgt_key: Vec<u8>,
 }
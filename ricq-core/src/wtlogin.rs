@@ -1,4 +1,5 @@
 use std::sync::atomic::Ordering;
+use std::time::{Duration, UNIX_EPOCH};
 
 use bytes::{BufMut, Bytes, BytesMut};
 
@@ -16,6 +17,47 @@ impl super::Engine {
         self.uin.store(resp.uin, Ordering::Relaxed);
     }
 
+    /// skey 是否已过期，pskey 随 skey 一同刷新
+    pub fn is_s_key_expired(&self) -> bool {
+        let now = UNIX_EPOCH.elapsed().unwrap().as_secs() as i64;
+        self.transport.sig.s_key.is_empty() || now >= self.transport.sig.s_key_expired_time
+    }
+
+    /// skey 剩余有效时间，已过期时为 0
+    pub fn s_key_expires_in(&self) -> Duration {
+        if self.transport.sig.s_key.is_empty() {
+            return Duration::ZERO;
+        }
+        let now = UNIX_EPOCH.elapsed().unwrap().as_secs() as i64;
+        Duration::from_secs((self.transport.sig.s_key_expired_time - now).max(0) as u64)
+    }
+
+    /// 生成指定域名的 cookie，没有该域名的 pskey 时返回 None
+    pub fn cookies(&self, domain: &str) -> Option<String> {
+        let sig = &self.transport.sig;
+        let ps_key = sig.ps_key_map.get(domain)?;
+        Some(format!(
+            "uin=o{uin}; skey={}; p_uin=o{uin}; p_skey={};",
+            String::from_utf8_lossy(&sig.s_key),
+            String::from_utf8_lossy(ps_key),
+            uin = self.uin(),
+        ))
+    }
+
+    /// 网页接口使用的 bkn (csrf token)，由 skey 计算
+    pub fn bkn(&self) -> i64 {
+        csrf_token(&self.transport.sig.s_key)
+    }
+
+    /// qzone 等网页接口使用的 g_tk，由对应域名的 pskey 计算
+    pub fn g_tk(&self, domain: &str) -> Option<i64> {
+        self.transport
+            .sig
+            .ps_key_map
+            .get(domain)
+            .map(|ps_key| csrf_token(ps_key))
+    }
+
     pub fn process_login_response(&mut self, login_response: &LoginResponse) {
         match login_response {
             LoginResponse::Success(resp) => self.process_login_success(resp.clone()),
@@ -92,3 +134,11 @@ fn set_t402(transport: &mut Transport, t402: Bytes) {
     v.put_slice(&transport.sig.t402);
     transport.sig.g = Bytes::from(md5::compute(&v).to_vec())
 }
+
+fn csrf_token(key: &[u8]) -> i64 {
+    let mut hash: i64 = 5381;
+    for c in key.iter() {
+        hash = (hash + (hash << 5) + *c as i64) & 0x7fffffff;
+    }
+    hash
+}
@@ -1,7 +1,9 @@
-use crate::protocol::protobuf;
+use crate::protocol::{protobuf, ChannelSettings, GuildImage};
+use bytes::Bytes;
 use dynamic_protobuf::{dynamic_message, DynamicMessage};
 use rand::Rng;
 use ricq_core::command::common::PbToBytes;
+use ricq_core::hex::encode_hex;
 use ricq_core::protocol::packet::Packet;
 use std::sync::atomic::Ordering;
 
@@ -100,6 +102,202 @@ impl<'a> super::Engine<'a> {
         )
     }
 
+    /// category_id 为 0 时不放入分组
+    pub fn build_create_channel_packet(
+        &self,
+        guild_id: u64,
+        name: String,
+        channel_type: u32,
+        category_id: u64,
+    ) -> Packet {
+        let payload = {
+            let msg = dynamic_message! {
+                1 => guild_id,
+                2 => dynamic_message! {
+                    2 => Bytes::from(name),
+                    7 => channel_type,
+                },
+                3 => category_id,
+            };
+
+            self.transport.encode_oidb_packet(0xf51, 1, msg.encode())
+        };
+
+        self.uni_packet("OidbSvcTrpcTcp.0xf51_1", payload)
+    }
+
+    pub fn build_delete_channel_packet(&self, guild_id: u64, channel_id: u64) -> Packet {
+        let payload = {
+            let msg = dynamic_message! {
+                1 => guild_id,
+                2 => channel_id,
+            };
+
+            self.transport.encode_oidb_packet(0xf52, 1, msg.encode())
+        };
+
+        self.uni_packet("OidbSvcTrpcTcp.0xf52_1", payload)
+    }
+
+    pub fn build_modify_channel_packet(
+        &self,
+        guild_id: u64,
+        channel_id: u64,
+        settings: &ChannelSettings,
+    ) -> Packet {
+        // filter 中置 1 的字段才会被修改
+        let mut filter = DynamicMessage::new();
+        let mut info = DynamicMessage::new();
+        info.set(1, channel_id);
+        info.set(5, guild_id);
+        if let Some(name) = &settings.name {
+            filter.set(2, 1u32);
+            info.set(2, Bytes::from(name.clone()));
+        }
+        if let Some(permission) = settings.talk_permission {
+            filter.set(8, 1u32);
+            info.set(8, permission as u32);
+        }
+        if let Some(topic) = &settings.topic {
+            filter.set(17, 1u32);
+            info.set(17, Bytes::from(topic.clone()));
+        }
+        if let Some(key) = settings.slow_mode_key {
+            filter.set(31, 1u32);
+            info.set(31, key as u32);
+        }
+
+        let payload = {
+            let msg = dynamic_message! {
+                1 => guild_id,
+                2 => channel_id,
+                3 => filter,
+                4 => info,
+            };
+
+            self.transport.encode_oidb_packet(0xf53, 1, msg.encode())
+        };
+
+        self.uni_packet("OidbSvcTrpcTcp.0xf53_1", payload)
+    }
+
+    pub fn build_get_channel_feeds_packet(
+        &self,
+        guild_id: u64,
+        channel_id: u64,
+        count: u32,
+        attach_info: String,
+    ) -> Packet {
+        let req = protobuf::StGetChannelFeedsReq {
+            ext_info: None,
+            count: Some(count),
+            from: Some(0),
+            channel_sign: Some(protobuf::StChannelSign {
+                guild_id: Some(guild_id),
+                channel_id: Some(channel_id),
+            }),
+            feed_attch_info: Some(attach_info),
+        };
+        self.uni_packet(
+            "QChannelSvr.trpc.qchannel.commreader.ComReader.GetChannelTimelineFeeds",
+            req.to_bytes(),
+        )
+    }
+
+    /// images 为已上传到频道的图片
+    pub fn build_publish_feed_packet(
+        &self,
+        guild_id: u64,
+        channel_id: u64,
+        title: String,
+        content: String,
+        images: &[GuildImage],
+    ) -> Packet {
+        let text = |text: String| protobuf::StRichText {
+            contents: vec![protobuf::StRichTextContent {
+                r#type: Some(1),
+                text_content: Some(protobuf::StRichTextTextContent { text: Some(text) }),
+                ..Default::default()
+            }],
+        };
+        let feed = protobuf::StFeed {
+            title: Some(text(title)),
+            contents: Some(text(content)),
+            images: images
+                .iter()
+                .enumerate()
+                .map(|(i, image)| protobuf::StImage {
+                    width: Some(image.width),
+                    height: Some(image.height),
+                    pic_url: Some(image.url()),
+                    pic_id: Some(image.file_id.to_string()),
+                    image_md5: Some(encode_hex(&image.md5)),
+                    display_index: Some(i as u32),
+                    ..Default::default()
+                })
+                .collect(),
+            channel_info: Some(protobuf::StChannelInfo {
+                sign: Some(protobuf::StChannelSign {
+                    guild_id: Some(guild_id),
+                    channel_id: Some(channel_id),
+                }),
+                name: None,
+                icon_url: None,
+            }),
+            ..Default::default()
+        };
+        let client_content = protobuf::StClientContent {
+            client_image_contents: images
+                .iter()
+                .map(|image| protobuf::StClientImageContent {
+                    task_id: None,
+                    pic_id: Some(image.file_id.to_string()),
+                    url: Some(image.url()),
+                })
+                .collect(),
+            client_video_contents: vec![],
+        };
+        let req = protobuf::StPublishFeedReq {
+            feed: Some(feed),
+            from: Some(0),
+            client_content: Some(client_content),
+            ..Default::default()
+        };
+        self.uni_packet(
+            "QChannelSvr.trpc.qchannel.commwriter.ComWriter.PublishFeed",
+            req.to_bytes(),
+        )
+    }
+
+    pub fn build_delete_feed_packet(
+        &self,
+        guild_id: u64,
+        channel_id: u64,
+        feed_id: String,
+    ) -> Packet {
+        let req = protobuf::StDelFeedReq {
+            ext_info: None,
+            feed: Some(protobuf::StFeed {
+                id: Some(feed_id),
+                channel_info: Some(protobuf::StChannelInfo {
+                    sign: Some(protobuf::StChannelSign {
+                        guild_id: Some(guild_id),
+                        channel_id: Some(channel_id),
+                    }),
+                    name: None,
+                    icon_url: None,
+                }),
+                ..Default::default()
+            }),
+            from: Some(0),
+            src: None,
+        };
+        self.uni_packet(
+            "QChannelSvr.trpc.qchannel.commwriter.ComWriter.DoDelFeed",
+            req.to_bytes(),
+        )
+    }
+
     #[allow(clippy::too_many_arguments)]
     pub fn build_guild_image_store_packet(
         &self,
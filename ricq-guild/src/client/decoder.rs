@@ -2,10 +2,14 @@ use bytes::Bytes;
 use ricq_core::{RQError, RQResult};
 
 use crate::protocol::protobuf::{self, FirstViewMsg, GuildUserProfile};
-use crate::protocol::{FirstViewResponse, GuildImageStoreResp};
+use crate::protocol::{
+    ChannelCreated, ChannelDestroyed, ChannelUpdated, FirstViewResponse, GuildChannel, GuildEvent,
+    GuildFeed, GuildImageStoreResp,
+};
 use crate::ricq_core::pb;
 use prost::Message;
 use ricq_core::common::RQAddr;
+use ricq_core::structs::{MessageReceipt, MessageTarget};
 
 pub struct Decoder;
 
@@ -53,6 +57,143 @@ impl Decoder {
         Ok(oidb.profile)
     }
 
+    fn decode_oidb_body(&self, payload: Bytes) -> RQResult<Vec<u8>> {
+        let pkg = pb::oidb::OidbssoPkg::decode(&*payload)?;
+        if pkg.result != 0 {
+            return Err(RQError::Other(format!(
+                "oidb 0x{:x} failed: {}, {}",
+                pkg.command, pkg.result, pkg.error_msg
+            )));
+        }
+        Ok(pkg.bodybuffer)
+    }
+
+    pub fn decode_create_channel_response(&self, payload: Bytes) -> RQResult<GuildChannel> {
+        let body = self.decode_oidb_body(payload)?;
+        let rsp = protobuf::ChannelOidb0xf51Rsp::decode(&*body)?;
+        let info = rsp.info.ok_or(RQError::EmptyField("info"))?;
+        Ok(GuildChannel::from(info))
+    }
+
+    pub fn decode_delete_channel_response(&self, payload: Bytes) -> RQResult<()> {
+        self.decode_oidb_body(payload)?;
+        Ok(())
+    }
+
+    pub fn decode_modify_channel_response(&self, payload: Bytes) -> RQResult<()> {
+        self.decode_oidb_body(payload)?;
+        Ok(())
+    }
+
+    // MsgProxy.SendMsg
+    pub fn decode_send_channel_message_response(
+        &self,
+        payload: Bytes,
+        guild_id: u64,
+        channel_id: u64,
+    ) -> RQResult<MessageReceipt> {
+        let rsp = protobuf::Df62RspBody::decode(&*payload)?;
+        if rsp.result() != 0 {
+            return Err(RQError::Other(format!(
+                "send channel message failed: {} {}",
+                rsp.result(),
+                String::from_utf8_lossy(rsp.errmsg())
+            )));
+        }
+        let content = rsp
+            .head
+            .and_then(|h| h.content_head)
+            .ok_or(RQError::EmptyField("content_head"))?;
+        Ok(MessageReceipt {
+            target: MessageTarget::Guild {
+                guild_id,
+                channel_id,
+            },
+            seqs: vec![content.seq() as i32],
+            rands: vec![content.random() as i32],
+            time: rsp.send_time() as i64,
+        })
+    }
+
+    // MsgPush.PushGroupProMsg
+    pub fn decode_guild_push_events(&self, payload: Bytes) -> RQResult<Vec<GuildEvent>> {
+        let push = protobuf::MsgOnlinePush::decode(&*payload)?;
+        let mut events = Vec::new();
+        for msg in push.msgs {
+            let is_event = msg
+                .head
+                .as_ref()
+                .and_then(|h| h.content_head.as_ref())
+                .and_then(|c| c.r#type)
+                == Some(3841);
+            if !is_event {
+                continue;
+            }
+            // 事件放在 service_type 为 500 的 CommonElem 中
+            let common = msg
+                .body
+                .and_then(|b| b.rich_text)
+                .into_iter()
+                .flat_map(|r| r.elems)
+                .find_map(|e| match e.elem {
+                    Some(pb::msg::elem::Elem::CommonElem(c)) if c.service_type == Some(500) => {
+                        Some(c)
+                    }
+                    _ => None,
+                });
+            let body = match common {
+                Some(c) => protobuf::EventBody::decode(&*c.pb_elem.unwrap_or_default())?,
+                None => continue,
+            };
+            if let Some(e) = body.create_chan {
+                events.push(GuildEvent::ChannelCreated(ChannelCreated {
+                    guild_id: e.guild_id.unwrap_or_default(),
+                    operator_id: e.operator_id.unwrap_or_default(),
+                    channel_ids: e.create_id.into_iter().filter_map(|c| c.chan_id).collect(),
+                }));
+            }
+            if let Some(e) = body.destroy_chan {
+                events.push(GuildEvent::ChannelDestroyed(ChannelDestroyed {
+                    guild_id: e.guild_id.unwrap_or_default(),
+                    operator_id: e.operator_id.unwrap_or_default(),
+                    channel_ids: e.delete_id.into_iter().filter_map(|c| c.chan_id).collect(),
+                }));
+            }
+            if let Some(e) = body.change_chan_info {
+                events.push(GuildEvent::ChannelUpdated(ChannelUpdated {
+                    guild_id: e.guild_id.unwrap_or_default(),
+                    channel_id: e.chan_id.unwrap_or_default(),
+                    operator_id: e.operator_id.unwrap_or_default(),
+                    update_type: e.update_type.unwrap_or_default(),
+                    channel: e.chan_info.map(GuildChannel::from),
+                }));
+            }
+        }
+        Ok(events)
+    }
+
+    /// 返回帖子和下一页的 attach_info，已经是最后一页时为 None
+    pub fn decode_get_channel_feeds_response(
+        &self,
+        payload: Bytes,
+    ) -> RQResult<(Vec<GuildFeed>, Option<String>)> {
+        let rsp = protobuf::StGetChannelFeedsRsp::decode(&*payload)?;
+        let feeds = rsp.vec_feed.into_iter().map(GuildFeed::from).collect();
+        let next = match rsp.is_finish {
+            Some(0) | None => rsp.feed_attch_info.filter(|a| !a.is_empty()),
+            _ => None,
+        };
+        Ok((feeds, next))
+    }
+
+    /// 返回帖子 id
+    pub fn decode_publish_feed_response(&self, payload: Bytes) -> RQResult<String> {
+        let rsp = protobuf::StPublishFeedRsp::decode(&*payload)?;
+        rsp.feed
+            .and_then(|f| f.id)
+            .ok_or(RQError::EmptyField("feed.id"))
+    }
+
     pub fn decode_guild_image_store_response(
         &self,
         payload: Bytes,
@@ -1,7 +1,8 @@
+use cached::{Cached, TimedCache};
 use dynamic_protobuf::dynamic_message;
 use std::collections::HashMap;
 use std::ops::Deref;
-use std::sync::Arc;
+use std::sync::{Arc, Mutex};
 
 use tokio::sync::{broadcast, RwLockReadGuard};
 use tokio::task::JoinHandle;
@@ -11,22 +12,30 @@ use ricq_core::highway::BdhInput;
 
 use ricq_core::msg::MessageChain;
 use ricq_core::protocol::packet::Packet;
+use ricq_core::structs::MessageReceipt;
 use ricq_core::{RQError, RQResult};
 
 use crate::client::decoder::Decoder;
 use crate::protocol::protobuf::FirstViewMsg;
 use crate::protocol::{
-    protobuf, FirstView, FirstViewMessage, GuildImage, GuildImageStoreResp, GuildSelfProfile,
+    protobuf, ChannelSettings, ChannelType, FirstView, FirstViewMessage, GuildChannel, GuildFeed,
+    GuildImage, GuildImageStoreResp, GuildSelfProfile, TalkPermission,
 };
 
 pub mod builder;
 pub mod decoder;
 pub mod processor;
 
+const FEED_PAGE_SIZE: u32 = 20;
+/// 已上传图片的缓存时间（秒），过期后重新向服务器查询
+const IMAGE_CACHE_LIFESPAN: u64 = 60 * 60;
+
 #[allow(dead_code)]
 pub struct GuildClient {
     rq_client: Arc<ricq::Client>,
     listeners: HashMap<&'static str, broadcast::Receiver<Packet>>,
+    /// 已上传的图片，key 为 (guild_id, md5)
+    images: Mutex<TimedCache<(u64, Vec<u8>), GuildImage>>,
 }
 
 impl GuildClient {
@@ -38,6 +47,7 @@ impl GuildClient {
         Self {
             rq_client,
             listeners,
+            images: Mutex::new(TimedCache::with_lifespan(IMAGE_CACHE_LIFESPAN)),
         }
     }
 
@@ -147,18 +157,165 @@ impl GuildClient {
         elems: MessageChain,
         guild_id: u64,
         channel_id: u64,
-    ) -> RQResult<Packet> {
+    ) -> RQResult<MessageReceipt> {
         let pkt = self.engine().await.build_send_channel_message_packet(
             elems.into(),
             guild_id,
             channel_id,
         );
 
-        let ret = self.rq_client.send_and_wait(pkt).await?;
+        let rsp = self.rq_client.send_and_wait(pkt).await?;
+        Decoder.decode_send_channel_message_response(rsp.body, guild_id, channel_id)
+    }
+
+    /// 创建子频道，需要管理员权限；category_id 为 0 时不放入分组
+    pub async fn create_channel(
+        &self,
+        guild_id: u64,
+        name: String,
+        channel_type: ChannelType,
+        category_id: u64,
+    ) -> RQResult<GuildChannel> {
+        let pkt = self.engine().await.build_create_channel_packet(
+            guild_id,
+            name,
+            channel_type as u32,
+            category_id,
+        );
+        let rsp = self.rq_client.send_and_wait(pkt).await?;
+        Decoder.decode_create_channel_response(rsp.body)
+    }
+
+    /// 删除子频道，需要管理员权限
+    pub async fn delete_channel(&self, guild_id: u64, channel_id: u64) -> RQResult<()> {
+        let pkt = self
+            .engine()
+            .await
+            .build_delete_channel_packet(guild_id, channel_id);
+        let rsp = self.rq_client.send_and_wait(pkt).await?;
+        Decoder.decode_delete_channel_response(rsp.body)
+    }
+
+    /// 修改子频道设置，需要管理员权限
+    pub async fn modify_channel(
+        &self,
+        guild_id: u64,
+        channel_id: u64,
+        settings: ChannelSettings,
+    ) -> RQResult<()> {
+        let pkt = self
+            .engine()
+            .await
+            .build_modify_channel_packet(guild_id, channel_id, &settings);
+        let rsp = self.rq_client.send_and_wait(pkt).await?;
+        Decoder.decode_modify_channel_response(rsp.body)
+    }
+
+    pub async fn set_channel_name(
+        &self,
+        guild_id: u64,
+        channel_id: u64,
+        name: String,
+    ) -> RQResult<()> {
+        self.modify_channel(guild_id, channel_id, ChannelSettings::new().name(name))
+            .await
+    }
+
+    pub async fn set_channel_topic(
+        &self,
+        guild_id: u64,
+        channel_id: u64,
+        topic: String,
+    ) -> RQResult<()> {
+        self.modify_channel(guild_id, channel_id, ChannelSettings::new().topic(topic))
+            .await
+    }
+
+    /// 设置慢速模式，key 为 0 时关闭
+    pub async fn set_channel_slow_mode(
+        &self,
+        guild_id: u64,
+        channel_id: u64,
+        key: i32,
+    ) -> RQResult<()> {
+        self.modify_channel(guild_id, channel_id, ChannelSettings::new().slow_mode(key))
+            .await
+    }
+
+    pub async fn set_channel_talk_permission(
+        &self,
+        guild_id: u64,
+        channel_id: u64,
+        permission: TalkPermission,
+    ) -> RQResult<()> {
+        self.modify_channel(
+            guild_id,
+            channel_id,
+            ChannelSettings::new().talk_permission(permission),
+        )
+        .await
+    }
+
+    /// 获取帖子频道中的公告，attach_info 为上一页返回的值，第一页传空字符串
+    ///
+    /// 返回公告和下一页的 attach_info，已经是最后一页时为 None
+    pub async fn get_channel_feeds(
+        &self,
+        guild_id: u64,
+        channel_id: u64,
+        attach_info: String,
+    ) -> RQResult<(Vec<GuildFeed>, Option<String>)> {
+        let pkt = self.engine().await.build_get_channel_feeds_packet(
+            guild_id,
+            channel_id,
+            FEED_PAGE_SIZE,
+            attach_info,
+        );
+        let rsp = self.rq_client.send_and_wait(pkt).await?;
+        Decoder.decode_get_channel_feeds_response(rsp.body)
+    }
+
+    /// 发布公告，图片会先上传到频道，返回公告 id
+    pub async fn publish_channel_feed(
+        &self,
+        guild_id: u64,
+        channel_id: u64,
+        title: String,
+        content: String,
+        images: Vec<Vec<u8>>,
+    ) -> RQResult<String> {
+        let mut uploaded = Vec::with_capacity(images.len());
+        for image in images {
+            uploaded.push(
+                self.upload_channel_image(guild_id, channel_id, &image)
+                    .await?,
+            );
+        }
+        let pkt = self
+            .engine()
+            .await
+            .build_publish_feed_packet(guild_id, channel_id, title, content, &uploaded);
+        let rsp = self.rq_client.send_and_wait(pkt).await?;
+        Decoder.decode_publish_feed_response(rsp.body)
+    }
 
-        Ok(ret) // todo: decode receipt
+    pub async fn delete_channel_feed(
+        &self,
+        guild_id: u64,
+        channel_id: u64,
+        feed_id: String,
+    ) -> RQResult<()> {
+        let pkt = self
+            .engine()
+            .await
+            .build_delete_feed_packet(guild_id, channel_id, feed_id);
+        self.rq_client.send_and_wait(pkt).await?;
+        Ok(())
     }
 
+    /// 上传频道图片，返回的 GuildImage 可以通过 `send_channel_message` 发送到同一频道的任意子频道
+    ///
+    /// 同一频道中上传过的图片不会重复上传
     pub async fn upload_channel_image(
         &self,
         guild_id: u64,
@@ -166,6 +323,9 @@ impl GuildClient {
         image: &[u8],
     ) -> RQResult<GuildImage> {
         let info = ImageInfo::try_new(image)?;
+        if let Some(image) = self.cached_image(guild_id, &info.md5) {
+            return Ok(image);
+        }
 
         let image_store = self
             .get_guild_image_store(guild_id, channel_id, image)
@@ -190,8 +350,8 @@ impl GuildClient {
                 mut upload_addrs,
                 download_index,
             } => {
-                let addr = match self.rq_client.highway_addrs.read().await.first() {
-                    Some(addr) => *addr,
+                let addr = match self.rq_client.select_highway_addr().await {
+                    Some(addr) => addr,
                     None => upload_addrs
                         .pop()
                         .ok_or(RQError::EmptyField("upload_addrs"))?,
@@ -235,10 +395,37 @@ impl GuildClient {
             server_ip: server.0,
             server_port: server.1,
         };
+        self.images
+            .lock()
+            .unwrap()
+            .cache_set((guild_id, guild_image.md5.clone()), guild_image.clone());
 
         Ok(guild_image)
     }
 
+    /// 上传图片并发送到子频道
+    pub async fn send_channel_image(
+        &self,
+        guild_id: u64,
+        channel_id: u64,
+        image: &[u8],
+    ) -> RQResult<MessageReceipt> {
+        let image = self
+            .upload_channel_image(guild_id, channel_id, image)
+            .await?;
+        let mut elems = MessageChain::default();
+        elems.push(image);
+        self.send_channel_message(elems, guild_id, channel_id).await
+    }
+
+    fn cached_image(&self, guild_id: u64, md5: &[u8]) -> Option<GuildImage> {
+        self.images
+            .lock()
+            .unwrap()
+            .cache_get(&(guild_id, md5.to_vec()))
+            .cloned()
+    }
+
     pub async fn get_guild_image_store(
         &self,
         guild_id: u64,
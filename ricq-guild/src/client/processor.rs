@@ -1 +1,42 @@
-impl super::GuildClient {}
+use tokio::sync::broadcast::error::RecvError;
+use tokio::sync::mpsc;
+
+use crate::client::decoder::Decoder;
+use crate::protocol::GuildEvent;
+
+const PUSH_COMMAND: &str = "MsgPush.PushGroupProMsg";
+
+impl super::GuildClient {
+    /// 监听频道推送，目前只解析子频道的创建、删除和设置修改
+    ///
+    /// 返回的 Receiver 被 drop 后停止监听
+    pub async fn listen_guild_events(&self) -> mpsc::UnboundedReceiver<GuildEvent> {
+        let mut rx = self.rq_client.listen_command(PUSH_COMMAND).await;
+        let (tx, events) = mpsc::unbounded_channel();
+        tokio::spawn(async move {
+            loop {
+                let pkt = match rx.recv().await {
+                    Ok(pkt) => pkt,
+                    Err(RecvError::Lagged(n)) => {
+                        tracing::warn!("guild push lagged, {} packets dropped", n);
+                        continue;
+                    }
+                    Err(RecvError::Closed) => return,
+                };
+                let decoded = match Decoder.decode_guild_push_events(pkt.body) {
+                    Ok(decoded) => decoded,
+                    Err(err) => {
+                        tracing::warn!("failed to decode guild push: {}", err);
+                        continue;
+                    }
+                };
+                for event in decoded {
+                    if tx.send(event).is_err() {
+                        return;
+                    }
+                }
+            }
+        });
+        events
+    }
+}
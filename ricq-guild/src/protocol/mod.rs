@@ -2,6 +2,7 @@ use crate::protocol::protobuf::{ChannelMsg, GuildNode};
 use bytes::Bytes;
 use dynamic_protobuf::{dynamic_message, DynamicMessage};
 use ricq_core::common::RQAddr;
+use ricq_core::hex::encode_hex;
 use ricq_core::msg::{MessageChainBuilder, MessageElem};
 
 #[derive(Clone, Debug, Default)]
@@ -42,6 +43,147 @@ pub struct GuildSelfProfile {
     pub avatar_url: String,
 }
 
+/// 子频道类型
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[repr(u32)]
+pub enum ChannelType {
+    Text = 1,
+    Voice = 2,
+    Live = 5,
+    App = 6,
+    Forum = 7,
+}
+
+#[derive(Clone, Debug, Default)]
+pub struct GuildChannel {
+    pub guild_id: u64,
+    pub channel_id: u64,
+    pub name: String,
+    pub channel_type: i32,
+    pub creator_tiny_id: u64,
+    pub create_time: i64,
+    pub talk_permission: i32,
+    /// 慢速模式，0 为关闭
+    pub slow_mode_key: i32,
+}
+
+impl From<protobuf::GuildChannelInfo> for GuildChannel {
+    fn from(info: protobuf::GuildChannelInfo) -> Self {
+        Self {
+            guild_id: info.guild_id.unwrap_or_default(),
+            channel_id: info.channel_id.unwrap_or_default(),
+            name: info.channel_name.unwrap_or_default(),
+            channel_type: info.channel_type.unwrap_or_default(),
+            creator_tiny_id: info.creator_tiny_id.unwrap_or_default(),
+            create_time: info.create_time.unwrap_or_default(),
+            talk_permission: info.talk_permission.unwrap_or_default(),
+            slow_mode_key: info.current_slow_mode_key.unwrap_or_default(),
+        }
+    }
+}
+
+impl From<protobuf::ServChannelInfo> for GuildChannel {
+    fn from(info: protobuf::ServChannelInfo) -> Self {
+        Self {
+            guild_id: info.guild_id.unwrap_or_default(),
+            channel_id: info.channel_id.unwrap_or_default(),
+            name: String::from_utf8_lossy(&info.channel_name.unwrap_or_default()).into_owned(),
+            channel_type: info.channel_type.unwrap_or_default() as i32,
+            creator_tiny_id: 0,
+            create_time: info.create_time.unwrap_or_default() as i64,
+            talk_permission: info.speak_permission.unwrap_or_default() as i32,
+            slow_mode_key: 0,
+        }
+    }
+}
+
+/// 子频道发言权限
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[repr(u32)]
+pub enum TalkPermission {
+    /// 所有人可发言
+    Everyone = 1,
+    /// 仅管理员和指定成员可发言
+    Specified = 2,
+}
+
+/// 修改子频道设置，只有设置过的字段会被修改
+///
+/// ```rust
+/// use ricq_guild::protocol::{ChannelSettings, TalkPermission};
+/// let settings = ChannelSettings::new()
+///     .name("公告")
+///     .talk_permission(TalkPermission::Specified);
+/// ```
+#[derive(Clone, Debug, Default)]
+pub struct ChannelSettings {
+    pub name: Option<String>,
+    pub topic: Option<String>,
+    pub slow_mode_key: Option<i32>,
+    pub talk_permission: Option<TalkPermission>,
+}
+
+impl ChannelSettings {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn name(mut self, name: impl Into<String>) -> Self {
+        self.name = Some(name.into());
+        self
+    }
+
+    /// 子频道简介
+    pub fn topic(mut self, topic: impl Into<String>) -> Self {
+        self.topic = Some(topic.into());
+        self
+    }
+
+    /// 慢速模式，0 为关闭，其他值对应服务器下发的发言间隔档位
+    pub fn slow_mode(mut self, key: i32) -> Self {
+        self.slow_mode_key = Some(key);
+        self
+    }
+
+    pub fn talk_permission(mut self, permission: TalkPermission) -> Self {
+        self.talk_permission = Some(permission);
+        self
+    }
+}
+
+/// 频道推送中解析出的事件
+#[derive(Clone, Debug)]
+pub enum GuildEvent {
+    ChannelCreated(ChannelCreated),
+    ChannelDestroyed(ChannelDestroyed),
+    ChannelUpdated(ChannelUpdated),
+}
+
+#[derive(Clone, Debug, Default)]
+pub struct ChannelCreated {
+    pub guild_id: u64,
+    pub operator_id: u64,
+    pub channel_ids: Vec<u64>,
+}
+
+#[derive(Clone, Debug, Default)]
+pub struct ChannelDestroyed {
+    pub guild_id: u64,
+    pub operator_id: u64,
+    pub channel_ids: Vec<u64>,
+}
+
+/// 子频道设置被修改
+#[derive(Clone, Debug, Default)]
+pub struct ChannelUpdated {
+    pub guild_id: u64,
+    pub channel_id: u64,
+    pub operator_id: u64,
+    pub update_type: u32,
+    /// 修改后的子频道信息，只有被修改的字段有效
+    pub channel: Option<GuildChannel>,
+}
+
 #[derive(Clone, Debug, Default)]
 pub struct GuildImage {
     pub file_id: u64,
@@ -56,6 +198,15 @@ pub struct GuildImage {
     pub server_port: u16,
 }
 
+impl GuildImage {
+    pub fn url(&self) -> String {
+        format!(
+            "https://gchat.qpic.cn/qmeetpic/0/0-0-{}/0",
+            encode_hex(&self.md5).to_uppercase()
+        )
+    }
+}
+
 impl ricq_core::msg::PushElem for GuildImage {
     fn push_to(img: Self, vec: &mut Vec<MessageElem>) {
         vec.push(MessageElem::CustomFace(ricq_core::pb::msg::CustomFace {
@@ -96,6 +247,14 @@ impl ricq_core::msg::PushElem for GuildImage {
     }
 }
 
+impl From<GuildImage> for Vec<MessageElem> {
+    fn from(img: GuildImage) -> Self {
+        let mut vec = Vec::new();
+        ricq_core::msg::PushElem::push_to(img, &mut vec);
+        vec
+    }
+}
+
 impl ricq_core::msg::PushBuilder for GuildImage {
     fn push_builder(elem: Self, builder: &mut MessageChainBuilder) {
         ricq_core::msg::PushElem::push_to(elem, &mut builder.elems);
@@ -117,6 +276,63 @@ pub enum GuildImageStoreResp {
     },
 }
 
+/// 帖子频道中的公告（帖子）
+#[derive(Clone, Debug, Default)]
+pub struct GuildFeed {
+    pub id: String,
+    pub guild_id: u64,
+    pub channel_id: u64,
+    /// 发帖人的 tiny_id
+    pub poster_id: String,
+    pub poster_nick: String,
+    pub title: String,
+    pub content: String,
+    /// 图片链接
+    pub images: Vec<String>,
+    pub create_time: u64,
+    pub comment_count: u32,
+}
+
+impl From<protobuf::StFeed> for GuildFeed {
+    fn from(feed: protobuf::StFeed) -> Self {
+        let sign = feed.channel_info.and_then(|c| c.sign).unwrap_or_default();
+        let poster = feed.poster.unwrap_or_default();
+        Self {
+            id: feed.id.unwrap_or_default(),
+            guild_id: sign.guild_id.unwrap_or_default(),
+            channel_id: sign.channel_id.unwrap_or_default(),
+            poster_id: poster.id.unwrap_or_default(),
+            poster_nick: poster.nick.unwrap_or_default(),
+            title: feed.title.map(rich_text_to_string).unwrap_or_default(),
+            content: feed.contents.map(rich_text_to_string).unwrap_or_default(),
+            images: feed
+                .images
+                .into_iter()
+                .filter_map(|image| image.pic_url)
+                .collect(),
+            create_time: feed.create_time.unwrap_or_default(),
+            comment_count: feed.comment_count.unwrap_or_default(),
+        }
+    }
+}
+
+fn rich_text_to_string(text: protobuf::StRichText) -> String {
+    let mut out = String::new();
+    for content in text.contents {
+        if let Some(t) = content.text_content.and_then(|t| t.text) {
+            out.push_str(&t);
+        } else if let Some(user) = content.at_content.and_then(|a| a.user) {
+            out.push('@');
+            out.push_str(&user.nick.unwrap_or_default());
+        } else if let Some(url) = content.url_content {
+            out.push_str(&url.display_text.or(url.url).unwrap_or_default());
+        } else if let Some(name) = content.emoji_content.and_then(|e| e.name) {
+            out.push_str(&format!("[{name}]"));
+        }
+    }
+    out
+}
+
 #[allow(clippy::all)]
 pub mod protobuf {
     include!(concat!(env!("OUT_DIR"), "/", "guild.rs"));
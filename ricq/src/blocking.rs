@@ -0,0 +1,153 @@
+//! 同步接口，内部持有 tokio runtime，适用于非 async 程序
+//!
+//! ```ignore
+//! let client = ricq::blocking::Client::new(Client::new(device, version, qsign_client, handler))?;
+//! client.connect()?;
+//! client.fast_login(&credential)?;
+//! client.send_group_message(group_code, MessageChain::new(Text::new("hello".into())))?;
+//! ```
+
+use std::future::Future;
+use std::sync::Arc;
+
+use ricq_core::command::friendlist::FriendListResponse;
+use ricq_core::msg::elem::{FriendImage, GroupImage};
+use ricq_core::msg::MessageChain;
+use ricq_core::structs::{GroupInfo, GroupMemberInfo, MessageReceipt};
+use tokio::runtime::Runtime;
+
+use crate::client::NetworkStatus;
+use crate::client::{Connector, DefaultConnector};
+use crate::ext::common::after_login;
+use crate::ext::reconnect::{fast_login, Credential};
+use crate::RQResult;
+
+/// `crate::Client` 的同步包装，所有方法都会阻塞当前线程
+pub struct Client {
+    runtime: Runtime,
+    inner: Arc<crate::Client>,
+}
+
+impl Client {
+    pub fn new(client: crate::Client) -> RQResult<Self> {
+        let runtime = tokio::runtime::Builder::new_multi_thread()
+            .enable_all()
+            .build()?;
+        Ok(Self {
+            runtime,
+            inner: Arc::new(client),
+        })
+    }
+
+    /// 内部的 async Client，没有同步包装的接口可以配合 `block_on` 使用
+    pub fn inner(&self) -> &Arc<crate::Client> {
+        &self.inner
+    }
+
+    /// 在内部 runtime 上运行 future 并等待结果
+    pub fn block_on<F: Future>(&self, future: F) -> F::Output {
+        self.runtime.block_on(future)
+    }
+
+    /// 连接服务器并在后台处理数据包
+    pub fn connect(&self) -> RQResult<()> {
+        let client = self.inner.clone();
+        let stream = self.block_on(DefaultConnector.connect(&client))?;
+        self.runtime
+            .spawn(async move { client.start(stream).await });
+        self.block_on(tokio::task::yield_now());
+        Ok(())
+    }
+
+    /// 使用 token 或密码登录，成功后开始心跳并刷新列表
+    pub fn fast_login(&self, credential: &Credential) -> RQResult<()> {
+        self.block_on(fast_login(&self.inner, credential))?;
+        self.block_on(after_login(&self.inner));
+        Ok(())
+    }
+
+    pub fn uin(&self) -> i64 {
+        self.block_on(self.inner.uin())
+    }
+
+    pub fn is_online(&self) -> bool {
+        self.inner.online.load(std::sync::atomic::Ordering::Relaxed)
+    }
+
+    pub fn send_group_message(
+        &self,
+        group_code: i64,
+        message_chain: MessageChain,
+    ) -> RQResult<MessageReceipt> {
+        self.block_on(self.inner.send_group_message(group_code, message_chain))
+    }
+
+    pub fn send_friend_message(
+        &self,
+        target: i64,
+        message_chain: MessageChain,
+    ) -> RQResult<MessageReceipt> {
+        self.block_on(self.inner.send_friend_message(target, message_chain))
+    }
+
+    pub fn recall_group_message(&self, group_code: i64, receipt: &MessageReceipt) -> RQResult<()> {
+        self.block_on(
+            self.inner
+                .recall_group_message_by_receipt(group_code, receipt),
+        )
+    }
+
+    pub fn recall_friend_message(&self, uin: i64, receipt: &MessageReceipt) -> RQResult<()> {
+        self.block_on(self.inner.recall_friend_message_by_receipt(uin, receipt))
+    }
+
+    pub fn recall_message(&self, receipt: &MessageReceipt) -> RQResult<()> {
+        self.block_on(self.inner.recall_message(receipt))
+    }
+
+    pub fn get_friend_list(&self) -> RQResult<FriendListResponse> {
+        self.block_on(self.inner.get_friend_list())
+    }
+
+    pub fn get_group_list(&self) -> RQResult<Vec<GroupInfo>> {
+        self.block_on(self.inner.get_group_list())
+    }
+
+    pub fn get_group_info(&self, group_code: i64) -> RQResult<Option<GroupInfo>> {
+        self.block_on(self.inner.get_group_info(group_code))
+    }
+
+    pub fn get_group_member_list(
+        &self,
+        group_code: i64,
+        group_owner_uin: i64,
+    ) -> RQResult<Vec<GroupMemberInfo>> {
+        self.block_on(
+            self.inner
+                .get_group_member_list(group_code, group_owner_uin),
+        )
+    }
+
+    pub fn get_group_member_info(&self, group_code: i64, uin: i64) -> RQResult<GroupMemberInfo> {
+        self.block_on(self.inner.get_group_member_info(group_code, uin))
+    }
+
+    pub fn upload_group_image(&self, group_code: i64, data: &[u8]) -> RQResult<GroupImage> {
+        self.block_on(self.inner.upload_group_image(group_code, data))
+    }
+
+    pub fn upload_friend_image(&self, target: i64, data: &[u8]) -> RQResult<FriendImage> {
+        self.block_on(self.inner.upload_friend_image(target, data))
+    }
+
+    /// 断开连接
+    pub fn stop(&self) {
+        self.inner.stop(NetworkStatus::Stop);
+    }
+}
+
+impl Drop for Client {
+    fn drop(&mut self) {
+        self.stop();
+    }
+}
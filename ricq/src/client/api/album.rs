@@ -0,0 +1,220 @@
+use base64::Engine;
+use serde::de::DeserializeOwned;
+use serde::Deserialize;
+
+use crate::structs::{GroupAlbum, GroupAlbumPhoto, GroupAlbumPhotoUpload};
+use crate::{RQError, RQResult};
+
+const QZONE_DOMAIN: &str = "qzone.qq.com";
+const ALBUM_PAGE_SIZE: usize = 100;
+
+#[derive(Deserialize)]
+struct QzoneRsp<T> {
+    #[serde(default)]
+    code: i32,
+    #[serde(default)]
+    message: String,
+    data: Option<T>,
+}
+
+#[derive(Deserialize)]
+struct CreateAlbumData {
+    #[serde(default)]
+    id: String,
+}
+
+#[derive(Deserialize)]
+struct AlbumListData {
+    #[serde(rename = "album", default)]
+    albums: Vec<GroupAlbum>,
+}
+
+#[derive(Deserialize)]
+struct PhotoListData {
+    #[serde(rename = "photolist", default)]
+    photos: Vec<GroupAlbumPhoto>,
+}
+
+// qzone 接口可能返回 JSONP，只取最外层的 JSON 对象
+fn parse_qzone<T: DeserializeOwned>(text: &str, action: &str) -> RQResult<T> {
+    let json = match (text.find('{'), text.rfind('}')) {
+        (Some(start), Some(end)) if start < end => &text[start..=end],
+        _ => return Err(RQError::Decode(format!("{action}: invalid response"))),
+    };
+    let rsp: QzoneRsp<T> =
+        serde_json::from_str(json).map_err(|e| RQError::Decode(e.to_string()))?;
+    if rsp.code != 0 {
+        return Err(RQError::Other(format!("{action}: {}", rsp.message)));
+    }
+    rsp.data.ok_or(RQError::EmptyField("data"))
+}
+
+/// 群相册，基于 qzone 网页接口
+impl super::super::Client {
+    /// 返回 qzone.qq.com 的 cookie 和 g_tk
+    async fn qzone_cookies(&self) -> RQResult<(String, i64)> {
+        let cookies = self.get_cookies(QZONE_DOMAIN).await?;
+        let g_tk = self
+            .engine
+            .read()
+            .await
+            .g_tk(QZONE_DOMAIN)
+            .ok_or(RQError::EmptyField("p_skey"))?;
+        Ok((cookies, g_tk))
+    }
+
+    async fn qzone_get<T: DeserializeOwned>(
+        &self,
+        cookies: String,
+        url: String,
+        action: &str,
+    ) -> RQResult<T> {
+        let text = self
+            .http_client()
+            .await
+            .get(url)
+            .header(reqwest::header::COOKIE, cookies)
+            .send()
+            .await
+            .map_err(|e| RQError::Other(e.to_string()))?
+            .text()
+            .await
+            .map_err(|e| RQError::Other(e.to_string()))?;
+        parse_qzone(&text, action)
+    }
+
+    async fn qzone_post_form<T: DeserializeOwned>(
+        &self,
+        cookies: String,
+        url: String,
+        form: &[(&str, String)],
+        action: &str,
+    ) -> RQResult<T> {
+        let text = self
+            .http_client()
+            .await
+            .post(url)
+            .header(reqwest::header::COOKIE, cookies)
+            .form(form)
+            .send()
+            .await
+            .map_err(|e| RQError::Other(e.to_string()))?
+            .text()
+            .await
+            .map_err(|e| RQError::Other(e.to_string()))?;
+        parse_qzone(&text, action)
+    }
+
+    /// 创建群相册，返回相册 id
+    pub async fn create_group_album(
+        &self,
+        group_code: i64,
+        name: String,
+        desc: String,
+    ) -> RQResult<String> {
+        let (cookies, g_tk) = self.qzone_cookies().await?;
+        let uin = self.uin().await;
+        let url = format!(
+            "https://h5.qzone.qq.com/proxy/domain/u.photo.qzone.qq.com/cgi-bin/upp/qun_create_album_v2?g_tk={g_tk}"
+        );
+        let data: CreateAlbumData = self
+            .qzone_post_form(
+                cookies,
+                url,
+                &[
+                    ("qunId", group_code.to_string()),
+                    ("uin", uin.to_string()),
+                    ("name", name),
+                    ("desc", desc),
+                    ("inCharset", "utf-8".into()),
+                    ("outCharset", "utf-8".into()),
+                ],
+                "create group album",
+            )
+            .await?;
+        Ok(data.id)
+    }
+
+    /// 上传照片到群相册，album_id 可以通过 create_group_album 创建
+    pub async fn upload_group_album_photo(
+        &self,
+        group_code: i64,
+        album_id: String,
+        image: Vec<u8>,
+    ) -> RQResult<GroupAlbumPhotoUpload> {
+        let (cookies, g_tk) = self.qzone_cookies().await?;
+        let uin = self.uin().await.to_string();
+        let url = format!(
+            "https://h5.qzone.qq.com/proxy/domain/up.qzone.qq.com/cgi-bin/upload/cgi_upload_image?g_tk={g_tk}"
+        );
+        let filename = format!("{:x}.jpg", md5::compute(&image));
+        self.qzone_post_form(
+            cookies,
+            url,
+            &[
+                ("filename", filename),
+                ("uploadtype", "1".into()),
+                ("albumtype", "7".into()),
+                ("exttype", "0".into()),
+                ("refer", "qun".into()),
+                ("output_type", "json".into()),
+                ("charset", "utf-8".into()),
+                ("output_charset", "utf-8".into()),
+                ("upload_hd", "1".into()),
+                ("base64", "1".into()),
+                ("uin", uin.clone()),
+                ("p_uin", uin),
+                ("qunid", group_code.to_string()),
+                ("albumid", album_id),
+                (
+                    "picfile",
+                    base64::engine::general_purpose::STANDARD.encode(&image),
+                ),
+            ],
+            "upload group album photo",
+        )
+        .await
+    }
+
+    /// 获取群相册列表
+    pub async fn get_group_albums(&self, group_code: i64) -> RQResult<Vec<GroupAlbum>> {
+        let uin = self.uin().await;
+        let mut albums = Vec::new();
+        loop {
+            let (cookies, g_tk) = self.qzone_cookies().await?;
+            let url = format!(
+                "https://h5.qzone.qq.com/proxy/domain/u.photo.qzone.qq.com/cgi-bin/upp/qun_list_album_v2?g_tk={g_tk}&qunId={group_code}&uin={uin}&start={}&num={ALBUM_PAGE_SIZE}&getMemberRole=1&inCharset=utf-8&outCharset=utf-8&source=qzone",
+                albums.len()
+            );
+            let data: AlbumListData = self.qzone_get(cookies, url, "get group albums").await?;
+            let count = data.albums.len();
+            albums.extend(data.albums);
+            if count < ALBUM_PAGE_SIZE {
+                return Ok(albums);
+            }
+        }
+    }
+
+    /// 获取群相册中的照片，包含上传者信息
+    pub async fn get_album_photos(
+        &self,
+        group_code: i64,
+        album_id: &str,
+    ) -> RQResult<Vec<GroupAlbumPhoto>> {
+        let uin = self.uin().await;
+        let mut photos = Vec::new();
+        loop {
+            let (cookies, g_tk) = self.qzone_cookies().await?;
+            let url = format!(
+                "https://h5.qzone.qq.com/proxy/domain/u.photo.qzone.qq.com/cgi-bin/upp/qun_list_photo_v2?g_tk={g_tk}&qunId={group_code}&albumId={album_id}&uin={uin}&start={}&num={ALBUM_PAGE_SIZE}&needUserInfo=1&inCharset=utf-8&outCharset=utf-8&source=qzone",
+                photos.len()
+            );
+            let data: PhotoListData = self.qzone_get(cookies, url, "get album photos").await?;
+            let count = data.photos.len();
+            photos.extend(data.photos);
+            if count < ALBUM_PAGE_SIZE {
+                return Ok(photos);
+            }
+        }
+    }
+}
@@ -0,0 +1,51 @@
+use ricq_core::msg::MessageChain;
+use ricq_core::structs::MessageReceipt;
+
+use crate::RQResult;
+
+/// 批量发送，每条之间间隔 `ClientOptions::broadcast_interval`，同时受 `send_rate_limit` 限制
+impl super::super::Client {
+    /// 向多个群发送同一条消息，返回每个群的发送结果，某个群失败不影响其他群
+    ///
+    /// 图片需要先在其中一个群上传，上传后的 GroupImage 可以在其他群使用
+    pub async fn broadcast_group_message(
+        &self,
+        group_codes: Vec<i64>,
+        message_chain: MessageChain,
+    ) -> Vec<(i64, RQResult<MessageReceipt>)> {
+        let mut results = Vec::with_capacity(group_codes.len());
+        for (i, group_code) in group_codes.into_iter().enumerate() {
+            if i > 0 {
+                tokio::time::sleep(self.options.broadcast_interval).await;
+            }
+            let result = self
+                .send_group_message(group_code, message_chain.clone())
+                .await;
+            if let Err(err) = &result {
+                tracing::warn!("failed to broadcast to group {}: {}", group_code, err);
+            }
+            results.push((group_code, result));
+        }
+        results
+    }
+
+    /// 向多个好友发送同一条消息，返回每个好友的发送结果
+    pub async fn broadcast_friend_message(
+        &self,
+        uins: Vec<i64>,
+        message_chain: MessageChain,
+    ) -> Vec<(i64, RQResult<MessageReceipt>)> {
+        let mut results = Vec::with_capacity(uins.len());
+        for (i, uin) in uins.into_iter().enumerate() {
+            if i > 0 {
+                tokio::time::sleep(self.options.broadcast_interval).await;
+            }
+            let result = self.send_friend_message(uin, message_chain.clone()).await;
+            if let Err(err) = &result {
+                tracing::warn!("failed to broadcast to friend {}: {}", uin, err);
+            }
+            results.push((uin, result));
+        }
+        results
+    }
+}
@@ -0,0 +1,154 @@
+use futures_util::future::select_ok;
+use futures_util::FutureExt;
+use reqwest::header::RANGE;
+use reqwest::{Response, StatusCode};
+use tokio::io::{AsyncWrite, AsyncWriteExt};
+
+use crate::{RQError, RQResult};
+
+/// 下载到 AsyncWrite，不需要把整个文件读入内存
+impl super::super::Client {
+    /// 下载 url 写入 writer，返回写入的字节数
+    ///
+    /// progress 在每次写入后调用，参数为已下载字节数和总字节数（服务器没有返回长度时为 None）
+    pub async fn download_to<W, F>(&self, url: &str, writer: &mut W, progress: F) -> RQResult<u64>
+    where
+        W: AsyncWrite + Unpin,
+        F: FnMut(u64, Option<u64>),
+    {
+        self.download_from_any(&[url.to_string()], writer, progress)
+            .await
+    }
+
+    /// 从多个地址下载同一个文件写入 writer，返回写入的字节数
+    ///
+    /// 同时请求所有地址，使用最先响应的一个；下载中断时从剩余地址中断点续传。
+    /// progress 同 download_to
+    pub async fn download_from_any<W, F>(
+        &self,
+        urls: &[String],
+        writer: &mut W,
+        mut progress: F,
+    ) -> RQResult<u64>
+    where
+        W: AsyncWrite + Unpin,
+        F: FnMut(u64, Option<u64>),
+    {
+        let mut sources: Vec<&str> = urls.iter().map(String::as_str).collect();
+        let mut downloaded = 0;
+        let mut last_err = RQError::EmptyField("urls");
+        while !sources.is_empty() {
+            let requests = sources.iter().enumerate().map(|(i, url)| {
+                async move {
+                    let http = self.http_client().await;
+                    let resp = request_from(&http, url, downloaded).await?;
+                    Ok::<_, RQError>((i, resp))
+                }
+                .boxed()
+            });
+            let ((i, resp), _) = select_ok(requests).await?;
+            let url = sources.remove(i);
+            match write_response(resp, writer, &mut downloaded, &mut progress).await {
+                Ok(()) => {
+                    writer.flush().await.map_err(RQError::IO)?;
+                    return Ok(downloaded);
+                }
+                // 已写入的数据不能撤回，只有网络错误才换地址继续
+                Err(RQError::IO(err)) => return Err(RQError::IO(err)),
+                Err(err) => {
+                    tracing::warn!("download from {} interrupted: {}", url, err);
+                    last_err = err;
+                }
+            }
+        }
+        Err(last_err)
+    }
+
+    /// 下载群文件写入 writer，参数同 get_group_file_download，失败时自动尝试其他服务器
+    pub async fn download_group_file_to<W, F>(
+        &self,
+        group_code: i64,
+        file_id: &str,
+        bus_id: u32,
+        file_name: &str,
+        writer: &mut W,
+        progress: F,
+    ) -> RQResult<u64>
+    where
+        W: AsyncWrite + Unpin,
+        F: FnMut(u64, Option<u64>),
+    {
+        let urls = self
+            .get_group_file_download_urls(group_code, file_id, bus_id, file_name)
+            .await?;
+        self.download_from_any(&urls, writer, progress).await
+    }
+
+    /// 下载好友离线文件写入 writer
+    pub async fn download_friend_file_to<W, F>(
+        &self,
+        file_uuid: Vec<u8>,
+        writer: &mut W,
+        progress: F,
+    ) -> RQResult<u64>
+    where
+        W: AsyncWrite + Unpin,
+        F: FnMut(u64, Option<u64>),
+    {
+        let url = self.download_friend_file(file_uuid).await?;
+        self.download_to(&url, writer, progress).await
+    }
+}
+
+/// offset 不为 0 时带上 Range 请求剩余部分
+async fn request_from(http: &reqwest::Client, url: &str, offset: u64) -> RQResult<Response> {
+    let mut req = http.get(url);
+    if offset > 0 {
+        req = req.header(RANGE, format!("bytes={offset}-"));
+    }
+    let resp = req
+        .send()
+        .await
+        .map_err(|e| RQError::Other(e.to_string()))?;
+    if !resp.status().is_success() {
+        return Err(RQError::Other(format!(
+            "download failed: status {}",
+            resp.status()
+        )));
+    }
+    Ok(resp)
+}
+
+async fn write_response<W, F>(
+    mut resp: Response,
+    writer: &mut W,
+    downloaded: &mut u64,
+    progress: &mut F,
+) -> RQResult<()>
+where
+    W: AsyncWrite + Unpin,
+    F: FnMut(u64, Option<u64>),
+{
+    // 服务器不支持 Range 时返回完整文件，跳过已写入的部分
+    let mut skip = if resp.status() == StatusCode::PARTIAL_CONTENT {
+        0
+    } else {
+        *downloaded
+    };
+    let total = resp.content_length().map(|len| len + *downloaded - skip);
+    while let Some(chunk) = resp
+        .chunk()
+        .await
+        .map_err(|e| RQError::Other(e.to_string()))?
+    {
+        let n = skip.min(chunk.len() as u64) as usize;
+        skip -= n as u64;
+        if n == chunk.len() {
+            continue;
+        }
+        writer.write_all(&chunk[n..]).await.map_err(RQError::IO)?;
+        *downloaded += (chunk.len() - n) as u64;
+        progress(*downloaded, total);
+    }
+    Ok(())
+}
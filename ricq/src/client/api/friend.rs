@@ -1,9 +1,15 @@
+use std::io::Cursor;
+use std::sync::Arc;
 use std::time::Duration;
 
 use bytes::BufMut;
+use cached::Cached;
+use tokio::io::{AsyncRead, AsyncSeek};
+use tokio_util::sync::CancellationToken;
 
 use ricq_core::command::long_conn::OffPicUpResp;
 use ricq_core::command::oidb_svc::{LinkShare, MusicShare, MusicVersion, ShareTarget};
+use ricq_core::command::summary_card::SearchUserInfo;
 use ricq_core::command::{friendlist::*, profile_service::*};
 use ricq_core::hex::encode_hex;
 use ricq_core::highway::BdhInput;
@@ -12,8 +18,10 @@ use ricq_core::msg::MessageChain;
 use ricq_core::pb;
 use ricq_core::pb::msg::routing_head::RoutingHead;
 use ricq_core::structs::FriendAudio;
-use ricq_core::structs::MessageReceipt;
+use ricq_core::structs::{MessageReceipt, MessageTarget};
 
+use crate::client::highway::stream_md5;
+use crate::client::throttle::SendTarget;
 use crate::structs::ImageInfo;
 use crate::{RQError, RQResult};
 
@@ -88,6 +96,7 @@ impl super::super::Client {
         let resp = self.send_and_wait(req).await?;
 
         let resp = self.engine.read().await.decode_remove_friend(resp.body)?;
+        self.friend_cache.write().await.cache_clear();
         if resp.error_code != 0 {
             Err(RQError::Other(format!(
                 "Delete Friend Failure : code = {}",
@@ -98,6 +107,75 @@ impl super::super::Client {
         }
     }
 
+    /// 获取对方的加好友设置，包括是否需要回答问题
+    pub async fn get_user_add_friend_setting(&self, uin: i64) -> RQResult<UserAddFriendSetting> {
+        let req = self
+            .engine
+            .read()
+            .await
+            .build_get_user_add_friend_setting_packet(uin);
+        let resp = self.send_and_wait(req).await?;
+        self.engine
+            .read()
+            .await
+            .decode_get_user_add_friend_setting_response(resp.body)
+    }
+
+    /// 按昵称/关键词搜索用户，同时查询每个结果的加好友设置
+    pub async fn search_user(&self, keyword: String) -> RQResult<Vec<SearchUserInfo>> {
+        let req = self
+            .engine
+            .read()
+            .await
+            .build_search_request_packet(keyword);
+        let resp = self.send_and_wait(req).await?;
+        let mut users = self.engine.read().await.decode_search_response(resp.body)?;
+        let settings = futures_util::future::join_all(
+            users
+                .iter()
+                .map(|user| self.get_user_add_friend_setting(user.uin)),
+        )
+        .await;
+        for (user, setting) in users.iter_mut().zip(settings) {
+            user.add_friend_setting = setting.ok();
+        }
+        Ok(users)
+    }
+
+    /// 获取好友列表，优先使用缓存，缓存过期时间见 `CacheOptions::friend_list`
+    pub async fn get_friend_list_cached(&self) -> RQResult<Arc<FriendListResponse>> {
+        if let Some(list) = self.friend_cache.write().await.cache_get(&()) {
+            return Ok(list.clone());
+        }
+        let _guard = self.friend_loading.lock().await;
+        // 等待锁期间可能已经被其他任务加载
+        if let Some(list) = self.friend_cache.write().await.cache_get(&()) {
+            return Ok(list.clone());
+        }
+        let list = Arc::new(self.get_friend_list().await?);
+        self.friend_cache.write().await.cache_set((), list.clone());
+        Ok(list)
+    }
+
+    /// 查询与用户的关系，优先使用缓存的好友列表，缓存失效时只查询该用户，不会拉取完整好友列表
+    pub async fn relationship(&self, uin: i64) -> RQResult<Relationship> {
+        if let Some(list) = self.friend_cache.write().await.cache_get(&()) {
+            return Ok(list.relationship(uin));
+        }
+        let req = self
+            .engine
+            .read()
+            .await
+            .build_friend_list_query_packet(vec![uin]);
+        let resp = self.send_and_wait(req).await?;
+        Ok(self
+            .engine
+            .read()
+            .await
+            .decode_friend_group_list_response(resp.body)?
+            .relationship(uin))
+    }
+
     /// 刷新好友列表
     pub async fn get_friend_list(&self) -> RQResult<FriendListResponse> {
         let mut output = FriendListResponse::default();
@@ -180,6 +258,7 @@ impl super::super::Client {
         message_chain: MessageChain,
         ptt: Option<pb::msg::Ptt>,
     ) -> RQResult<MessageReceipt> {
+        self.throttle_send(SendTarget::Friend(target)).await?;
         self.send_message(
             RoutingHead::C2c(pb::msg::C2c {
                 to_uin: Some(target),
@@ -191,7 +270,25 @@ impl super::super::Client {
     }
 
     pub async fn upload_friend_image(&self, target: i64, data: &[u8]) -> RQResult<FriendImage> {
-        let image_info = ImageInfo::try_new(data)?;
+        self.upload_friend_image_stream(target, Cursor::new(data), &CancellationToken::new())
+            .await
+    }
+
+    /// 从 reader 上传好友图片，不需要把整个文件读入内存，可以通过 cancel 取消上传
+    pub async fn upload_friend_image_stream<R: AsyncRead + AsyncSeek + Unpin>(
+        &self,
+        target: i64,
+        mut reader: R,
+        cancel: &CancellationToken,
+    ) -> RQResult<FriendImage> {
+        let image_info = ImageInfo::try_from_reader(&mut reader).await?;
+        let cache = self.image_cache.read().await.clone();
+        if let Some(image) = cache
+            .as_ref()
+            .and_then(|c| c.get_friend_image(target, &image_info.md5))
+        {
+            return Ok(image);
+        }
         let image_store = self.get_off_pic_store(target, &image_info).await?;
 
         let friend_image = match image_store {
@@ -202,13 +299,13 @@ impl super::super::Client {
                 upload_key,
                 mut upload_addrs,
             } => {
-                let addr = match self.highway_addrs.read().await.first() {
-                    Some(addr) => *addr,
+                let addr = match self.select_highway_addr().await {
+                    Some(addr) => addr,
                     None => upload_addrs
                         .pop()
                         .ok_or(RQError::EmptyField("upload_addrs"))?,
                 };
-                self.highway_upload_bdh(
+                self.highway_upload_bdh_reader(
                     addr.into(),
                     BdhInput {
                         command_id: 1,
@@ -218,12 +315,18 @@ impl super::super::Client {
                         chunk_size: 256 * 1024,
                         send_echo: true,
                     },
-                    data,
+                    reader,
+                    image_info.size as u64,
+                    image_info.md5.clone(),
+                    cancel,
                 )
                 .await?;
                 image_info.into_friend_image(res_id, uuid)
             }
         };
+        if let Some(cache) = cache {
+            cache.put_friend_image(target, &friend_image);
+        }
         Ok(friend_image)
     }
 
@@ -254,28 +357,30 @@ impl super::super::Client {
         uin: i64,
         music_share: MusicShare,
         music_version: MusicVersion,
-    ) -> RQResult<()> {
+    ) -> RQResult<MessageReceipt> {
         let req = self.engine.read().await.build_share_music_request_packet(
             ShareTarget::Friend(uin),
             music_share,
             music_version,
         );
-        let _ = self.send_and_wait(req).await?;
-        Ok(())
+        self.send_share(MessageTarget::Friend(uin), req).await
     }
 
     /// 分享链接
-    pub async fn send_friend_link_share(&self, uin: i64, link_share: LinkShare) -> RQResult<()> {
+    pub async fn send_friend_link_share(
+        &self,
+        uin: i64,
+        link_share: LinkShare,
+    ) -> RQResult<MessageReceipt> {
         let req = self
             .engine
             .read()
             .await
             .build_share_link_request_packet(ShareTarget::Friend(uin), link_share);
-        let _ = self.send_and_wait(req).await?;
-        Ok(())
+        self.send_share(MessageTarget::Friend(uin), req).await
     }
 
-    // 撤回好友消息
+    /// 撤回自己发送的好友消息，msg_time 为发送时间
     pub async fn recall_friend_message(
         &self,
         uin: i64,
@@ -288,8 +393,26 @@ impl super::super::Client {
             .read()
             .await
             .build_friend_recall_packet(uin, msg_time, seqs, rands);
-        let _ = self.send_and_wait(req).await?;
-        Ok(())
+        let resp = self.send_and_wait(req).await?;
+        self.engine
+            .read()
+            .await
+            .decode_msg_withdraw_response(resp.body)
+    }
+
+    /// 通过发送消息返回的 MessageReceipt 撤回好友消息
+    pub async fn recall_friend_message_by_receipt(
+        &self,
+        uin: i64,
+        receipt: &MessageReceipt,
+    ) -> RQResult<()> {
+        self.recall_friend_message(
+            uin,
+            receipt.time,
+            receipt.seqs.clone(),
+            receipt.rands.clone(),
+        )
+        .await
     }
 
     pub async fn upload_friend_audio(
@@ -298,8 +421,25 @@ impl super::super::Client {
         data: &[u8],
         audio_duration: Duration,
     ) -> RQResult<FriendAudio> {
-        let md5 = md5::compute(data).to_vec();
-        let size = data.len();
+        self.upload_friend_audio_stream(
+            target,
+            Cursor::new(data),
+            audio_duration,
+            &CancellationToken::new(),
+        )
+        .await
+    }
+
+    /// 从 reader 上传好友语音，不需要把整个文件读入内存，可以通过 cancel 取消上传
+    pub async fn upload_friend_audio_stream<R: AsyncRead + AsyncSeek + Unpin>(
+        &self,
+        target: i64,
+        mut reader: R,
+        audio_duration: Duration,
+        cancel: &CancellationToken,
+    ) -> RQResult<FriendAudio> {
+        let (md5, size) = stream_md5(&mut reader).await?;
+        let size = size as usize;
         let ext = self.engine.read().await.build_friend_try_up_ptt_req(
             target,
             md5.clone(),
@@ -307,11 +447,8 @@ impl super::super::Client {
             size as i32,
         );
         let addr = self
-            .highway_addrs
-            .read()
+            .select_highway_addr()
             .await
-            .first()
-            .copied()
             .ok_or(RQError::EmptyField("highway_addrs"))?;
         let ticket = self
             .highway_session
@@ -321,7 +458,7 @@ impl super::super::Client {
             .clone()
             .to_vec();
         let resp = self
-            .highway_upload_bdh(
+            .highway_upload_bdh_reader(
                 addr.into(),
                 BdhInput {
                     command_id: 26,
@@ -331,7 +468,10 @@ impl super::super::Client {
                     chunk_size: 256 * 1024,
                     send_echo: true,
                 },
-                data,
+                reader,
+                size as u64,
+                md5.clone(),
+                cancel,
             )
             .await?;
         let uuid = self
@@ -384,15 +524,84 @@ impl super::super::Client {
         self.engine.read().await.decode_c2c_ptt_down(resp.body)
     }
 
-    /// 标记私聊消息已读 TODO 待测试
-    pub async fn mark_friend_message_readed(&self, uin: i64, time: i64) -> RQResult<()> {
+    /// 获取好友图片下载链接，有 orig_url 时直接使用，否则通过 LongConn.OffPicDown 请求
+    pub async fn get_friend_image_url(
+        &self,
+        sender_uin: i64,
+        image: &FriendImage,
+    ) -> RQResult<String> {
+        if !image.orig_url.is_empty() {
+            return Ok(image.url());
+        }
+        let res_id = if image.res_id.is_empty() {
+            image.download_path.clone()
+        } else {
+            image.res_id.clone()
+        };
+        if res_id.is_empty() {
+            return Err(RQError::EmptyField("res_id"));
+        }
+        let req = self
+            .engine
+            .read()
+            .await
+            .build_off_pic_down_packet(sender_uin, res_id);
+        let resp = self.send_and_wait(req).await?;
+        self.engine
+            .read()
+            .await
+            .decode_off_pic_down_response(resp.body)
+    }
+
+    /// 好友语音转文字，返回识别结果
+    pub async fn friend_audio_to_text(
+        &self,
+        sender_uin: i64,
+        audio: FriendAudio,
+    ) -> RQResult<String> {
+        let req = self
+            .engine
+            .read()
+            .await
+            .build_c2c_ptt_trans_req(sender_uin, &audio.0);
+        let resp = self.send_and_wait(req).await?;
+        self.engine
+            .read()
+            .await
+            .decode_ptt_trans_response(resp.body)
+    }
+
+    /// 获取好友离线文件的下载链接，file_uuid 见 FriendFileReceivedEvent
+    pub async fn download_friend_file(&self, file_uuid: Vec<u8>) -> RQResult<String> {
+        let req = self
+            .engine
+            .read()
+            .await
+            .build_offline_file_download_request_packet(file_uuid);
+        let resp = self.send_and_wait(req).await?;
+        self.engine
+            .read()
+            .await
+            .decode_offline_file_download_response(resp.body)
+    }
+
+    /// 标记私聊消息已读，time 为最后一条已读消息的时间
+    pub async fn mark_friend_read(&self, uin: i64, time: i64) -> RQResult<()> {
         let req = self
             .engine
             .read()
             .await
             .build_friend_msg_readed_packet(uin, time);
-        let _ = self.send_and_wait(req).await?;
-        Ok(())
+        let resp = self.send_and_wait(req).await?;
+        self.engine
+            .read()
+            .await
+            .decode_msg_readed_response(resp.body)
+    }
+
+    /// 标记私聊消息已读，同 mark_friend_read
+    pub async fn mark_friend_message_readed(&self, uin: i64, time: i64) -> RQResult<()> {
+        self.mark_friend_read(uin, time).await
     }
 
     /// 获取好友个性签名
@@ -1,13 +1,19 @@
 use std::collections::HashMap;
+use std::io::Cursor;
+use std::sync::Arc;
 use std::time::{Duration, UNIX_EPOCH};
 
 use bytes::Bytes;
 use cached::Cached;
 use prost::Message;
+use tokio::io::{AsyncRead, AsyncReadExt, AsyncSeek};
+use tokio::sync::Mutex;
+use tokio_util::sync::CancellationToken;
 
 use ricq_core::command::common::PbToBytes;
 use ricq_core::command::img_store::GroupImageStoreResp;
 use ricq_core::command::multi_msg::gen_forward_preview;
+use ricq_core::command::summary_card::PublicGroupInfo;
 use ricq_core::command::{friendlist::*, oidb_svc::*, profile_service::*};
 use ricq_core::common::group_code2uin;
 use ricq_core::hex::encode_hex;
@@ -18,11 +24,18 @@ use ricq_core::pb;
 use ricq_core::pb::short_video::ShortVideoUploadRsp;
 use ricq_core::structs::{ForwardMessage, GroupFileCount, GroupFileList, MessageNode};
 use ricq_core::structs::{GroupAudio, GroupMemberPermission};
-use ricq_core::structs::{GroupInfo, GroupMemberInfo, MessageReceipt};
+use ricq_core::structs::{
+    GroupInfo, GroupJoinMode, GroupMemberInfo, GroupSettings, MessageReceipt, MessageTarget,
+};
 
+use crate::client::highway::{stream_md5, stream_md5_into};
+use crate::client::throttle::SendTarget;
 use crate::structs::ImageInfo;
 use crate::{RQError, RQResult};
 
+/// OidbSvc.0x88d_0 单个请求包含的群数量
+const GROUP_INFO_BATCH_SIZE: usize = 20;
+
 impl super::super::Client {
     /// 获取进群申请信息
     async fn get_group_system_messages(&self, suspicious: bool) -> RQResult<GroupSystemMessages> {
@@ -96,15 +109,40 @@ impl super::super::Client {
     }
 
     /// 发送群消息
+    ///
+    /// seq 从自身消息的推送中获取，5 秒内没有收到时回执的 seq 为 0，之后收到的推送会被丢弃
     pub async fn send_group_message(
         &self,
         group_code: i64,
-        message_chain: MessageChain,
+        mut message_chain: MessageChain,
     ) -> RQResult<MessageReceipt> {
+        self.fill_group_at_display(group_code, &mut message_chain)
+            .await;
         self._send_group_message(group_code, message_chain.into(), None)
             .await
     }
 
+    /// 从成员列表缓存中补全 At 的显示名称，优先使用群名片
+    ///
+    /// 只使用已经加载的缓存，不会为了发送消息去拉取成员列表
+    async fn fill_group_at_display(&self, group_code: i64, chain: &mut MessageChain) {
+        if !chain.has_at_without_display() {
+            return;
+        }
+        let members = match self.group_member_cache.write().await.cache_get(&group_code) {
+            Some(members) => members.clone(),
+            None => return,
+        };
+        chain.fill_at_display(|uin| {
+            let member = members.iter().find(|m| m.uin == uin)?;
+            if member.card_name.is_empty() {
+                Some(member.nickname.clone())
+            } else {
+                Some(member.card_name.clone())
+            }
+        });
+    }
+
     /// 发送群语音
     pub async fn send_group_audio(
         &self,
@@ -121,6 +159,7 @@ impl super::super::Client {
         elems: Vec<pb::msg::Elem>,
         ptt: Option<pb::msg::Ptt>,
     ) -> RQResult<MessageReceipt> {
+        self.throttle_send(SendTarget::Group(group_code)).await?;
         let ran = (rand::random::<u32>() >> 1) as i32;
         let (tx, rx) = tokio::sync::oneshot::channel();
         {
@@ -131,25 +170,55 @@ impl super::super::Client {
             .read()
             .await
             .build_group_sending_packet(group_code, elems, ptt, ran, 1, 0, 0, false);
-        let _ = self.send_and_wait(req).await?;
+        let sent = async {
+            let resp = self.send_and_wait(req).await?;
+            self.engine
+                .read()
+                .await
+                .decode_send_message_response(resp.body)
+        }
+        .await;
+        let send_time = match sent {
+            Ok(send_time) => send_time,
+            Err(err) => {
+                self.receipt_waiters.lock().await.cache_remove(&ran);
+                return Err(err);
+            }
+        };
         let mut receipt = MessageReceipt {
+            target: MessageTarget::Group(group_code),
             seqs: vec![0],
             rands: vec![ran],
-            time: UNIX_EPOCH.elapsed().unwrap().as_secs() as i64,
+            time: if send_time != 0 {
+                send_time
+            } else {
+                UNIX_EPOCH.elapsed().unwrap().as_secs() as i64
+            },
         };
+        // 群消息的 seq 由服务器分配，从自身消息的推送中获取
         match tokio::time::timeout(Duration::from_secs(5), rx).await {
             Ok(Ok(seq)) => {
                 if let Some(s) = receipt.seqs.first_mut() {
                     *s = seq;
                 }
             }
-            Ok(Err(_)) => {} //todo
-            Err(_) => {}
+            Ok(Err(_)) | Err(_) => {
+                if self
+                    .receipt_waiters
+                    .lock()
+                    .await
+                    .cache_remove(&ran)
+                    .is_some()
+                {
+                    self.late_receipts.lock().await.cache_set(ran, ());
+                }
+                tracing::warn!("group message receipt seq not received, rand: {}", ran);
+            }
         }
         Ok(receipt)
     }
 
-    /// 发送群成员临时消息
+    /// 发送群成员临时消息，可主动发起或回复 GroupTempMessageEvent
     pub async fn send_group_temp_message(
         &self,
         group_code: i64,
@@ -185,8 +254,39 @@ impl super::super::Client {
             .decode_group_member_info_response(resp.body)
     }
 
-    /// 批量获取群信息
+    /// 批量获取群信息，每个请求最多包含 GROUP_INFO_BATCH_SIZE 个群，多个请求并发发送
+    ///
+    /// 返回顺序与 group_codes 一致，获取失败的群（包括请求失败的批次）会被跳过，
+    /// 所有批次都失败时返回第一个错误
     pub async fn get_group_infos(&self, group_codes: Vec<i64>) -> RQResult<Vec<GroupInfo>> {
+        let batches = group_codes
+            .chunks(GROUP_INFO_BATCH_SIZE)
+            .map(|codes| self._get_group_infos(codes.to_vec()));
+        let mut infos: HashMap<i64, GroupInfo> = HashMap::new();
+        let mut first_err = None;
+        let mut succeeded = false;
+        for batch in futures_util::future::join_all(batches).await {
+            match batch {
+                Ok(batch) => {
+                    succeeded = true;
+                    infos.extend(batch.into_iter().map(|info| (info.code, info)));
+                }
+                Err(err) => {
+                    tracing::warn!("failed to get group infos: {}", err);
+                    first_err.get_or_insert(err);
+                }
+            }
+        }
+        if let (false, Some(err)) = (succeeded, first_err) {
+            return Err(err);
+        }
+        Ok(group_codes
+            .iter()
+            .filter_map(|code| infos.remove(code))
+            .collect())
+    }
+
+    async fn _get_group_infos(&self, group_codes: Vec<i64>) -> RQResult<Vec<GroupInfo>> {
         let req = self
             .engine
             .read()
@@ -201,7 +301,34 @@ impl super::super::Client {
 
     /// 获取群信息
     pub async fn get_group_info(&self, group_code: i64) -> RQResult<Option<GroupInfo>> {
-        Ok(self.get_group_infos(vec![group_code]).await?.pop())
+        Ok(self
+            .get_group_infos(vec![group_code])
+            .await?
+            .into_iter()
+            .find(|info| info.code == group_code))
+    }
+
+    /// 获取群信息，不是群成员时回退到搜索群号，只返回公开信息，可用于处理入群邀请
+    pub async fn get_public_group_info(
+        &self,
+        group_code: i64,
+    ) -> RQResult<Option<PublicGroupInfo>> {
+        if let Ok(Some(info)) = self.get_group_info(group_code).await {
+            return Ok(Some(PublicGroupInfo::from(&info)));
+        }
+        let req = self
+            .engine
+            .read()
+            .await
+            .build_group_search_request_packet(group_code.to_string());
+        let resp = self.send_and_wait(req).await?;
+        Ok(self
+            .engine
+            .read()
+            .await
+            .decode_group_search_response(resp.body)?
+            .into_iter()
+            .find(|info| info.code == group_code))
     }
 
     /// 刷新群列表
@@ -238,7 +365,7 @@ impl super::super::Client {
         self.engine
             .read()
             .await
-            .decode_group_member_list_response(resp.body, group_owner_uin)
+            .decode_group_member_list_response_with_group(resp.body, group_code, group_owner_uin)
     }
 
     /// 获取群成员列表
@@ -246,19 +373,34 @@ impl super::super::Client {
         &self,
         group_code: i64,
         group_owner_uin: i64,
+    ) -> RQResult<Vec<GroupMemberInfo>> {
+        self.get_group_member_list_with_cancel(
+            group_code,
+            group_owner_uin,
+            &CancellationToken::new(),
+        )
+        .await
+    }
+
+    /// 获取群成员列表，每页请求前检查 `cancel`，取消后返回 `RQError::Cancelled`
+    pub async fn get_group_member_list_with_cancel(
+        &self,
+        group_code: i64,
+        group_owner_uin: i64,
+        cancel: &CancellationToken,
     ) -> RQResult<Vec<GroupMemberInfo>> {
         let mut next_uin = 0;
         let mut list = Vec::new();
         loop {
+            if cancel.is_cancelled() {
+                return Err(RQError::Cancelled);
+            }
             let mut resp = self
                 ._get_group_member_list(group_code, next_uin, group_owner_uin)
                 .await?;
             if resp.list.is_empty() {
                 return Err(RQError::EmptyField("GroupMemberListResponse.list"));
             }
-            for m in resp.list.iter_mut() {
-                m.group_code = group_code;
-            }
             list.append(&mut resp.list);
             next_uin = resp.next_uin;
             if next_uin == 0 {
@@ -268,15 +410,112 @@ impl super::super::Client {
         Ok(list)
     }
 
-    /// 标记群消息已读
-    pub async fn mark_group_message_readed(&self, group_code: i64, seq: i32) -> RQResult<()> {
+    /// 获取群成员列表，优先使用缓存，缓存过期时间见 `CacheOptions::group_member`
+    pub async fn get_group_members_cached(
+        &self,
+        group_code: i64,
+    ) -> RQResult<Arc<Vec<GroupMemberInfo>>> {
+        if let Some(members) = self.group_member_cache.write().await.cache_get(&group_code) {
+            return Ok(members.clone());
+        }
+        let loading = self.group_member_loading_lock(group_code).await;
+        let result = {
+            let _guard = loading.lock().await;
+            // 等待锁期间可能已经被其他任务加载
+            let cached = self
+                .group_member_cache
+                .write()
+                .await
+                .cache_get(&group_code)
+                .cloned();
+            match cached {
+                Some(members) => Ok(members),
+                None => self.load_group_members(group_code).await,
+            }
+        };
+        self.release_group_member_loading(group_code, loading).await;
+        result
+    }
+
+    /// 重新获取群成员列表并刷新缓存
+    pub async fn refresh_group_members(
+        &self,
+        group_code: i64,
+    ) -> RQResult<Arc<Vec<GroupMemberInfo>>> {
+        let loading = self.group_member_loading_lock(group_code).await;
+        let result = {
+            let _guard = loading.lock().await;
+            self.load_group_members(group_code).await
+        };
+        self.release_group_member_loading(group_code, loading).await;
+        result
+    }
+
+    /// 从缓存的成员列表中查找群成员，找不到时单独请求
+    pub async fn get_group_member_info_cached(
+        &self,
+        group_code: i64,
+        uin: i64,
+    ) -> RQResult<GroupMemberInfo> {
+        let members = self.get_group_members_cached(group_code).await?;
+        match members.iter().find(|m| m.uin == uin) {
+            Some(member) => Ok(member.clone()),
+            None => self.get_group_member_info(group_code, uin).await,
+        }
+    }
+
+    async fn group_member_loading_lock(&self, group_code: i64) -> Arc<Mutex<()>> {
+        self.group_member_loading
+            .lock()
+            .await
+            .entry(group_code)
+            .or_default()
+            .clone()
+    }
+
+    /// 加载结束后移除群的加载锁，还有其他任务在等待时保留
+    async fn release_group_member_loading(&self, group_code: i64, loading: Arc<Mutex<()>>) {
+        let mut loading_map = self.group_member_loading.lock().await;
+        // 只在持有 map 的锁时 clone，这里计数不会变化
+        let idle = loading_map
+            .get(&group_code)
+            .is_some_and(|l| Arc::ptr_eq(l, &loading) && Arc::strong_count(&loading) == 2);
+        if idle {
+            loading_map.remove(&group_code);
+        }
+    }
+
+    async fn load_group_members(&self, group_code: i64) -> RQResult<Arc<Vec<GroupMemberInfo>>> {
+        let owner_uin = self
+            .get_group_info(group_code)
+            .await?
+            .ok_or(RQError::EmptyField("group_info"))?
+            .owner_uin;
+        let members = Arc::new(self.get_group_member_list(group_code, owner_uin).await?);
+        self.group_member_cache
+            .write()
+            .await
+            .cache_set(group_code, members.clone());
+        Ok(members)
+    }
+
+    /// 标记群消息已读，seq 为最后一条已读消息的 seq
+    pub async fn mark_group_read(&self, group_code: i64, seq: i32) -> RQResult<()> {
         let req = self
             .engine
             .read()
             .await
             .build_group_msg_readed_packet(group_code, seq);
-        let _ = self.send_and_wait(req).await?;
-        Ok(())
+        let resp = self.send_and_wait(req).await?;
+        self.engine
+            .read()
+            .await
+            .decode_msg_readed_response(resp.body)
+    }
+
+    /// 标记群消息已读，同 mark_group_read
+    pub async fn mark_group_message_readed(&self, group_code: i64, seq: i32) -> RQResult<()> {
+        self.mark_group_read(group_code, seq).await
     }
 
     /// 群禁言 (解除禁言 duration=0)
@@ -328,6 +567,58 @@ impl super::super::Client {
         Ok(())
     }
 
+    /// 获取加群方式，需要是群成员
+    pub async fn get_group_join_mode(&self, group_code: i64) -> RQResult<GroupJoinMode> {
+        let req = self
+            .engine
+            .read()
+            .await
+            .build_group_info_request_packet(vec![group_code]);
+        let resp = self.send_and_wait(req).await?;
+        self.engine
+            .read()
+            .await
+            .decode_group_join_mode_response(resp.body)?
+            .into_iter()
+            .find(|(code, _)| *code == group_code)
+            .map(|(_, mode)| mode)
+            .ok_or(RQError::EmptyField("group_option"))
+    }
+
+    /// 设置加群方式和问题，需要管理员权限
+    pub async fn set_group_join_mode(&self, group_code: i64, mode: &GroupJoinMode) -> RQResult<()> {
+        let req = self
+            .engine
+            .read()
+            .await
+            .build_group_join_mode_packet(group_code, mode);
+        let _ = self.send_and_wait(req).await?;
+        Ok(())
+    }
+
+    /// 获取群设置，需要是群成员
+    pub async fn get_group_settings(&self, group_code: i64) -> RQResult<GroupSettings> {
+        self.get_group_info(group_code)
+            .await?
+            .map(|info| info.settings)
+            .ok_or(RQError::EmptyField("group_info"))
+    }
+
+    /// 修改群设置，需要管理员权限，一次提交所有字段
+    pub async fn update_group_settings(
+        &self,
+        group_code: i64,
+        settings: &GroupSettings,
+    ) -> RQResult<()> {
+        let req = self
+            .engine
+            .read()
+            .await
+            .build_group_settings_update_packet(group_code, settings);
+        let _ = self.send_and_wait(req).await?;
+        Ok(())
+    }
+
     /// 设置群管理员
     ///
     /// flag: true 设置管理员 false 取消管理员
@@ -436,14 +727,13 @@ impl super::super::Client {
         group_code: i64,
         music_share: MusicShare,
         music_version: MusicVersion,
-    ) -> RQResult<()> {
+    ) -> RQResult<MessageReceipt> {
         let req = self.engine.read().await.build_share_music_request_packet(
             ShareTarget::Group(group_code),
             music_share,
             music_version,
         );
-        let _ = self.send_and_wait(req).await?;
-        Ok(())
+        self.send_share(MessageTarget::Group(group_code), req).await
     }
 
     /// 分享链接
@@ -451,14 +741,13 @@ impl super::super::Client {
         &self,
         group_code: i64,
         link_share: LinkShare,
-    ) -> RQResult<()> {
+    ) -> RQResult<MessageReceipt> {
         let req = self
             .engine
             .read()
             .await
             .build_share_link_request_packet(ShareTarget::Group(group_code), link_share);
-        let _ = self.send_and_wait(req).await?;
-        Ok(())
+        self.send_share(MessageTarget::Group(group_code), req).await
     }
 
     /// 修改群名片
@@ -489,8 +778,21 @@ impl super::super::Client {
             .read()
             .await
             .build_group_recall_packet(group_code, seqs, rands);
-        let _ = self.send_and_wait(req).await?;
-        Ok(())
+        let resp = self.send_and_wait(req).await?;
+        self.engine
+            .read()
+            .await
+            .decode_msg_withdraw_response(resp.body)
+    }
+
+    /// 通过发送消息返回的 MessageReceipt 撤回群消息
+    pub async fn recall_group_message_by_receipt(
+        &self,
+        group_code: i64,
+        receipt: &MessageReceipt,
+    ) -> RQResult<()> {
+        self.recall_group_message(group_code, receipt.seqs.clone(), receipt.rands.clone())
+            .await
     }
 
     // 用 highway 上传群图片之前调用，获取 upload_key
@@ -517,10 +819,29 @@ impl super::super::Client {
 
     /// 上传群图片
     pub async fn upload_group_image(&self, group_code: i64, data: &[u8]) -> RQResult<GroupImage> {
-        let image_info = ImageInfo::try_new(data)?;
+        self.upload_group_image_stream(group_code, Cursor::new(data), &CancellationToken::new())
+            .await
+    }
 
-        let image_store = self.get_group_image_store(group_code, &image_info).await?;
+    /// 从 reader 上传群图片，不需要把整个文件读入内存，可以通过 cancel 取消上传
+    pub async fn upload_group_image_stream<R: AsyncRead + AsyncSeek + Unpin>(
+        &self,
+        group_code: i64,
+        mut reader: R,
+        cancel: &CancellationToken,
+    ) -> RQResult<GroupImage> {
+        let image_info = ImageInfo::try_from_reader(&mut reader).await?;
         let signature = self.highway_session.read().await.session_key.to_vec();
+        let cache = self.image_cache.read().await.clone();
+        if let Some(mut image) = cache
+            .as_ref()
+            .and_then(|c| c.get_group_image(group_code, &image_info.md5))
+        {
+            image.signature = signature;
+            return Ok(image);
+        }
+
+        let image_store = self.get_group_image_store(group_code, &image_info).await?;
         let group_image = match image_store {
             GroupImageStoreResp::Exist { file_id, addrs } => image_info.into_group_image(
                 file_id,
@@ -532,13 +853,13 @@ impl super::super::Client {
                 upload_key,
                 mut upload_addrs,
             } => {
-                let addr = match self.highway_addrs.read().await.first() {
-                    Some(addr) => *addr,
+                let addr = match self.select_highway_addr().await {
+                    Some(addr) => addr,
                     None => upload_addrs
                         .pop()
                         .ok_or(RQError::EmptyField("upload_addrs"))?,
                 };
-                self.highway_upload_bdh(
+                self.highway_upload_bdh_reader(
                     addr.into(),
                     BdhInput {
                         command_id: 2,
@@ -548,12 +869,18 @@ impl super::super::Client {
                         chunk_size: 256 * 1024,
                         send_echo: true,
                     },
-                    data,
+                    reader,
+                    image_info.size as u64,
+                    image_info.md5.clone(),
+                    cancel,
                 )
                 .await?;
                 image_info.into_group_image(file_id, addr, signature)
             }
         };
+        if let Some(cache) = cache {
+            cache.put_group_image(group_code, &group_image);
+        }
         Ok(group_image)
     }
 
@@ -564,8 +891,24 @@ impl super::super::Client {
         data: &[u8],
         codec: u32,
     ) -> RQResult<GroupAudio> {
-        let md5 = md5::compute(data).to_vec();
-        let size = data.len();
+        self.upload_group_audio_stream(
+            group_code,
+            Cursor::new(data),
+            codec,
+            &CancellationToken::new(),
+        )
+        .await
+    }
+
+    /// 从 reader 上传群音频，不需要把整个文件读入内存，可以通过 cancel 取消上传
+    pub async fn upload_group_audio_stream<R: AsyncRead + AsyncSeek + Unpin>(
+        &self,
+        group_code: i64,
+        mut reader: R,
+        codec: u32,
+        cancel: &CancellationToken,
+    ) -> RQResult<GroupAudio> {
+        let (md5, size) = stream_md5(&mut reader).await?;
         let ext = self.engine.read().await.build_group_try_up_ptt_req(
             group_code,
             md5.clone(),
@@ -574,11 +917,8 @@ impl super::super::Client {
             size as u32,
         );
         let addr = self
-            .highway_addrs
-            .read()
+            .select_highway_addr()
             .await
-            .first()
-            .copied()
             .ok_or(RQError::EmptyField("highway_addrs"))?;
         let ticket = self
             .highway_session
@@ -588,7 +928,7 @@ impl super::super::Client {
             .clone()
             .to_vec();
         let resp = self
-            .highway_upload_bdh(
+            .highway_upload_bdh_reader(
                 addr.into(),
                 BdhInput {
                     command_id: 29,
@@ -598,7 +938,10 @@ impl super::super::Client {
                     chunk_size: 256 * 1024,
                     send_echo: true,
                 },
-                data,
+                reader,
+                size,
+                md5.clone(),
+                cancel,
             )
             .await?;
         let file_key = self
@@ -632,6 +975,42 @@ impl super::super::Client {
         self.engine.read().await.decode_group_ptt_down(resp.body)
     }
 
+    /// 获取群图片下载链接，有 orig_url 时直接使用，否则通过 ImgStore.GroupPicDown 请求
+    pub async fn get_group_image_url(
+        &self,
+        group_code: i64,
+        image: &GroupImage,
+    ) -> RQResult<String> {
+        if image.orig_url.is_some() {
+            return Ok(image.url());
+        }
+        let req = self.engine.read().await.build_group_image_down_packet(
+            group_code,
+            image.file_id,
+            image.md5.clone(),
+            image.size as u64,
+        );
+        let resp = self.send_and_wait(req).await?;
+        self.engine
+            .read()
+            .await
+            .decode_group_image_down_response(resp.body)
+    }
+
+    /// 群语音转文字，返回识别结果
+    pub async fn audio_to_text(&self, group_code: i64, audio: GroupAudio) -> RQResult<String> {
+        let req = self
+            .engine
+            .read()
+            .await
+            .build_group_ptt_trans_req(group_code, &audio.0);
+        let resp = self.send_and_wait(req).await?;
+        self.engine
+            .read()
+            .await
+            .decode_ptt_trans_response(resp.body)
+    }
+
     // 用 highway 上传群视频之前调用，获取 upload_key
     pub async fn get_group_short_video_store(
         &self,
@@ -650,16 +1029,38 @@ impl super::super::Client {
     }
 
     /// 上传群短视频 参数：群号，视频数据，封面数据
-    /// TODO 未来可能会改成输入 std::io::Read
     pub async fn upload_group_short_video(
         &self,
         group_code: i64,
         video_data: &[u8],
         thumb_data: &[u8],
     ) -> RQResult<VideoFile> {
-        let video_md5 = md5::compute(video_data).to_vec();
+        self.upload_group_short_video_stream(
+            group_code,
+            Cursor::new(video_data),
+            thumb_data,
+            &CancellationToken::new(),
+        )
+        .await
+    }
+
+    /// 从 reader 上传群短视频，视频只读取两遍（计算 md5、上传），不会整个读入内存，可以通过 cancel 取消上传
+    pub async fn upload_group_short_video_stream<R: AsyncRead + AsyncSeek + Unpin>(
+        &self,
+        group_code: i64,
+        mut video: R,
+        thumb_data: &[u8],
+        cancel: &CancellationToken,
+    ) -> RQResult<VideoFile> {
+        // 上传的数据为 封面 + 视频，需要同时计算视频和整体的 md5
+        let mut all_ctx = md5::Context::new();
+        all_ctx.consume(thumb_data);
+        let mut ctxs = [md5::Context::new(), all_ctx];
+        let video_size = stream_md5_into(&mut video, &mut ctxs).await? as usize;
+        let [video_ctx, all_ctx] = ctxs;
+        let video_md5 = video_ctx.compute().to_vec();
+        let all_md5 = all_ctx.compute().to_vec();
         let thumb_md5 = md5::compute(thumb_data).to_vec();
-        let video_size = video_data.len();
         let thumb_size = thumb_data.len();
         let short_video_up_req = self.engine.read().await.build_short_video_up_req(
             group_code,
@@ -684,23 +1085,17 @@ impl super::super::Client {
         }
 
         let addr = self
-            .highway_addrs
-            .read()
+            .select_highway_addr()
             .await
-            .first()
-            .copied()
             .ok_or(RQError::EmptyField("highway_addrs"))?;
 
         if self.highway_session.read().await.session_key.is_empty() {
             return Err(RQError::EmptyField("highway_session_key"));
         }
         let ticket = self.highway_session.read().await.sig_session.to_vec();
-        let mut data = Vec::with_capacity(thumb_size + video_size);
-        data.copy_from_slice(thumb_data);
-        data[thumb_size..].copy_from_slice(video_data);
 
         let rsp = self
-            .highway_upload_bdh(
+            .highway_upload_bdh_reader(
                 addr.into(),
                 BdhInput {
                     command_id: 25,
@@ -710,7 +1105,10 @@ impl super::super::Client {
                     chunk_size: 256 * 1024,
                     send_echo: true,
                 },
-                &data,
+                thumb_data.chain(video),
+                (thumb_size + video_size) as u64,
+                all_md5,
+                cancel,
             )
             .await?;
         let rsp = pb::short_video::ShortVideoUploadRsp::decode(&*rsp)
@@ -926,4 +1324,24 @@ impl super::super::Client {
             .await
             .decode_group_file_download_response(resp.body, file_name)
     }
+
+    /// 获取文件的所有下载链接，用于 download_from_any
+    pub async fn get_group_file_download_urls(
+        &self,
+        group_code: i64,
+        file_id: &str,
+        bus_id: u32,
+        file_name: &str,
+    ) -> RQResult<Vec<String>> {
+        let req = self
+            .engine
+            .read()
+            .await
+            .build_group_file_download_request_packet(group_code, file_id.into(), bus_id as i32);
+        let resp = self.send_and_wait(req).await?;
+        self.engine
+            .read()
+            .await
+            .decode_group_file_download_urls_response(resp.body, file_name)
+    }
 }
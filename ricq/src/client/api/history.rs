@@ -0,0 +1,274 @@
+use std::collections::hash_map::Entry;
+use std::collections::HashMap;
+
+use cached::Cached;
+use tokio_util::sync::CancellationToken;
+
+use ricq_core::msg::elem::Reply;
+use ricq_core::msg::MessageChain;
+use ricq_core::pb;
+use ricq_core::structs::{FriendMessage, GroupMessage};
+
+use crate::client::processor::c2c::friend_msg::parse_friend_message_parts;
+use crate::RQResult;
+
+/// 引用回复时最多往前拉取的好友漫游消息条数
+const QUOTED_ROAM_COUNT: u32 = 20;
+
+// 历史消息不分片，撤回的消息没有 body，会被忽略
+fn parse_history_group_message(msg: pb::msg::Message) -> Option<GroupMessage> {
+    let head = msg.head?;
+    let group_info = head.group_info.as_ref()?;
+    let rich_text = msg.body?.rich_text?;
+    Some(GroupMessage {
+        seqs: vec![head.msg_seq()],
+        rands: vec![rich_text
+            .attr
+            .as_ref()
+            .map(|a| a.random())
+            .unwrap_or_default()],
+        group_code: group_info.group_code(),
+        group_name: String::from_utf8_lossy(group_info.group_name()).into_owned(),
+        group_card: String::from_utf8_lossy(group_info.group_card()).into_owned(),
+        from_uin: head.from_uin(),
+        time: head.msg_time(),
+        elements: MessageChain::from(rich_text.elems),
+        quoted: None,
+    })
+}
+
+// 长消息的多个分片按 (from_uin, div_seq) 合并，保持第一个分片出现的顺序
+// 拉取范围边界上不完整的消息只包含拉取到的分片
+fn group_friend_message_parts(msgs: Vec<pb::msg::Message>) -> Vec<Vec<pb::msg::Message>> {
+    let mut groups: Vec<Vec<pb::msg::Message>> = Vec::new();
+    let mut index: HashMap<(i64, i32), usize> = HashMap::new();
+    for msg in msgs {
+        let div = match &msg.content {
+            Some(content) if content.pkg_num() > 1 => Some(content.div_seq()),
+            _ => None,
+        };
+        match div {
+            Some(div_seq) => {
+                let from_uin = msg.head.as_ref().map(|h| h.from_uin()).unwrap_or_default();
+                match index.entry((from_uin, div_seq)) {
+                    Entry::Occupied(entry) => groups[*entry.get()].push(msg),
+                    Entry::Vacant(entry) => {
+                        entry.insert(groups.len());
+                        groups.push(vec![msg]);
+                    }
+                }
+            }
+            None => groups.push(vec![msg]),
+        }
+    }
+    groups
+}
+
+/// 历史消息
+impl super::super::Client {
+    /// 按 seq 范围拉取群历史消息（包含两端）
+    pub async fn get_group_message_history(
+        &self,
+        group_code: i64,
+        begin_seq: i64,
+        end_seq: i64,
+    ) -> RQResult<Vec<GroupMessage>> {
+        self.get_group_message_history_with_cancel(
+            group_code,
+            begin_seq,
+            end_seq,
+            &CancellationToken::new(),
+        )
+        .await
+    }
+
+    /// 可取消的 get_group_message_history，取消后返回 `RQError::Cancelled`
+    pub async fn get_group_message_history_with_cancel(
+        &self,
+        group_code: i64,
+        begin_seq: i64,
+        end_seq: i64,
+        cancel: &CancellationToken,
+    ) -> RQResult<Vec<GroupMessage>> {
+        let req = self
+            .engine
+            .read()
+            .await
+            .build_get_group_msg_request(group_code, begin_seq, end_seq);
+        let resp = self.send_and_wait_with_cancel(req, cancel).await?;
+        let msgs = self
+            .engine
+            .read()
+            .await
+            .decode_get_group_msg_response(resp.body)?;
+        Ok(msgs
+            .into_iter()
+            .filter_map(parse_history_group_message)
+            .collect())
+    }
+
+    /// 拉取好友漫游消息，从 last_msg_time 往前最多 count 条
+    pub async fn get_friend_roam_messages(
+        &self,
+        friend_uin: i64,
+        last_msg_time: i64,
+        count: u32,
+    ) -> RQResult<Vec<FriendMessage>> {
+        self.get_friend_roam_messages_with_cancel(
+            friend_uin,
+            last_msg_time,
+            count,
+            &CancellationToken::new(),
+        )
+        .await
+    }
+
+    /// 可取消的 get_friend_roam_messages，取消后返回 `RQError::Cancelled`
+    pub async fn get_friend_roam_messages_with_cancel(
+        &self,
+        friend_uin: i64,
+        last_msg_time: i64,
+        count: u32,
+        cancel: &CancellationToken,
+    ) -> RQResult<Vec<FriendMessage>> {
+        let req = self.engine.read().await.build_get_one_day_roam_msg_request(
+            friend_uin,
+            last_msg_time,
+            0,
+            count,
+        );
+        let resp = self.send_and_wait_with_cancel(req, cancel).await?;
+        let msgs = self
+            .engine
+            .read()
+            .await
+            .decode_get_one_day_roam_msg_response(resp.body)?;
+        Ok(group_friend_message_parts(msgs)
+            .into_iter()
+            .filter_map(|parts| parse_friend_message_parts(parts).ok())
+            .collect())
+    }
+
+    /// 获取引用回复的群消息原文，优先使用缓存
+    pub async fn get_quoted_group_message(
+        &self,
+        group_code: i64,
+        reply: &Reply,
+    ) -> RQResult<Option<MessageChain>> {
+        let key = (true, group_code, reply.reply_seq);
+        if let Some(chain) = self.quoted_cache.write().await.cache_get(&key) {
+            return Ok(Some(chain.clone()));
+        }
+        let seq = reply.reply_seq as i64;
+        let chain = self
+            .get_group_message_history(group_code, seq, seq)
+            .await?
+            .into_iter()
+            .find(|m| m.seqs.contains(&reply.reply_seq))
+            .map(|m| m.elements);
+        if let Some(chain) = chain.as_ref() {
+            self.quoted_cache
+                .write()
+                .await
+                .cache_set(key, chain.clone());
+        }
+        Ok(chain)
+    }
+
+    /// 获取引用回复的私聊消息原文，优先使用缓存
+    pub async fn get_quoted_friend_message(
+        &self,
+        friend_uin: i64,
+        reply: &Reply,
+    ) -> RQResult<Option<MessageChain>> {
+        let key = (false, friend_uin, reply.reply_seq);
+        if let Some(chain) = self.quoted_cache.write().await.cache_get(&key) {
+            return Ok(Some(chain.clone()));
+        }
+        // 漫游消息按时间往前拉取，+1 包含引用消息所在的那一秒
+        let chain = self
+            .get_friend_roam_messages(friend_uin, reply.time as i64 + 1, QUOTED_ROAM_COUNT)
+            .await?
+            .into_iter()
+            .find(|m| m.seqs.contains(&reply.reply_seq))
+            .map(|m| m.elements);
+        if let Some(chain) = chain.as_ref() {
+            self.quoted_cache
+                .write()
+                .await
+                .cache_set(key, chain.clone());
+        }
+        Ok(chain)
+    }
+
+    /// 开启 resolve_quoted_messages 时由消息处理调用，只读取缓存，不发起网络请求，
+    /// 缓存中没有时使用引用元素中附带的内容。需要完整原文时调用事件的 `quoted_message`
+    pub(crate) async fn resolve_quoted(
+        &self,
+        target: i64,
+        elements: &MessageChain,
+        group: bool,
+    ) -> Option<MessageChain> {
+        let reply = elements.reply()?;
+        let key = (group, target, reply.reply_seq);
+        if let Some(chain) = self.quoted_cache.write().await.cache_get(&key) {
+            return Some(chain.clone());
+        }
+        Some(reply.elements)
+    }
+
+    /// 开启 resolve_quoted_messages 时缓存收到的消息，之后引用这条消息时不需要再拉取
+    pub(crate) async fn cache_quoted(
+        &self,
+        target: i64,
+        seqs: &[i32],
+        elements: &MessageChain,
+        group: bool,
+    ) {
+        let mut cache = self.quoted_cache.write().await;
+        for seq in seqs {
+            cache.cache_set((group, target, *seq), elements.clone());
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn part(from_uin: i64, seq: i32, content: Option<(i32, i32, i32)>) -> pb::msg::Message {
+        pb::msg::Message {
+            head: Some(pb::msg::MessageHead {
+                from_uin: Some(from_uin),
+                msg_seq: Some(seq),
+                ..Default::default()
+            }),
+            content: content.map(|(pkg_num, pkg_index, div_seq)| pb::msg::ContentHead {
+                pkg_num: Some(pkg_num),
+                pkg_index: Some(pkg_index),
+                div_seq: Some(div_seq),
+                ..Default::default()
+            }),
+            body: None,
+        }
+    }
+
+    #[test]
+    fn test_group_friend_message_parts() {
+        let groups = group_friend_message_parts(vec![
+            part(1, 10, Some((2, 1, 7))),
+            part(1, 11, None),
+            part(1, 12, Some((2, 0, 7))),
+            part(2, 13, Some((2, 0, 7))),
+        ]);
+        let seqs: Vec<Vec<i32>> = groups
+            .iter()
+            .map(|g| {
+                g.iter()
+                    .map(|m| m.head.as_ref().unwrap().msg_seq())
+                    .collect()
+            })
+            .collect();
+        assert_eq!(seqs, vec![vec![10, 12], vec![11], vec![13]]);
+    }
+}
@@ -1,11 +1,13 @@
 use std::sync::atomic::Ordering;
 
+use crate::client::event::LoginStage;
 use crate::jce::SvcRespRegister;
 use crate::qsign::QSignClient;
 use crate::{RQError, RQResult};
 use ricq_core::command::wtlogin::*;
 use ricq_core::hex::decode_hex;
-use ricq_core::token::Token;
+use ricq_core::token::{A1Token, Token};
+use tokio_util::sync::CancellationToken;
 
 /// 登录相关
 impl super::super::Client {
@@ -51,6 +53,10 @@ impl super::super::Client {
                 .read()
                 .await
                 .build_qrcode_login_packet(tmp_pwd, tmp_no_pic_sig, tgt_qr);
+        self.report_login_progress(LoginStage::Authenticating {
+            method: "qrcode".into(),
+        })
+        .await;
         let resp = self.send_and_wait(req).await?;
         let resp = self.engine.read().await.decode_login_response(resp.body)?;
         self.process_login_response(&resp).await;
@@ -67,6 +73,9 @@ impl super::super::Client {
             &engine.transport.version.sdk_version,
             sub_cmd as u32,
         );
+        if let Some(provider) = self.energy_provider.read().await.as_ref() {
+            return provider.energy(uin, data, &salt);
+        }
         let resp = self
             .qsign_client
             .custom_energy(
@@ -90,6 +99,17 @@ impl super::super::Client {
         &self,
         uin: i64,
         password_md5: &[u8],
+    ) -> RQResult<LoginResponse> {
+        self.password_md5_login_with_cancel(uin, password_md5, &CancellationToken::new())
+            .await
+    }
+
+    /// 可取消的 password_md5_login，取消后不再等待响应或计算 T546 PoW，返回 `RQError::Cancelled`
+    pub async fn password_md5_login_with_cancel(
+        &self,
+        uin: i64,
+        password_md5: &[u8],
+        cancel: &CancellationToken,
     ) -> RQResult<LoginResponse> {
         self.engine.read().await.uin.store(uin, Ordering::Relaxed);
         let sign = self.sign("810_9").await?;
@@ -98,8 +118,16 @@ impl super::super::Client {
             .read()
             .await
             .build_login_packet(password_md5, &sign, true);
-        let resp = self.send_and_wait(req).await?;
-        let resp = self.engine.read().await.decode_login_response(resp.body)?;
+        self.report_login_progress(LoginStage::Authenticating {
+            method: "password".into(),
+        })
+        .await;
+        let resp = self.send_and_wait_with_cancel(req, cancel).await?;
+        let resp = self
+            .engine
+            .read()
+            .await
+            .decode_login_response_with_cancel(resp.body, &|| cancel.is_cancelled())?;
         self.process_login_response(&resp).await;
         Ok(resp)
     }
@@ -115,6 +143,12 @@ impl super::super::Client {
         let resp = self.send_and_wait(req).await?;
         let resp = self.engine.read().await.decode_login_response(resp.body)?;
         self.process_login_response(&resp).await;
+        if let LoginResponse::DeviceLocked(ref locked) = resp {
+            self.report_login_progress(LoginStage::SmsSent {
+                phone: locked.sms_phone.clone(),
+            })
+            .await;
+        }
         Ok(resp)
     }
 
@@ -126,6 +160,10 @@ impl super::super::Client {
             .read()
             .await
             .build_sms_code_submit_packet(code.trim(), &sign);
+        self.report_login_progress(LoginStage::Authenticating {
+            method: "sms".into(),
+        })
+        .await;
         let resp = self.send_and_wait(req).await?;
         let resp = self.engine.read().await.decode_login_response(resp.body)?;
         self.process_login_response(&resp).await;
@@ -140,6 +178,10 @@ impl super::super::Client {
             .read()
             .await
             .build_ticket_submit_packet(ticket, &sign);
+        self.report_login_progress(LoginStage::Authenticating {
+            method: "ticket".into(),
+        })
+        .await;
         let resp = self.send_and_wait(req).await?;
         let resp = self.engine.read().await.decode_login_response(resp.body)?;
         self.process_login_response(&resp).await;
@@ -149,6 +191,10 @@ impl super::super::Client {
     /// 设备锁登录 - 二维码、密码登录都需要
     pub async fn device_lock_login(&self) -> RQResult<LoginResponse> {
         let req = self.engine.read().await.build_device_lock_login_packet();
+        self.report_login_progress(LoginStage::Authenticating {
+            method: "device_lock".into(),
+        })
+        .await;
         let resp = self.send_and_wait(req).await?;
         let resp = self.engine.read().await.decode_login_response(resp.body)?;
         self.process_login_response(&resp).await;
@@ -161,6 +207,28 @@ impl super::super::Client {
         self.request_change_sig(None).await
     }
 
+    /// 快速登录：使用保存的 encrypted A1 换取新的登录凭证，不需要密码，成功后需要 register
+    pub async fn fast_login(&self, token: A1Token) -> RQResult<LoginResponse> {
+        if token.encrypted_a1.is_empty() {
+            return Err(RQError::EmptyField("encrypted_a1"));
+        }
+        self.engine.write().await.load_a1_token(token);
+        self.report_login_progress(LoginStage::ExchangingKeys).await;
+        let req = self
+            .engine
+            .read()
+            .await
+            .build_request_tgtgt_no_pic_sig_packet();
+        let resp = self.send_and_wait(req).await?;
+        let resp = self
+            .engine
+            .read()
+            .await
+            .decode_exchange_emp_response(resp.body)?;
+        self.process_login_response(&resp).await;
+        Ok(resp)
+    }
+
     /// 换 token，使用后需要重新 register
     pub async fn request_change_sig(&self, main_sig_map: Option<u32>) -> RQResult<LoginResponse> {
         let req = self
@@ -168,6 +236,7 @@ impl super::super::Client {
             .read()
             .await
             .build_request_change_sig_packet(main_sig_map);
+        self.report_login_progress(LoginStage::ExchangingKeys).await;
         let resp = self.send_and_wait(req).await?;
         let resp = self
             .engine
@@ -178,6 +247,50 @@ impl super::super::Client {
         Ok(resp)
     }
 
+    /// 刷新 skey、pskey 等登录凭证并重新注册，与服务器推送 sid 过期时的处理相同
+    pub async fn refresh_sig(&self) -> RQResult<()> {
+        if !matches!(
+            self.request_change_sig(Some(3554528)).await?,
+            LoginResponse::Success(_)
+        ) {
+            return Err(RQError::Other("refresh sig: login required".into()));
+        }
+        self.register_client().await?;
+        Ok(())
+    }
+
+    /// 获取指定域名（如 qun.qq.com、qzone.qq.com）的 cookie，skey 过期时自动刷新
+    ///
+    /// 并发调用时只有一个会刷新，其他的等待刷新完成后直接读取。
+    /// 登录时没有获取该域名的 pskey 时返回错误，不会刷新
+    pub async fn get_cookies(&self, domain: &str) -> RQResult<String> {
+        if let Some(cookies) = self.cookies_if_valid(domain).await {
+            return cookies;
+        }
+        let _guard = self.sig_refreshing.lock().await;
+        // 等待锁期间可能已经被其他任务刷新
+        if let Some(cookies) = self.cookies_if_valid(domain).await {
+            return cookies;
+        }
+        self.refresh_sig().await?;
+        self.cookies_if_valid(domain)
+            .await
+            .unwrap_or(Err(RQError::Other("skey expired after refresh".into())))
+    }
+
+    /// skey 过期时返回 None
+    async fn cookies_if_valid(&self, domain: &str) -> Option<RQResult<String>> {
+        let engine = self.engine.read().await;
+        if engine.is_s_key_expired() {
+            return None;
+        }
+        Some(
+            engine
+                .cookies(domain)
+                .ok_or_else(|| RQError::Other(format!("no pskey for domain {domain}"))),
+        )
+    }
+
     /// 注册客户端，登录后必须注册
     pub async fn register_client(&self) -> RQResult<SvcRespRegister> {
         let req = self.engine.read().await.build_client_register_packet();
@@ -191,6 +304,18 @@ impl super::super::Client {
             return Err(RQError::Other(resp.result + &resp.reply_code.to_string()));
         }
         self.online.store(true, Ordering::SeqCst);
+        {
+            let mut account_info = self.account_info.write().await;
+            account_info.online_status = resp.status;
+            account_info.ext_online_status = resp.ext_online_status;
+        }
+        let status = self.online_status.read().await.clone();
+        if let Some(status) = status {
+            // 注册已经成功，恢复在线状态失败不影响登录
+            if let Err(err) = self.update_online_status(status).await {
+                tracing::warn!("failed to restore online status: {}", err);
+            }
+        }
         Ok(resp)
     }
 
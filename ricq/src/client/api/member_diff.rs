@@ -0,0 +1,93 @@
+use std::sync::Arc;
+use std::time::Instant;
+
+use ricq_core::structs::{GroupMemberInfo, MemberInfoChange};
+
+use crate::client::event::MemberInfoChangeEvent;
+use crate::config::MemberDiffOptions;
+use crate::handler::QEvent;
+use crate::RQResult;
+
+pub(crate) struct MemberDiffState {
+    options: MemberDiffOptions,
+    /// 上次拉取的成员列表，第一次比对前为 None
+    members: Option<Arc<Vec<GroupMemberInfo>>>,
+    last_diff: Option<Instant>,
+}
+
+/// 群成员比对，用于发现服务器不推送的变化（群名片、头衔、权限、静默退群等）
+impl super::super::Client {
+    /// 开始比对指定群的成员列表，重复调用时只更新参数
+    pub async fn watch_group_members(&self, group_code: i64, options: MemberDiffOptions) {
+        self.member_diff
+            .write()
+            .await
+            .entry(group_code)
+            .and_modify(|state| state.options = options.clone())
+            .or_insert(MemberDiffState {
+                options,
+                members: None,
+                last_diff: None,
+            });
+    }
+
+    pub async fn unwatch_group_members(&self, group_code: i64) {
+        self.member_diff.write().await.remove(&group_code);
+    }
+
+    /// 重新拉取成员列表并与上次比较，变化会作为 MemberInfoChange 事件分发
+    ///
+    /// 群需要先调用 watch_group_members，第一次调用只记录成员列表
+    pub async fn diff_group_members(
+        self: &Arc<Self>,
+        group_code: i64,
+    ) -> RQResult<Vec<MemberInfoChange>> {
+        if !self.member_diff.read().await.contains_key(&group_code) {
+            return Ok(Vec::new());
+        }
+        let members = self.refresh_group_members(group_code).await?;
+        let (old, options) = {
+            let mut diff = self.member_diff.write().await;
+            // 拉取期间可能已经 unwatch
+            let state = match diff.get_mut(&group_code) {
+                Some(state) => state,
+                None => return Ok(Vec::new()),
+            };
+            state.last_diff = Some(Instant::now());
+            (
+                state.members.replace(members.clone()),
+                state.options.clone(),
+            )
+        };
+        let changes: Vec<MemberInfoChange> = match old {
+            Some(old) => MemberInfoChange::diff(&old, &members)
+                .into_iter()
+                .filter(|c| options.accepts(&c.change))
+                .collect(),
+            None => Vec::new(),
+        };
+        for change in changes.iter() {
+            self.handler
+                .handle(QEvent::MemberInfoChange(MemberInfoChangeEvent {
+                    client: self.clone(),
+                    inner: Arc::new(change.clone()),
+                }))
+                .await;
+        }
+        Ok(changes)
+    }
+
+    /// 距离上次比对已超过 interval 的群
+    pub(crate) async fn member_diff_due_groups(&self) -> Vec<i64> {
+        self.member_diff
+            .read()
+            .await
+            .iter()
+            .filter(|(_, state)| match state.last_diff {
+                Some(last) => last.elapsed() >= state.options.interval,
+                None => true,
+            })
+            .map(|(group_code, _)| *group_code)
+            .collect()
+    }
+}
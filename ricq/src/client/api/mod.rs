@@ -1,5 +1,6 @@
 use std::net::SocketAddr;
 use std::sync::atomic::Ordering;
+use std::sync::Arc;
 use std::time::UNIX_EPOCH;
 
 use bytes::Bytes;
@@ -9,22 +10,51 @@ use ricq_core::command::message_svc::MessageSyncResponse;
 use ricq_core::command::oidb_svc::*;
 use ricq_core::common::{group_code2uin, RQAddr};
 use ricq_core::highway::BdhInput;
+use ricq_core::msg::elem::RichMsg;
 use ricq_core::msg::MessageChain;
 use ricq_core::pb;
 use ricq_core::structs::Status;
-use ricq_core::structs::SummaryCardInfo;
-use ricq_core::structs::{ForwardMessage, MessageReceipt};
+use ricq_core::structs::{
+    AccountInfo, AccountInfoChange, OnlineDevice, StrangerInfo, SummaryCardInfo,
+};
+use ricq_core::structs::TempSession;
+use ricq_core::structs::{ForwardMessage, MessageReceipt, MessageTarget};
 
+use crate::client::event::AccountInfoChangeEvent;
+use crate::handler::QEvent;
 use crate::jce::SvcDevLoginInfo;
 use crate::{RQError, RQResult};
 
+#[cfg(feature = "web-api")]
+mod album;
+mod broadcast;
+mod download;
 mod friend;
 mod group;
+mod history;
 mod login;
+mod member_diff;
+mod receipt;
+mod template;
+#[cfg(feature = "web-api")]
+mod web;
+
+pub(crate) use member_diff::MemberDiffState;
 
 /// API
 impl super::Client {
-    /// 设置在线状态 TODO net_type
+    /// 设置在线状态（隐身、忙碌、Q我吧、离开、请勿打扰等），断线重连注册后自动恢复
+    pub async fn set_online_status<T>(&self, status: T) -> RQResult<()>
+    where
+        T: Into<Status>,
+    {
+        let status = status.into();
+        self.update_online_status(status.clone()).await?;
+        *self.online_status.write().await = Some(status);
+        Ok(())
+    }
+
+    /// 设置在线状态，仅对本次注册有效 TODO net_type
     pub async fn update_online_status<T>(&self, status: T) -> RQResult<()>
     where
         T: Into<Status>,
@@ -35,12 +65,20 @@ impl super::Client {
                 return Err(RQError::Other("invalid wording length".into()));
             }
         }
-        let req = self.engine.read().await.build_set_online_status_packet(
-            status.online_status,
-            status.ext_online_status,
-            status.custom_status,
-        );
+        let req = self
+            .engine
+            .read()
+            .await
+            .build_set_online_status_packet_with_battery(
+                status.online_status,
+                status.ext_online_status,
+                status.custom_status,
+                status.battery_status,
+            );
         let _ = self.send_and_wait(req).await?;
+        let mut account_info = self.account_info.write().await;
+        account_info.online_status = status.online_status;
+        account_info.ext_online_status = status.ext_online_status;
         Ok(())
     }
 
@@ -84,6 +122,40 @@ impl super::Client {
         self.engine.read().await.decode_dev_list_response(resp.body)
     }
 
+    /// 获取当前账号在线的其他设备
+    pub async fn get_online_devices(&self) -> RQResult<Vec<OnlineDevice>> {
+        let req = self
+            .engine
+            .read()
+            .await
+            .build_online_device_list_request_packet();
+        let resp = self.send_and_wait(req).await?;
+        self.engine
+            .read()
+            .await
+            .decode_online_device_list_response(resp.body)
+    }
+
+    /// 将其他在线设备踢下线，device 通过 get_online_devices 获取
+    pub async fn kick_device(&self, device: &OnlineDevice) -> RQResult<()> {
+        if !device.can_be_kicked {
+            return Err(RQError::Other(format!(
+                "device {} can not be kicked",
+                device.device_name
+            )));
+        }
+        let req = self
+            .engine
+            .read()
+            .await
+            .build_kick_device_request_packet(device.app_id, Bytes::from(device.guid.clone()));
+        let resp = self.send_and_wait(req).await?;
+        self.engine
+            .read()
+            .await
+            .decode_kick_device_response(resp.body)
+    }
+
     /// 文本翻译
     pub async fn translate(
         &self,
@@ -127,6 +199,21 @@ impl super::Client {
         Ok(())
     }
 
+    /// 给用户点赞并检查结果，每日次数用完时返回 RQError::LikeLimitExceeded
+    pub async fn like_user(&self, uin: i64, count: i32) -> RQResult<()> {
+        let cookies = self.get_summary_info(uin).await?.cookie;
+        let req = self
+            .engine
+            .read()
+            .await
+            .build_send_like_packet(uin, count, 1, cookies);
+        let resp = self.send_and_wait(req).await?;
+        self.engine
+            .read()
+            .await
+            .decode_send_like_response(resp.body)
+    }
+
     // TODO 待完善
     // 图片 OCR
     pub async fn image_ocr(
@@ -267,6 +354,18 @@ impl super::Client {
         Ok(msgs)
     }
 
+    /// 拉取离线期间的私聊消息并按普通事件分发，offline 为 true，返回拉取到的消息数
+    ///
+    /// 从 sync_cookie 记录的位置开始同步，重启后需要先 load_token 或 set_sync_cookie
+    pub async fn sync_offline_messages(self: &Arc<Self>) -> RQResult<usize> {
+        let _guard = self.offline_sync.lock.lock().await;
+        self.offline_sync.take();
+        let msgs = self.sync_all_message().await?;
+        let count = msgs.len();
+        self.process_message_sync(msgs, true).await;
+        Ok(count)
+    }
+
     // 获取名片信息
     pub async fn get_summary_info(&self, uin: i64) -> RQResult<SummaryCardInfo> {
         let req = self
@@ -281,6 +380,38 @@ impl super::Client {
             .decode_summary_card_response(resp.body)
     }
 
+    /// 获取陌生人信息，不需要是好友
+    pub async fn get_stranger_info(&self, uin: i64) -> RQResult<StrangerInfo> {
+        Ok(self.get_summary_info(uin).await?.into())
+    }
+
+    /// 从服务器重新获取自己的昵称、年龄、性别和在线状态，有变化时发出 AccountInfoChange 事件
+    pub async fn refresh_account_info(self: &Arc<Self>) -> RQResult<AccountInfo> {
+        let old = self.account_info.read().await.clone();
+        let card = self.get_summary_info(self.uin().await).await?;
+        // 在线状态只在注册客户端的返回中
+        self.register_client().await?;
+        let new = {
+            let mut account_info = self.account_info.write().await;
+            account_info.nickname = card.nickname;
+            account_info.age = card.age;
+            account_info.gender = card.sex;
+            account_info.clone()
+        };
+        if new != old {
+            self.handler
+                .handle(QEvent::AccountInfoChange(AccountInfoChangeEvent {
+                    client: self.clone(),
+                    inner: Arc::new(AccountInfoChange {
+                        old,
+                        new: new.clone(),
+                    }),
+                }))
+                .await;
+        }
+        Ok(new)
+    }
+
     // 准备上传消息，获取 ukey, resid, ip, port
     async fn multi_msg_apply_up(
         &self,
@@ -396,6 +527,59 @@ impl super::Client {
         todo!()
     }
 
+    /// 分享好友名片到好友或群
+    pub async fn send_friend_card(
+        &self,
+        target: ShareTarget,
+        uin: i64,
+    ) -> RQResult<MessageReceipt> {
+        let info = self.get_summary_info(uin).await?;
+        let card = RichMsg::friend_card(uin, &info.nickname);
+        self.send_to_share_target(target, MessageChain::new(card))
+            .await
+    }
+
+    /// 分享群名片到好友或群
+    pub async fn send_group_card(
+        &self,
+        target: ShareTarget,
+        group_code: i64,
+    ) -> RQResult<MessageReceipt> {
+        let info = self
+            .get_group_info(group_code)
+            .await?
+            .ok_or(RQError::EmptyField("group_info"))?;
+        let card = RichMsg::group_card(group_code, &info.name);
+        self.send_to_share_target(target, MessageChain::new(card))
+            .await
+    }
+
+    async fn send_to_share_target(
+        &self,
+        target: ShareTarget,
+        message_chain: MessageChain,
+    ) -> RQResult<MessageReceipt> {
+        match target {
+            ShareTarget::Friend(uin) => self.send_friend_message(uin, message_chain).await,
+            ShareTarget::Group(group_code) => {
+                self.send_group_message(group_code, message_chain).await
+            }
+            ShareTarget::Guild { .. } => Err(RQError::Other(
+                "contact card is not supported in guild".into(),
+            )),
+        }
+    }
+
+    /// 发送临时会话消息，session 来自 TempMessage
+    pub async fn send_temp_message(
+        &self,
+        session: &TempSession,
+        message_chain: MessageChain,
+    ) -> RQResult<MessageReceipt> {
+        self.send_message(session.routing_head(), message_chain, None)
+            .await
+    }
+
     /// 发送消息
     pub async fn send_message(
         &self,
@@ -403,6 +587,7 @@ impl super::Client {
         message_chain: MessageChain,
         ptt: Option<pb::msg::Ptt>,
     ) -> RQResult<MessageReceipt> {
+        let target = MessageTarget::from_routing_head(&routing_head);
         let time = UNIX_EPOCH.elapsed().unwrap().as_secs() as i64;
         let seq = self.engine.read().await.next_friend_seq();
         let ran = (rand::random::<u32>() >> 1) as i32;
@@ -418,11 +603,24 @@ impl super::Client {
             ran,
             time,
         );
-        self.send_and_wait(req).await?;
+        let sent = async {
+            let resp = self.send_and_wait(req).await?;
+            self.engine
+                .read()
+                .await
+                .decode_send_message_response(resp.body)
+        }
+        .await;
+        if let Err(err) = sent {
+            self.receipt_waiters.lock().await.cache_remove(&ran);
+            return Err(err);
+        }
         let receipt = MessageReceipt {
+            target,
             seqs: vec![seq],
             rands: vec![ran],
-            time: UNIX_EPOCH.elapsed().unwrap().as_secs() as i64,
+            // 撤回好友消息时需要与发送时间一致
+            time,
         };
         // 除了群聊，都不需要等 receipt 的 seq
         Ok(receipt)
@@ -0,0 +1,148 @@
+use std::time::{Duration, UNIX_EPOCH};
+
+use cached::Cached;
+
+use ricq_core::msg::MessageChain;
+use ricq_core::pb;
+use ricq_core::protocol::packet::Packet;
+use ricq_core::structs::{MessageReceipt, MessageTarget};
+
+use crate::{RQError, RQResult};
+
+/// 按 MessageReceipt::target 统一发送、回复、撤回，不需要区分好友和群
+impl super::super::Client {
+    /// 发送消息到 target，频道消息暂不支持
+    pub async fn send_message_to(
+        &self,
+        target: MessageTarget,
+        message_chain: MessageChain,
+    ) -> RQResult<MessageReceipt> {
+        match target {
+            MessageTarget::Friend(uin) => self.send_friend_message(uin, message_chain).await,
+            MessageTarget::Group(group_code) => {
+                self.send_group_message(group_code, message_chain).await
+            }
+            MessageTarget::Temp { group_code, uin } if group_code != 0 => {
+                self.send_group_temp_message(group_code, uin, message_chain)
+                    .await
+            }
+            _ => Err(unsupported("send", &target)),
+        }
+    }
+
+    /// 引用自己发送的消息回复，elements 为原消息内容
+    pub async fn reply_to_receipt(
+        &self,
+        receipt: &MessageReceipt,
+        elements: MessageChain,
+        mut message_chain: MessageChain,
+    ) -> RQResult<MessageReceipt> {
+        message_chain.with_reply(receipt.reply(self.uin().await, elements));
+        self.send_message_to(receipt.target, message_chain).await
+    }
+
+    /// 撤回自己发送的消息
+    pub async fn recall_message(&self, receipt: &MessageReceipt) -> RQResult<()> {
+        match receipt.target {
+            MessageTarget::Friend(uin) => self.recall_friend_message_by_receipt(uin, receipt).await,
+            MessageTarget::Group(group_code) => {
+                self.recall_group_message_by_receipt(group_code, receipt)
+                    .await
+            }
+            target => Err(unsupported("recall", &target)),
+        }
+    }
+
+    /// 设置或取消精华消息，只支持群消息
+    pub async fn set_essence_by_receipt(
+        &self,
+        receipt: &MessageReceipt,
+        flag: bool,
+    ) -> RQResult<pb::oidb::EacRspBody> {
+        match receipt.target {
+            MessageTarget::Group(group_code) => {
+                self.operate_group_essence(group_code, receipt.seq(), receipt.rand(), flag)
+                    .await
+            }
+            target => Err(unsupported("essence", &target)),
+        }
+    }
+}
+
+/// 分享消息
+impl super::super::Client {
+    /// 发送音乐、链接等分享，回执从自身消息的推送中获取，超时未收到时 seqs 为空
+    pub(crate) async fn send_share(
+        &self,
+        target: MessageTarget,
+        req: Packet,
+    ) -> RQResult<MessageReceipt> {
+        let (tx, rx) = tokio::sync::oneshot::channel();
+        self.share_waiters.lock().await.cache_set(target, tx);
+        if let Err(err) = self.send_and_wait(req).await {
+            self.share_waiters.lock().await.cache_remove(&target);
+            return Err(err);
+        }
+        match tokio::time::timeout(Duration::from_secs(5), rx).await {
+            Ok(Ok(receipt)) => Ok(receipt),
+            Ok(Err(_)) | Err(_) => {
+                if self
+                    .share_waiters
+                    .lock()
+                    .await
+                    .cache_remove(&target)
+                    .is_some()
+                {
+                    self.late_shares.lock().await.cache_set(target, ());
+                }
+                tracing::warn!("share message receipt not received, target: {:?}", target);
+                Ok(MessageReceipt {
+                    target,
+                    time: UNIX_EPOCH.elapsed().unwrap().as_secs() as i64,
+                    ..Default::default()
+                })
+            }
+        }
+    }
+
+    /// 收到自身发送的分享消息时交给 send_share，返回是否有等待的分享（或是等待超时的分享）
+    pub(crate) async fn complete_share<'a>(
+        &self,
+        receipt: MessageReceipt,
+        mut elems: impl Iterator<Item = &'a pb::msg::elem::Elem>,
+    ) -> bool {
+        let is_share = elems.any(|e| {
+            matches!(
+                e,
+                pb::msg::elem::Elem::RichMsg(_) | pb::msg::elem::Elem::LightApp(_)
+            )
+        });
+        if !is_share {
+            return false;
+        }
+        match self
+            .share_waiters
+            .lock()
+            .await
+            .cache_remove(&receipt.target)
+        {
+            Some(tx) => {
+                let _ = tx.send(receipt);
+                true
+            }
+            None => self
+                .late_shares
+                .lock()
+                .await
+                .cache_remove(&receipt.target)
+                .is_some(),
+        }
+    }
+}
+
+fn unsupported(operation: &str, target: &MessageTarget) -> RQError {
+    RQError::Other(format!(
+        "{operation} is not supported for {:?} message",
+        target.kind()
+    ))
+}
@@ -0,0 +1,96 @@
+use std::path::{Component, Path, PathBuf};
+
+use ricq_core::msg::template::{parse_template, TemplateToken};
+use ricq_core::msg::MessageChain;
+
+use crate::{RQError, RQResult};
+
+#[derive(Clone, Copy)]
+enum TemplateTarget {
+    Group(i64),
+    Friend(i64),
+}
+
+/// 消息模板，除 `MessageChain::from_template` 支持的占位符外，
+/// 还支持 `{image:path}` 从 `ClientOptions::template_image_dir` 目录上传图片
+impl super::super::Client {
+    /// 从模板构造群消息
+    pub async fn group_chain_from_template(
+        &self,
+        group_code: i64,
+        template: &str,
+    ) -> RQResult<MessageChain> {
+        self.chain_from_template(TemplateTarget::Group(group_code), template)
+            .await
+    }
+
+    /// 从模板构造好友消息
+    pub async fn friend_chain_from_template(
+        &self,
+        uin: i64,
+        template: &str,
+    ) -> RQResult<MessageChain> {
+        self.chain_from_template(TemplateTarget::Friend(uin), template)
+            .await
+    }
+
+    async fn chain_from_template(
+        &self,
+        target: TemplateTarget,
+        template: &str,
+    ) -> RQResult<MessageChain> {
+        let mut chain = MessageChain::default();
+        for token in parse_template(template)? {
+            match token {
+                TemplateToken::Placeholder { kind, arg } if kind == "image" => {
+                    let base =
+                        self.options.template_image_dir.as_deref().ok_or_else(|| {
+                            RQError::Other("template_image_dir is not set".into())
+                        })?;
+                    let path = template_image_path(base, &arg)?;
+                    let data = tokio::fs::read(&path).await.map_err(RQError::IO)?;
+                    match target {
+                        TemplateTarget::Group(code) => {
+                            chain.push(self.upload_group_image(code, &data).await?)
+                        }
+                        TemplateTarget::Friend(uin) => {
+                            chain.push(self.upload_friend_image(uin, &data).await?)
+                        }
+                    }
+                }
+                token => chain.push_template_token(token)?,
+            }
+        }
+        Ok(chain)
+    }
+}
+
+/// 只允许 base 下的相对路径，拒绝绝对路径和 `..`
+fn template_image_path(base: &Path, arg: &str) -> RQResult<PathBuf> {
+    let path = Path::new(arg);
+    if arg.is_empty() || !path.components().all(|c| matches!(c, Component::Normal(_))) {
+        return Err(RQError::Other(format!(
+            "invalid template image path: {arg}"
+        )));
+    }
+    Ok(base.join(path))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_template_image_path() {
+        let base = Path::new("images");
+        assert_eq!(
+            template_image_path(base, "a/b.png").unwrap(),
+            Path::new("images/a/b.png")
+        );
+        assert!(template_image_path(base, "../secret").is_err());
+        assert!(template_image_path(base, "a/../../secret").is_err());
+        assert!(template_image_path(base, "/etc/passwd").is_err());
+        assert!(template_image_path(base, "./a.png").is_err());
+        assert!(template_image_path(base, "").is_err());
+    }
+}
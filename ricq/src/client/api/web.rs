@@ -0,0 +1,263 @@
+use std::collections::HashMap;
+
+use serde::de::DeserializeOwned;
+use serde::Deserialize;
+
+use crate::structs::{
+    GroupEssenceMessage, GroupHonorInfo, GroupHonorType, GroupLevelRanking, GroupMemberActivity,
+    GroupNotice,
+};
+use crate::{RQError, RQResult};
+
+#[derive(Deserialize)]
+struct NoticeListRsp {
+    #[serde(default)]
+    ec: i32,
+    #[serde(default)]
+    em: String,
+    #[serde(default)]
+    feeds: Vec<GroupNotice>,
+}
+
+#[derive(Deserialize)]
+struct NoticeOperateRsp {
+    #[serde(default)]
+    ec: i32,
+    #[serde(default)]
+    em: String,
+    #[serde(default)]
+    new_fid: String,
+}
+
+#[derive(Deserialize)]
+struct EssenceListRsp {
+    #[serde(default)]
+    retcode: i32,
+    #[serde(default)]
+    retmsg: String,
+    data: Option<EssenceListData>,
+}
+
+#[derive(Deserialize)]
+struct EssenceListData {
+    msg_list: Option<Vec<GroupEssenceMessage>>,
+    #[serde(default)]
+    is_end: bool,
+}
+
+#[derive(Deserialize)]
+struct SearchMembersRsp {
+    #[serde(default)]
+    ec: i32,
+    #[serde(default)]
+    em: String,
+    #[serde(default)]
+    count: usize,
+    #[serde(default)]
+    mems: Vec<GroupMemberActivity>,
+    #[serde(default)]
+    levelname: HashMap<String, String>,
+}
+
+const SEARCH_MEMBERS_PAGE_SIZE: usize = 40;
+
+/// 基于 qun.qq.com 网页接口的实现，用于协议包不稳定或缺失的群操作
+impl super::super::Client {
+    /// 返回 qun.qq.com 的 cookie 和 bkn
+    async fn qun_cookies(&self) -> RQResult<(String, i64)> {
+        let cookies = self.get_cookies("qun.qq.com").await?;
+        Ok((cookies, self.engine.read().await.bkn()))
+    }
+
+    async fn web_get<T: DeserializeOwned>(&self, cookies: String, url: String) -> RQResult<T> {
+        self.http_client()
+            .await
+            .get(url)
+            .header(reqwest::header::COOKIE, cookies)
+            .send()
+            .await
+            .map_err(|e| RQError::Other(e.to_string()))?
+            .json()
+            .await
+            .map_err(|e| RQError::Other(e.to_string()))
+    }
+
+    async fn web_post_form<T: DeserializeOwned>(
+        &self,
+        cookies: String,
+        url: String,
+        form: &[(&str, String)],
+    ) -> RQResult<T> {
+        self.http_client()
+            .await
+            .post(url)
+            .header(reqwest::header::COOKIE, cookies)
+            .form(form)
+            .send()
+            .await
+            .map_err(|e| RQError::Other(e.to_string()))?
+            .json()
+            .await
+            .map_err(|e| RQError::Other(e.to_string()))
+    }
+
+    /// 获取群公告 (网页接口)
+    pub async fn web_get_group_notices(&self, group_code: i64) -> RQResult<Vec<GroupNotice>> {
+        let (cookies, bkn) = self.qun_cookies().await?;
+        let url = format!(
+            "https://web.qun.qq.com/cgi-bin/announce/get_t_list?bkn={bkn}&qid={group_code}&ft=23&s=-1&n=20"
+        );
+        let rsp: NoticeListRsp = self.web_get(cookies, url).await?;
+        if rsp.ec != 0 {
+            return Err(RQError::Other(format!("get group notices: {}", rsp.em)));
+        }
+        Ok(rsp.feeds)
+    }
+
+    /// 发布群公告 (网页接口)，返回公告 id
+    pub async fn web_send_group_notice(&self, group_code: i64, text: String) -> RQResult<String> {
+        let (cookies, bkn) = self.qun_cookies().await?;
+        let url = format!("https://web.qun.qq.com/cgi-bin/announce/add_qun_notice?bkn={bkn}");
+        let rsp: NoticeOperateRsp = self
+            .web_post_form(
+                cookies,
+                url,
+                &[
+                    ("qid", group_code.to_string()),
+                    ("bkn", bkn.to_string()),
+                    ("text", text),
+                    ("pinned", "0".into()),
+                    ("type", "1".into()),
+                ],
+            )
+            .await?;
+        if rsp.ec != 0 {
+            return Err(RQError::Other(format!("send group notice: {}", rsp.em)));
+        }
+        Ok(rsp.new_fid)
+    }
+
+    /// 删除群公告 (网页接口)
+    pub async fn web_delete_group_notice(
+        &self,
+        group_code: i64,
+        notice_id: String,
+    ) -> RQResult<()> {
+        let (cookies, bkn) = self.qun_cookies().await?;
+        let url = format!("https://web.qun.qq.com/cgi-bin/announce/del_feed?bkn={bkn}");
+        let rsp: NoticeOperateRsp = self
+            .web_post_form(
+                cookies,
+                url,
+                &[
+                    ("qid", group_code.to_string()),
+                    ("bkn", bkn.to_string()),
+                    ("fid", notice_id),
+                    ("format", "json".into()),
+                ],
+            )
+            .await?;
+        if rsp.ec != 0 {
+            return Err(RQError::Other(format!("delete group notice: {}", rsp.em)));
+        }
+        Ok(())
+    }
+
+    /// 获取群精华消息列表 (网页接口)，返回当前页和是否为最后一页
+    pub async fn web_get_group_essence_list(
+        &self,
+        group_code: i64,
+        page: i32,
+    ) -> RQResult<(Vec<GroupEssenceMessage>, bool)> {
+        let (cookies, bkn) = self.qun_cookies().await?;
+        let url = format!(
+            "https://qun.qq.com/cgi-bin/group_digest/digest_list?bkn={bkn}&group_code={group_code}&page_start={page}&page_limit=20"
+        );
+        let rsp: EssenceListRsp = self.web_get(cookies, url).await?;
+        if rsp.retcode != 0 {
+            return Err(RQError::Other(format!(
+                "get group essence list: {}",
+                rsp.retmsg
+            )));
+        }
+        let data = rsp.data.ok_or(RQError::EmptyField("data"))?;
+        Ok((data.msg_list.unwrap_or_default(), data.is_end))
+    }
+
+    /// 获取群荣誉信息 (网页接口)
+    pub async fn web_get_group_honor_info(
+        &self,
+        group_code: i64,
+        honor_type: GroupHonorType,
+    ) -> RQResult<GroupHonorInfo> {
+        let (cookies, _) = self.qun_cookies().await?;
+        let url = format!(
+            "https://qun.qq.com/interactive/honorlist?gc={group_code}&type={}",
+            honor_type as i32
+        );
+        let html = self
+            .http_client()
+            .await
+            .get(url)
+            .header(reqwest::header::COOKIE, cookies)
+            .send()
+            .await
+            .map_err(|e| RQError::Other(e.to_string()))?
+            .text()
+            .await
+            .map_err(|e| RQError::Other(e.to_string()))?;
+        let state = html
+            .split_once("window.__INITIAL_STATE__=")
+            .and_then(|(_, s)| s.split_once("</script>"))
+            .map(|(s, _)| s.trim().trim_end_matches(';'))
+            .ok_or_else(|| RQError::Decode("missing honor initial state".into()))?;
+        serde_json::from_str(state).map_err(|e| RQError::Decode(e.to_string()))
+    }
+
+    /// 获取群成员等级 (活跃度) 排行和等级头衔 (网页接口)
+    pub async fn web_get_group_level_ranking(
+        &self,
+        group_code: i64,
+    ) -> RQResult<GroupLevelRanking> {
+        let mut ranking = GroupLevelRanking::default();
+        loop {
+            let (cookies, bkn) = self.qun_cookies().await?;
+            let start = ranking.members.len();
+            let url = format!("https://qun.qq.com/cgi-bin/qun_mgr/search_group_members?bkn={bkn}");
+            let rsp: SearchMembersRsp = self
+                .web_post_form(
+                    cookies,
+                    url,
+                    &[
+                        ("gc", group_code.to_string()),
+                        ("st", start.to_string()),
+                        ("end", (start + SEARCH_MEMBERS_PAGE_SIZE - 1).to_string()),
+                        ("sort", "0".into()),
+                        ("bkn", bkn.to_string()),
+                    ],
+                )
+                .await?;
+            if rsp.ec != 0 {
+                return Err(RQError::Other(format!(
+                    "get group level ranking: {}",
+                    rsp.em
+                )));
+            }
+            // key 形如 lvln1
+            ranking
+                .level_names
+                .extend(rsp.levelname.into_iter().filter_map(|(level, name)| {
+                    Some((level.trim_start_matches("lvln").parse().ok()?, name))
+                }));
+            let page = rsp.mems.len();
+            ranking.members.extend(rsp.mems);
+            if page < SEARCH_MEMBERS_PAGE_SIZE || ranking.members.len() >= rsp.count {
+                break;
+            }
+        }
+        ranking
+            .members
+            .sort_by(|a, b| b.level.point.cmp(&a.level.point));
+        Ok(ranking)
+    }
+}
@@ -0,0 +1,265 @@
+//! 抓包与回放，用于离线调试解析问题
+//!
+//! 文件格式为每行一个包，字段以 `\t` 分隔：
+//! `时间戳(毫秒) in/out seq uin command body(hex)`
+
+use std::fs::File;
+use std::io::{BufRead, BufReader, BufWriter, Write};
+use std::path::Path;
+use std::sync::{mpsc, Arc};
+use std::thread::JoinHandle;
+use std::time::UNIX_EPOCH;
+
+use bytes::Bytes;
+
+use ricq_core::hex::{decode_hex, encode_hex};
+use ricq_core::protocol::packet::Packet;
+
+use crate::{Client, RQError, RQResult};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Direction {
+    /// 收到的包，已解密
+    Incoming,
+    /// 发出的包，加密前
+    Outgoing,
+}
+
+/// 记录收发的包，通过 `Client::set_packet_recorder` 设置
+pub trait PacketRecorder: Send + Sync {
+    fn record(&self, direction: Direction, pkt: &Packet);
+}
+
+/// 一条抓包记录
+#[derive(Debug, Clone)]
+pub struct CapturedPacket {
+    /// 毫秒时间戳
+    pub time: i64,
+    pub direction: Direction,
+    pub seq_id: i32,
+    pub uin: i64,
+    pub command_name: String,
+    pub body: Bytes,
+}
+
+impl CapturedPacket {
+    pub fn to_line(&self) -> String {
+        let direction = match self.direction {
+            Direction::Incoming => "in",
+            Direction::Outgoing => "out",
+        };
+        format!(
+            "{}\t{}\t{}\t{}\t{}\t{}",
+            self.time,
+            direction,
+            self.seq_id,
+            self.uin,
+            self.command_name,
+            encode_hex(&self.body)
+        )
+    }
+
+    pub fn from_line(line: &str) -> RQResult<Self> {
+        let invalid = || RQError::Decode(format!("invalid capture line: {line}"));
+        let fields: Vec<&str> = line.split('\t').collect();
+        if fields.len() != 6 || fields[5].len() % 2 != 0 || !fields[5].is_ascii() {
+            return Err(invalid());
+        }
+        Ok(Self {
+            time: fields[0].parse().map_err(|_| invalid())?,
+            direction: match fields[1] {
+                "in" => Direction::Incoming,
+                "out" => Direction::Outgoing,
+                _ => return Err(invalid()),
+            },
+            seq_id: fields[2].parse().map_err(|_| invalid())?,
+            uin: fields[3].parse().map_err(|_| invalid())?,
+            command_name: fields[4].to_string(),
+            body: Bytes::from(decode_hex(fields[5]).map_err(|_| invalid())?),
+        })
+    }
+
+    pub fn to_packet(&self) -> Packet {
+        Packet {
+            seq_id: self.seq_id,
+            uin: self.uin,
+            command_name: self.command_name.clone(),
+            body: self.body.clone(),
+            ..Default::default()
+        }
+    }
+}
+
+/// 写入队列长度，写入线程跟不上时丢弃新的记录
+const RECORD_QUEUE_SIZE: usize = 1024;
+
+enum Record {
+    Line(String),
+    Flush(mpsc::SyncSender<std::io::Result<()>>),
+}
+
+/// 写入文件的 PacketRecorder，commands 不为空时只记录这些 command
+///
+/// 文件在单独的线程中写入，不会阻塞收发包。默认不记录 `wtlogin.*` 登录包
+pub struct FileRecorder {
+    sender: Option<mpsc::SyncSender<Record>>,
+    writer: Option<JoinHandle<()>>,
+    commands: Vec<String>,
+    include_login: bool,
+}
+
+impl FileRecorder {
+    pub fn create<P: AsRef<Path>>(path: P, commands: Vec<String>) -> RQResult<Self> {
+        let mut file = BufWriter::new(File::create(path)?);
+        let (sender, receiver) = mpsc::sync_channel(RECORD_QUEUE_SIZE);
+        let writer = std::thread::Builder::new()
+            .name("ricq-capture".into())
+            .spawn(move || {
+                for record in receiver {
+                    match record {
+                        Record::Line(line) => {
+                            if let Err(err) = writeln!(file, "{line}") {
+                                tracing::warn!("failed to record packet: {}", err);
+                            }
+                        }
+                        Record::Flush(done) => {
+                            done.send(file.flush()).ok();
+                        }
+                    }
+                }
+                file.flush().ok();
+            })?;
+        Ok(Self {
+            sender: Some(sender),
+            writer: Some(writer),
+            commands,
+            include_login: false,
+        })
+    }
+
+    /// 是否记录 `wtlogin.*` 登录包，其中包含密码和登录凭证，默认不记录
+    pub fn include_login(mut self, include_login: bool) -> Self {
+        self.include_login = include_login;
+        self
+    }
+
+    /// 等待已记录的包写入文件
+    pub fn flush(&self) -> RQResult<()> {
+        let (done, wait) = mpsc::sync_channel(1);
+        if let Some(sender) = &self.sender {
+            if sender.send(Record::Flush(done)).is_ok() {
+                if let Ok(result) = wait.recv() {
+                    result?;
+                }
+            }
+        }
+        Ok(())
+    }
+
+    fn should_record(&self, command: &str) -> bool {
+        if !self.include_login && command.starts_with("wtlogin.") {
+            return false;
+        }
+        self.commands.is_empty() || self.commands.iter().any(|c| c == command)
+    }
+}
+
+impl PacketRecorder for FileRecorder {
+    fn record(&self, direction: Direction, pkt: &Packet) {
+        if !self.should_record(&pkt.command_name) {
+            return;
+        }
+        let captured = CapturedPacket {
+            time: UNIX_EPOCH.elapsed().unwrap_or_default().as_millis() as i64,
+            direction,
+            seq_id: pkt.seq_id,
+            uin: pkt.uin,
+            command_name: pkt.command_name.clone(),
+            body: pkt.body.clone(),
+        };
+        if let Some(sender) = &self.sender {
+            if let Err(mpsc::TrySendError::Full(_)) =
+                sender.try_send(Record::Line(captured.to_line()))
+            {
+                tracing::warn!("capture queue is full, dropped {}", pkt.command_name);
+            }
+        }
+    }
+}
+
+impl Drop for FileRecorder {
+    fn drop(&mut self) {
+        // 关闭队列后写入线程会写完剩余的记录再退出
+        self.sender.take();
+        if let Some(writer) = self.writer.take() {
+            writer.join().ok();
+        }
+    }
+}
+
+/// 读取抓包文件
+pub fn read_capture<P: AsRef<Path>>(path: P) -> RQResult<Vec<CapturedPacket>> {
+    BufReader::new(File::open(path)?)
+        .lines()
+        .filter(|line| !matches!(line, Ok(line) if line.trim().is_empty()))
+        .map(|line| CapturedPacket::from_line(line?.trim_end()))
+        .collect()
+}
+
+/// 把抓包文件中收到的包按文件顺序交给 process_income_packet 处理，返回回放的包数量
+///
+/// 不需要连接服务器，事件会正常分发给 handler。与在线时相同，同一群或同一会话的包按顺序处理，
+/// 不同会话之间并发处理；函数返回时包可能还没有处理完
+pub async fn replay_capture<P: AsRef<Path>>(client: &Arc<Client>, path: P) -> RQResult<usize> {
+    let mut count = 0;
+    for captured in read_capture(path)? {
+        if captured.direction == Direction::Incoming {
+            client.process_income_packet(captured.to_packet()).await;
+            count += 1;
+        }
+    }
+    Ok(count)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_capture_line() {
+        let captured = CapturedPacket {
+            time: 1650000000000,
+            direction: Direction::Incoming,
+            seq_id: -5,
+            uin: 10000,
+            command_name: "OnlinePush.PbPushGroupMsg".into(),
+            body: Bytes::from_static(&[0x0a, 0xff]),
+        };
+        let parsed = CapturedPacket::from_line(&captured.to_line()).unwrap();
+        assert_eq!(parsed.direction, Direction::Incoming);
+        assert_eq!(parsed.seq_id, -5);
+        assert_eq!(parsed.command_name, captured.command_name);
+        assert_eq!(parsed.body, captured.body);
+        assert!(CapturedPacket::from_line("1\tin\t2").is_err());
+    }
+
+    #[test]
+    fn test_file_recorder() {
+        let path = std::env::temp_dir().join(format!("ricq-capture-{}.txt", std::process::id()));
+        let recorder = FileRecorder::create(&path, Vec::new()).unwrap();
+        for command in ["wtlogin.login", "MessageSvc.PushNotify"] {
+            let pkt = Packet {
+                command_name: command.into(),
+                body: Bytes::from_static(&[1, 2]),
+                ..Default::default()
+            };
+            recorder.record(Direction::Incoming, &pkt);
+        }
+        recorder.flush().unwrap();
+        let captured = read_capture(&path).unwrap();
+        drop(recorder);
+        std::fs::remove_file(&path).ok();
+        assert_eq!(captured.len(), 1);
+        assert_eq!(captured[0].command_name, "MessageSvc.PushNotify");
+    }
+}
@@ -0,0 +1,10 @@
+use crate::RQResult;
+
+/// 登录时 tlv 0x544 (T544) 签名的计算方式
+///
+/// 默认通过 QSignClient 的 custom_energy 接口计算，设置后改用本地实现，
+/// 通过 `Client::set_energy_provider` 设置
+pub trait EnergyProvider: Send + Sync {
+    /// data 形如 "810_9"，salt 见 `QSignClient::calc_salt`
+    fn energy(&self, uin: i64, data: &str, salt: &[u8]) -> RQResult<Vec<u8>>;
+}
@@ -1,28 +1,72 @@
 use std::sync::Arc;
 
 use ricq_core::command::profile_service::{JoinGroupRequest, NewFriendRequest, SelfInvited};
+use ricq_core::command::wtlogin::LoginResponse;
+use ricq_core::msg::elem::{At, FriendImage, Reply};
+use ricq_core::msg::MessageChain;
 use ricq_core::structs::{
-    DeleteFriend, FriendAudioMessage, FriendInfo, FriendMessageRecall, FriendPoke,
-    GroupAudioMessage, GroupDisband, GroupLeave, GroupMessageRecall, GroupMute, GroupNameUpdate,
-    GroupPoke, GroupTempMessage, MemberPermissionChange, NewMember,
+    AccountInfoChange, BotMute, DeleteFriend, FriendAudioMessage, FriendFile, FriendInfo,
+    FriendMessageRecall, FriendPoke, GroupAudioMessage, GroupDisband, GroupGift, GroupLeave,
+    GroupMemberInfo, GroupMessageRecall, GroupMute, GroupNameUpdate, GroupPoke, GroupTempMessage,
+    MemberInfoChange, MemberPermissionChange, MessageReceipt, NewMember, PacketDecodeError,
+    RawGrayTip, RawPacket, ServerNotice, SummaryCardInfo, TempMessage,
 };
 use ricq_core::{jce, RQResult};
+use serde::{Deserialize, Serialize};
 
 use crate::client::NetworkStatus;
 use crate::structs::{FriendMessage, GroupMessage};
 use crate::Client;
 
+/// 事件数据使用 Arc 共享，分发给多个 Handler 时不会复制消息内容
 #[derive(Clone, derivative::Derivative)]
 #[derivative(Debug)]
 pub struct EventWithClient<T> {
     #[derivative(Debug = "ignore")]
     pub client: Arc<Client>,
-    pub inner: T,
+    pub inner: Arc<T>,
 }
 
 pub type GroupMessageEvent = EventWithClient<GroupMessage>;
 
 impl GroupMessageEvent {
+    /// 在来源群回复
+    pub async fn reply(&self, message_chain: MessageChain) -> RQResult<MessageReceipt> {
+        self.client
+            .send_group_message(self.inner.group_code, message_chain)
+            .await
+    }
+
+    /// 在来源群引用这条消息回复
+    pub async fn reply_quote(&self, mut message_chain: MessageChain) -> RQResult<MessageReceipt> {
+        message_chain.with_reply(Reply {
+            reply_seq: self.inner.seqs.first().copied().unwrap_or_default(),
+            sender: self.inner.from_uin,
+            time: self.inner.time,
+            elements: self.inner.elements.clone(),
+        });
+        self.reply(message_chain).await
+    }
+
+    /// @ 发送者，优先显示群名片
+    pub fn at_sender(&self) -> At {
+        if self.inner.group_card.is_empty() {
+            At::new(self.inner.from_uin)
+        } else {
+            At {
+                target: self.inner.from_uin,
+                display: format!("@{}", self.inner.group_card),
+            }
+        }
+    }
+
+    /// 获取发送者的群成员信息，优先使用群成员缓存
+    pub async fn sender_member_info(&self) -> RQResult<GroupMemberInfo> {
+        self.client
+            .get_group_member_info_cached(self.inner.group_code, self.inner.from_uin)
+            .await
+    }
+
     pub async fn recall(&self) -> RQResult<()> {
         // TODO check permission
         self.client
@@ -33,10 +77,134 @@ impl GroupMessageEvent {
             )
             .await
     }
+
+    /// 获取引用回复的原消息，优先使用缓存，没有引用时返回 None
+    pub async fn quoted_message(&self) -> RQResult<Option<MessageChain>> {
+        match self.inner.elements.reply() {
+            Some(reply) => {
+                self.client
+                    .get_quoted_group_message(self.inner.group_code, &reply)
+                    .await
+            }
+            None => Ok(None),
+        }
+    }
 }
 
 pub type FriendMessageEvent = EventWithClient<FriendMessage>;
+
+impl FriendMessageEvent {
+    /// 回复好友
+    pub async fn reply(&self, message_chain: MessageChain) -> RQResult<MessageReceipt> {
+        self.client
+            .send_friend_message(self.inner.from_uin, message_chain)
+            .await
+    }
+
+    /// 上传可以发送给该好友的图片
+    pub async fn upload_image(&self, data: &[u8]) -> RQResult<FriendImage> {
+        self.client
+            .upload_friend_image(self.inner.from_uin, data)
+            .await
+    }
+
+    /// 戳一戳好友
+    pub async fn poke(&self) -> RQResult<()> {
+        self.client.friend_poke(self.inner.from_uin).await
+    }
+
+    /// 获取好友资料卡
+    pub async fn sender_info(&self) -> RQResult<SummaryCardInfo> {
+        self.client.get_summary_info(self.inner.from_uin).await
+    }
+
+    /// 获取引用回复的原消息，优先使用缓存，没有引用时返回 None
+    pub async fn quoted_message(&self) -> RQResult<Option<MessageChain>> {
+        match self.inner.elements.reply() {
+            Some(reply) => {
+                self.client
+                    .get_quoted_friend_message(self.inner.from_uin, &reply)
+                    .await
+            }
+            None => Ok(None),
+        }
+    }
+}
+
+/// 本账号在其他设备发送的群消息
+pub type SelfGroupMessageEvent = EventWithClient<GroupMessage>;
+
+/// 本账号在其他设备发送的私聊消息，target 为好友 QQ
+pub type SelfFriendMessageEvent = EventWithClient<FriendMessage>;
+
+pub type FriendFileReceivedEvent = EventWithClient<FriendFile>;
+
+impl FriendFileReceivedEvent {
+    /// 获取文件下载链接
+    pub async fn download_url(&self) -> RQResult<String> {
+        self.client
+            .download_friend_file(self.inner.file_uuid.to_vec())
+            .await
+    }
+
+    /// 下载文件写入 writer，progress 参数为已下载字节数和总字节数
+    pub async fn download_to<W, F>(&self, writer: &mut W, progress: F) -> RQResult<u64>
+    where
+        W: tokio::io::AsyncWrite + Unpin,
+        F: FnMut(u64, Option<u64>),
+    {
+        self.client
+            .download_friend_file_to(self.inner.file_uuid.to_vec(), writer, progress)
+            .await
+    }
+}
+
 pub type GroupTempMessageEvent = EventWithClient<GroupTempMessage>;
+
+impl GroupTempMessageEvent {
+    /// 使用收到消息的路由信息回复临时会话
+    pub async fn reply(&self, message_chain: MessageChain) -> RQResult<MessageReceipt> {
+        self.client
+            .send_temp_message(&self.inner.session, message_chain)
+            .await
+    }
+
+    /// 上传可以在临时会话中发送的图片
+    pub async fn upload_image(&self, data: &[u8]) -> RQResult<FriendImage> {
+        self.client
+            .upload_friend_image(self.inner.from_uin, data)
+            .await
+    }
+
+    /// 获取发送者资料卡
+    pub async fn sender_info(&self) -> RQResult<SummaryCardInfo> {
+        self.client.get_summary_info(self.inner.from_uin).await
+    }
+}
+
+pub type TempMessageEvent = EventWithClient<TempMessage>;
+
+impl TempMessageEvent {
+    /// 使用来源会话的路由信息回复
+    pub async fn reply(&self, message_chain: MessageChain) -> RQResult<MessageReceipt> {
+        self.client
+            .send_temp_message(&self.inner.session, message_chain)
+            .await
+    }
+
+    /// 上传可以在临时会话中发送的图片
+    pub async fn upload_image(&self, data: &[u8]) -> RQResult<FriendImage> {
+        self.client
+            .upload_friend_image(self.inner.from_uin, data)
+            .await
+    }
+
+    /// 获取发送者资料卡
+    pub async fn sender_info(&self) -> RQResult<SummaryCardInfo> {
+        self.client.get_summary_info(self.inner.from_uin).await
+    }
+}
+
 pub type JoinGroupRequestEvent = EventWithClient<JoinGroupRequest>;
 
 impl JoinGroupRequestEvent {
@@ -89,6 +257,8 @@ impl NewFriendRequestEvent {
 
 pub type NewMemberEvent = EventWithClient<NewMember>;
 pub type GroupMuteEvent = EventWithClient<GroupMute>;
+/// 同时也会发出 GroupMuteEvent
+pub type BotMuteEvent = EventWithClient<BotMute>;
 pub type FriendMessageRecallEvent = EventWithClient<FriendMessageRecall>;
 pub type GroupMessageRecallEvent = EventWithClient<GroupMessageRecall>;
 pub type NewFriendEvent = EventWithClient<FriendInfo>;
@@ -96,18 +266,34 @@ pub type GroupLeaveEvent = EventWithClient<GroupLeave>;
 pub type GroupDisbandEvent = EventWithClient<GroupDisband>;
 pub type FriendPokeEvent = EventWithClient<FriendPoke>;
 pub type GroupPokeEvent = EventWithClient<GroupPoke>;
+pub type GroupGiftEvent = EventWithClient<GroupGift>;
 pub type GroupNameUpdateEvent = EventWithClient<GroupNameUpdate>;
 pub type DeleteFriendEvent = EventWithClient<DeleteFriend>;
 pub type MemberPermissionChangeEvent = EventWithClient<MemberPermissionChange>;
+/// 由成员列表比对得出，见 `Client::diff_group_members`
+pub type MemberInfoChangeEvent = EventWithClient<MemberInfoChange>;
+/// 见 `Client::refresh_account_info`
+pub type AccountInfoChangeEvent = EventWithClient<AccountInfoChange>;
 pub type SelfInvitedEvent = EventWithClient<SelfInvited>;
 pub type GroupAudioMessageEvent = EventWithClient<GroupAudioMessage>;
 
+pub type ServerNoticeEvent = EventWithClient<ServerNotice>;
+/// 未识别模板的群灰条，见 `RawGrayTip::param`
+pub type RawGrayTipEvent = EventWithClient<RawGrayTip>;
+
 impl GroupAudioMessageEvent {
     pub async fn url(&self) -> RQResult<String> {
         self.client
             .get_group_audio_url(self.inner.group_code, self.inner.audio.clone())
             .await
     }
+
+    /// 语音转文字
+    pub async fn audio_to_text(&self) -> RQResult<String> {
+        self.client
+            .audio_to_text(self.inner.group_code, self.inner.audio.clone())
+            .await
+    }
 }
 
 pub type FriendAudioMessageEvent = EventWithClient<FriendAudioMessage>;
@@ -118,12 +304,20 @@ impl FriendAudioMessageEvent {
             .get_friend_audio_url(self.inner.from_uin, self.inner.audio.clone())
             .await
     }
+
+    /// 语音转文字
+    pub async fn audio_to_text(&self) -> RQResult<String> {
+        self.client
+            .friend_audio_to_text(self.inner.from_uin, self.inner.audio.clone())
+            .await
+    }
 }
 
 pub type KickedOfflineEvent = EventWithClient<jce::RequestPushForceOffline>;
 pub type MSFOfflineEvent = EventWithClient<jce::RequestMSFForceOffline>;
+pub type DecodeErrorEvent = EventWithClient<PacketDecodeError>;
 
-#[derive(Copy, Clone, Debug)]
+#[derive(Copy, Clone, Debug, Serialize, Deserialize)]
 #[repr(u8)]
 pub enum DisconnectReason {
     /// 主动断开
@@ -144,8 +338,91 @@ impl DisconnectReason {
 
 pub type ClientDisconnect = EventWithClient<DisconnectReason>;
 
+/// 连接异常的原因
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub enum UnhealthyReason {
+    /// 连续多次心跳没有响应
+    HeartbeatMissed(u32),
+    /// 重新注册客户端失败
+    RegisterFailed(String),
+}
+
+/// 心跳检测到连接异常，见 `ClientOptions::heartbeat_policy`
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct ConnectionUnhealthy {
+    pub reason: UnhealthyReason,
+    /// 是否断开连接，断开后会收到 ClientDisconnect 事件，可以由 `ext::reconnect` 重连
+    pub disconnecting: bool,
+}
+
+pub type ConnectionUnhealthyEvent = EventWithClient<ConnectionUnhealthy>;
+pub type RawPacketEvent = EventWithClient<RawPacket>;
+
+/// 登录阶段
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub enum LoginStage {
+    /// 已连接服务器，开始处理数据包
+    Connected,
+    /// 已发送登录请求，method 为 password / qrcode / ticket / sms / device_lock
+    Authenticating {
+        method: String,
+    },
+    /// 使用 token 换取新的登录凭证（wtlogin.exchange_emp）
+    ExchangingKeys,
+    /// 需要滑块验证
+    CaptchaRequired {
+        verify_url: Option<String>,
+    },
+    /// 需要设备锁验证
+    DeviceLocked {
+        sms_phone: Option<String>,
+        verify_url: Option<String>,
+    },
+    /// 短信验证码已发送，在请求短信返回的 DeviceLocked 之后上报
+    SmsSent {
+        phone: Option<String>,
+    },
+    Success,
+    Failed {
+        message: String,
+    },
+}
+
+impl LoginStage {
+    /// 登录结果对应的阶段，DeviceLockLogin 需要继续发包，返回 None
+    pub fn from_response(resp: &LoginResponse) -> Option<Self> {
+        Some(match resp {
+            LoginResponse::Success(_) => LoginStage::Success,
+            LoginResponse::NeedCaptcha(e) => LoginStage::CaptchaRequired {
+                verify_url: e.verify_url.clone(),
+            },
+            LoginResponse::DeviceLocked(e) => LoginStage::DeviceLocked {
+                sms_phone: e.sms_phone.clone(),
+                verify_url: e.verify_url.clone(),
+            },
+            LoginResponse::DeviceLockLogin(_) => return None,
+            LoginResponse::AccountFrozen => LoginStage::Failed {
+                message: "account frozen".into(),
+            },
+            LoginResponse::TooManySMSRequest => LoginStage::Failed {
+                message: "too many sms requests".into(),
+            },
+            LoginResponse::UnknownStatus(e) => LoginStage::Failed {
+                message: format!("status {}: {}", e.status, e.message),
+            },
+        })
+    }
+}
+
+/// 登录进度，供界面展示登录状态
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct LoginProgress {
+    pub uin: i64,
+    pub stage: LoginStage,
+}
+
 impl ClientDisconnect {
     pub fn reason(&self) -> DisconnectReason {
-        self.inner
+        *self.inner
     }
 }
@@ -0,0 +1,145 @@
+use std::future::Future;
+use std::pin::Pin;
+
+use async_trait::async_trait;
+
+use super::{Handler, QEvent};
+
+/// 事件中间件，返回 `Some` 时把事件（可以是修改后的）交给下一个，返回 `None` 时拦截事件
+///
+/// 所有 `async fn(QEvent) -> Option<QEvent>` 都已自动实现 `Middleware`。
+#[async_trait]
+pub trait Middleware: Sync {
+    async fn handle(&self, event: QEvent) -> Option<QEvent>;
+}
+
+impl<Fut> Middleware for fn(QEvent) -> Fut
+where
+    Fut: Future<Output = Option<QEvent>> + Send,
+{
+    fn handle<'a: 'b, 'b>(
+        &'a self,
+        e: QEvent,
+    ) -> Pin<Box<dyn Future<Output = Option<QEvent>> + Send + 'b>> {
+        Box::pin(async move { self(e).await })
+    }
+}
+
+/// 把 Handler 当作中间件使用，事件处理完后继续向后传递
+pub struct PassThrough<H>(pub H);
+
+#[async_trait]
+impl<H> Middleware for PassThrough<H>
+where
+    H: Handler + Send,
+{
+    async fn handle(&self, event: QEvent) -> Option<QEvent> {
+        self.0.handle(event.clone()).await;
+        Some(event)
+    }
+}
+
+/// 按注册顺序依次调用的中间件链，本身实现了 Handler
+///
+/// # Examples
+///
+/// ```ignore
+/// let handler = HandlerChain::new()
+///     .with(log_middleware as fn(_) -> _)
+///     .with(PassThrough(plugin_a))
+///     .then(DefaultHandler);
+/// let client = Client::new(device, version, qsign_client, handler);
+/// ```
+#[derive(Default)]
+pub struct HandlerChain {
+    middlewares: Vec<Box<dyn Middleware + Send + 'static>>,
+    handler: Option<Box<dyn Handler + Send + 'static>>,
+}
+
+impl HandlerChain {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// 在链尾追加一个中间件
+    pub fn with<M>(mut self, middleware: M) -> Self
+    where
+        M: Middleware + Send + 'static,
+    {
+        self.middlewares.push(Box::new(middleware));
+        self
+    }
+
+    /// 设置最终处理事件的 Handler，只有没被拦截的事件会到达
+    pub fn then<H>(mut self, handler: H) -> Self
+    where
+        H: Handler + Send + 'static,
+    {
+        self.handler = Some(Box::new(handler));
+        self
+    }
+}
+
+#[async_trait]
+impl Handler for HandlerChain {
+    fn subscribes(&self, kind: &str) -> bool {
+        // 中间件可能需要所有事件
+        !self.middlewares.is_empty()
+            || self
+                .handler
+                .as_ref()
+                .map(|h| h.subscribes(kind))
+                .unwrap_or_default()
+    }
+
+    async fn handle(&self, event: QEvent) {
+        let mut event = event;
+        for middleware in self.middlewares.iter() {
+            match middleware.handle(event).await {
+                Some(e) => event = e,
+                None => return,
+            }
+        }
+        if let Some(handler) = self.handler.as_ref() {
+            handler.handle(event).await
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    use std::sync::Arc;
+
+    use super::*;
+
+    struct Count(Arc<AtomicUsize>);
+
+    #[async_trait]
+    impl Handler for Count {
+        async fn handle(&self, _: QEvent) {
+            self.0.fetch_add(1, Ordering::SeqCst);
+        }
+    }
+
+    async fn drop_zero(e: QEvent) -> Option<QEvent> {
+        match e {
+            QEvent::Login(0) => None,
+            e => Some(e),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_handler_chain() {
+        let before = Arc::new(AtomicUsize::new(0));
+        let after = Arc::new(AtomicUsize::new(0));
+        let chain = HandlerChain::new()
+            .with(PassThrough(Count(before.clone())))
+            .with(drop_zero as fn(_) -> _)
+            .then(Count(after.clone()));
+        chain.handle(QEvent::Login(1)).await;
+        chain.handle(QEvent::Login(0)).await;
+        assert_eq!(before.load(Ordering::SeqCst), 2);
+        assert_eq!(after.load(Ordering::SeqCst), 1);
+    }
+}
@@ -0,0 +1,146 @@
+use serde::{Deserialize, Serialize};
+
+use ricq_core::command::profile_service::{JoinGroupRequest, NewFriendRequest, SelfInvited};
+use ricq_core::jce;
+use ricq_core::structs::{
+    AccountInfoChange, BotMute, DeleteFriend, FriendAudioMessage, FriendFile, FriendInfo,
+    FriendMessage, FriendMessageRecall, FriendPoke, GroupAudioMessage, GroupDisband, GroupGift,
+    GroupLeave, GroupMessage, GroupMessageRecall, GroupMute, GroupNameUpdate, GroupPoke,
+    GroupTempMessage, MemberInfoChange, MemberPermissionChange, NewMember, PacketDecodeError,
+    RawGrayTip, RawPacket, ServerNotice, TempMessage,
+};
+
+use crate::client::event::{ConnectionUnhealthy, DisconnectReason, LoginProgress};
+use crate::{RQError, RQResult};
+
+use super::QEvent;
+
+/// 序列化格式版本，字段有不兼容的修改时增加
+pub const EVENT_ENVELOPE_VERSION: u32 = 1;
+
+/// 不含 client 的事件，可序列化后通过 IPC / 消息队列发送给其他进程
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct EventEnvelope {
+    pub version: u32,
+    /// 收到事件的机器人 QQ
+    pub self_uin: i64,
+    pub event: EventPayload,
+}
+
+impl EventEnvelope {
+    pub async fn from_event(event: &QEvent) -> Self {
+        let self_uin = match event {
+            QEvent::Login(uin) => *uin,
+            QEvent::LoginProgress(p) => p.uin,
+            _ => match event.client() {
+                Some(client) => client.uin().await,
+                None => 0,
+            },
+        };
+        Self {
+            version: EVENT_ENVELOPE_VERSION,
+            self_uin,
+            event: EventPayload::from(event),
+        }
+    }
+
+    /// 检查版本，反序列化后调用
+    pub fn check_version(&self) -> RQResult<()> {
+        if self.version != EVENT_ENVELOPE_VERSION {
+            return Err(RQError::Decode(format!(
+                "unsupported event envelope version {}, expected {}",
+                self.version, EVENT_ENVELOPE_VERSION
+            )));
+        }
+        Ok(())
+    }
+}
+
+/// 与 QEvent 一一对应，类型名见 `QEvent::kind`
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(tag = "kind", content = "data")]
+pub enum EventPayload {
+    Login(i64),
+    LoginProgress(LoginProgress),
+    GroupMessage(GroupMessage),
+    GroupAudioMessage(GroupAudioMessage),
+    FriendMessage(FriendMessage),
+    FriendAudioMessage(FriendAudioMessage),
+    GroupTempMessage(GroupTempMessage),
+    TempMessage(TempMessage),
+    GroupRequest(JoinGroupRequest),
+    SelfInvited(SelfInvited),
+    NewFriendRequest(NewFriendRequest),
+    NewMember(NewMember),
+    GroupMute(GroupMute),
+    FriendMessageRecall(FriendMessageRecall),
+    GroupMessageRecall(GroupMessageRecall),
+    NewFriend(FriendInfo),
+    GroupLeave(GroupLeave),
+    GroupDisband(GroupDisband),
+    FriendPoke(FriendPoke),
+    GroupPoke(GroupPoke),
+    GroupGift(GroupGift),
+    GroupNameUpdate(GroupNameUpdate),
+    DeleteFriend(DeleteFriend),
+    MemberPermissionChange(MemberPermissionChange),
+    KickedOffline(jce::RequestPushForceOffline),
+    MSFOffline(jce::RequestMSFForceOffline),
+    ClientDisconnect(DisconnectReason),
+    DecodeError(PacketDecodeError),
+    SelfGroupMessage(GroupMessage),
+    SelfFriendMessage(FriendMessage),
+    ServerNotice(ServerNotice),
+    FriendFileReceived(FriendFile),
+    MemberInfoChange(MemberInfoChange),
+    RawGrayTip(RawGrayTip),
+    AccountInfoChange(AccountInfoChange),
+    BotMute(BotMute),
+    ConnectionUnhealthy(ConnectionUnhealthy),
+    RawPacket(RawPacket),
+}
+
+impl From<&QEvent> for EventPayload {
+    fn from(event: &QEvent) -> Self {
+        match event {
+            QEvent::Login(uin) => Self::Login(*uin),
+            QEvent::LoginProgress(p) => Self::LoginProgress(p.clone()),
+            QEvent::GroupMessage(e) => Self::GroupMessage((*e.inner).clone()),
+            QEvent::GroupAudioMessage(e) => Self::GroupAudioMessage((*e.inner).clone()),
+            QEvent::FriendMessage(e) => Self::FriendMessage((*e.inner).clone()),
+            QEvent::FriendAudioMessage(e) => Self::FriendAudioMessage((*e.inner).clone()),
+            QEvent::GroupTempMessage(e) => Self::GroupTempMessage((*e.inner).clone()),
+            QEvent::TempMessage(e) => Self::TempMessage((*e.inner).clone()),
+            QEvent::GroupRequest(e) => Self::GroupRequest((*e.inner).clone()),
+            QEvent::SelfInvited(e) => Self::SelfInvited((*e.inner).clone()),
+            QEvent::NewFriendRequest(e) => Self::NewFriendRequest((*e.inner).clone()),
+            QEvent::NewMember(e) => Self::NewMember((*e.inner).clone()),
+            QEvent::GroupMute(e) => Self::GroupMute((*e.inner).clone()),
+            QEvent::FriendMessageRecall(e) => Self::FriendMessageRecall((*e.inner).clone()),
+            QEvent::GroupMessageRecall(e) => Self::GroupMessageRecall((*e.inner).clone()),
+            QEvent::NewFriend(e) => Self::NewFriend((*e.inner).clone()),
+            QEvent::GroupLeave(e) => Self::GroupLeave((*e.inner).clone()),
+            QEvent::GroupDisband(e) => Self::GroupDisband((*e.inner).clone()),
+            QEvent::FriendPoke(e) => Self::FriendPoke((*e.inner).clone()),
+            QEvent::GroupPoke(e) => Self::GroupPoke((*e.inner).clone()),
+            QEvent::GroupGift(e) => Self::GroupGift((*e.inner).clone()),
+            QEvent::GroupNameUpdate(e) => Self::GroupNameUpdate((*e.inner).clone()),
+            QEvent::DeleteFriend(e) => Self::DeleteFriend((*e.inner).clone()),
+            QEvent::MemberPermissionChange(e) => Self::MemberPermissionChange((*e.inner).clone()),
+            QEvent::KickedOffline(e) => Self::KickedOffline((*e.inner).clone()),
+            QEvent::MSFOffline(e) => Self::MSFOffline((*e.inner).clone()),
+            QEvent::ClientDisconnect(e) => Self::ClientDisconnect(*e.inner),
+            QEvent::DecodeError(e) => Self::DecodeError((*e.inner).clone()),
+            QEvent::SelfGroupMessage(e) => Self::SelfGroupMessage((*e.inner).clone()),
+            QEvent::SelfFriendMessage(e) => Self::SelfFriendMessage((*e.inner).clone()),
+            QEvent::ServerNotice(e) => Self::ServerNotice((*e.inner).clone()),
+            QEvent::FriendFileReceived(e) => Self::FriendFileReceived((*e.inner).clone()),
+            QEvent::MemberInfoChange(e) => Self::MemberInfoChange((*e.inner).clone()),
+            QEvent::RawGrayTip(e) => Self::RawGrayTip((*e.inner).clone()),
+            QEvent::AccountInfoChange(e) => Self::AccountInfoChange((*e.inner).clone()),
+            QEvent::BotMute(e) => Self::BotMute((*e.inner).clone()),
+            QEvent::ConnectionUnhealthy(e) => Self::ConnectionUnhealthy((*e.inner).clone()),
+            QEvent::RawPacket(e) => Self::RawPacket((*e.inner).clone()),
+        }
+    }
+}
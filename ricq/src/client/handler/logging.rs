@@ -0,0 +1,245 @@
+use std::collections::HashSet;
+use std::fmt::Display;
+use std::sync::Arc;
+
+use async_trait::async_trait;
+use ricq_core::msg::render::{ChainRenderer, TextRenderer};
+
+use super::{Handler, QEvent};
+
+/// 按事件类型打印日志的 Handler，每种事件使用单独的 target（如 `ricq::event::group_message`）
+///
+/// 可以通过 `allow` / `deny` 过滤事件类型，类型名见 `QEvent::kind`
+pub struct TracingHandler {
+    allow: Option<HashSet<&'static str>>,
+    deny: HashSet<&'static str>,
+    preview_len: usize,
+    renderer: Arc<dyn ChainRenderer + Send + Sync>,
+}
+
+impl Default for TracingHandler {
+    fn default() -> Self {
+        Self {
+            allow: None,
+            deny: HashSet::new(),
+            preview_len: 100,
+            renderer: Arc::new(TextRenderer::default()),
+        }
+    }
+}
+
+impl TracingHandler {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// 只打印这些类型的事件
+    pub fn allow<I: IntoIterator<Item = &'static str>>(mut self, kinds: I) -> Self {
+        self.allow = Some(kinds.into_iter().collect());
+        self
+    }
+
+    /// 不打印这些类型的事件
+    pub fn deny<I: IntoIterator<Item = &'static str>>(mut self, kinds: I) -> Self {
+        self.deny.extend(kinds);
+        self
+    }
+
+    /// 消息预览的最大字符数
+    pub fn preview_len(mut self, len: usize) -> Self {
+        self.preview_len = len;
+        self
+    }
+
+    /// 消息转为文本的方式，默认为 [`TextRenderer::default`]
+    pub fn renderer<R: ChainRenderer + Send + Sync + 'static>(mut self, renderer: R) -> Self {
+        self.renderer = Arc::new(renderer);
+        self
+    }
+
+    pub fn is_enabled(&self, kind: &str) -> bool {
+        !self.deny.contains(kind)
+            && self
+                .allow
+                .as_ref()
+                .map(|allow| allow.contains(kind))
+                .unwrap_or(true)
+    }
+
+    fn preview(&self, message: &impl Display) -> String {
+        let message = message.to_string();
+        match message.char_indices().nth(self.preview_len) {
+            Some((i, _)) => format!("{}...", &message[..i]),
+            None => message,
+        }
+    }
+}
+
+#[async_trait]
+impl Handler for TracingHandler {
+    fn subscribes(&self, kind: &str) -> bool {
+        self.is_enabled(kind)
+    }
+
+    async fn handle(&self, e: QEvent) {
+        if !self.is_enabled(e.kind()) {
+            return;
+        }
+        match e {
+            QEvent::Login(uin) => {
+                tracing::info!(target: "ricq::event::login", "LOGIN (UIN={})", uin)
+            }
+            QEvent::LoginProgress(p) => {
+                tracing::info!(target: "ricq::event::login_progress", "{:?}", p)
+            }
+            QEvent::GroupMessage(m) => tracing::info!(
+                target: "ricq::event::group_message",
+                "MESSAGE (GROUP={}, UIN={}): {}",
+                m.inner.group_code,
+                m.inner.from_uin,
+                self.preview(&self.renderer.render(&m.inner.elements))
+            ),
+            QEvent::GroupAudioMessage(m) => {
+                tracing::info!(target: "ricq::event::group_audio_message", "{:?}", m.inner)
+            }
+            QEvent::FriendMessage(m) => tracing::info!(
+                target: "ricq::event::friend_message",
+                "MESSAGE (FRIEND={}): {}",
+                m.inner.from_uin,
+                self.preview(&self.renderer.render(&m.inner.elements))
+            ),
+            QEvent::FriendAudioMessage(m) => {
+                tracing::info!(target: "ricq::event::friend_audio_message", "{:?}", m.inner)
+            }
+            QEvent::GroupTempMessage(m) => tracing::info!(
+                target: "ricq::event::group_temp_message",
+                "MESSAGE (TEMP={}, GROUP={}): {}",
+                m.inner.from_uin,
+                m.inner.group_code,
+                self.preview(&self.renderer.render(&m.inner.elements))
+            ),
+            QEvent::TempMessage(m) => tracing::info!(
+                target: "ricq::event::temp_message",
+                "MESSAGE (TEMP={}, SCENE={:?}): {}",
+                m.inner.from_uin,
+                m.inner.session.scene,
+                self.preview(&self.renderer.render(&m.inner.elements))
+            ),
+            QEvent::GroupRequest(m) => {
+                tracing::info!(target: "ricq::event::group_request", "{:?}", m.inner)
+            }
+            QEvent::SelfInvited(m) => {
+                tracing::info!(target: "ricq::event::self_invited", "{:?}", m.inner)
+            }
+            QEvent::NewFriendRequest(m) => {
+                tracing::info!(target: "ricq::event::new_friend_request", "{:?}", m.inner)
+            }
+            QEvent::NewMember(m) => {
+                tracing::info!(target: "ricq::event::new_member", "{:?}", m.inner)
+            }
+            QEvent::GroupMute(m) => {
+                tracing::info!(target: "ricq::event::group_mute", "{:?}", m.inner)
+            }
+            QEvent::FriendMessageRecall(m) => {
+                tracing::info!(target: "ricq::event::friend_message_recall", "{:?}", m.inner)
+            }
+            QEvent::GroupMessageRecall(m) => {
+                tracing::info!(target: "ricq::event::group_message_recall", "{:?}", m.inner)
+            }
+            QEvent::NewFriend(m) => {
+                tracing::info!(target: "ricq::event::new_friend", "{:?}", m.inner)
+            }
+            QEvent::GroupLeave(m) => {
+                tracing::info!(target: "ricq::event::group_leave", "{:?}", m.inner)
+            }
+            QEvent::GroupDisband(m) => {
+                tracing::info!(target: "ricq::event::group_disband", "{:?}", m.inner)
+            }
+            QEvent::FriendPoke(m) => {
+                tracing::info!(target: "ricq::event::friend_poke", "{:?}", m.inner)
+            }
+            QEvent::GroupPoke(m) => {
+                tracing::info!(target: "ricq::event::group_poke", "{:?}", m.inner)
+            }
+            QEvent::GroupGift(m) => {
+                tracing::info!(target: "ricq::event::group_gift", "{:?}", m.inner)
+            }
+            QEvent::GroupNameUpdate(m) => {
+                tracing::info!(target: "ricq::event::group_name_update", "{:?}", m.inner)
+            }
+            QEvent::DeleteFriend(m) => {
+                tracing::info!(target: "ricq::event::delete_friend", "{:?}", m.inner)
+            }
+            QEvent::MemberPermissionChange(m) => {
+                tracing::info!(target: "ricq::event::member_permission_change", "{:?}", m.inner)
+            }
+            QEvent::KickedOffline(m) => {
+                tracing::info!(target: "ricq::event::kicked_offline", "{:?}", m.inner)
+            }
+            QEvent::MSFOffline(m) => {
+                tracing::info!(target: "ricq::event::msf_offline", "{:?}", m.inner)
+            }
+            QEvent::ClientDisconnect(m) => {
+                tracing::info!(target: "ricq::event::client_disconnect", "{:?}", m.inner)
+            }
+            QEvent::DecodeError(m) => {
+                tracing::warn!(target: "ricq::event::decode_error", "{:?}", m.inner)
+            }
+            QEvent::SelfGroupMessage(m) => tracing::info!(
+                target: "ricq::event::self_group_message",
+                "SELF MESSAGE (GROUP={}): {}",
+                m.inner.group_code,
+                self.preview(&self.renderer.render(&m.inner.elements))
+            ),
+            QEvent::SelfFriendMessage(m) => tracing::info!(
+                target: "ricq::event::self_friend_message",
+                "SELF MESSAGE (FRIEND={}): {}",
+                m.inner.target,
+                self.preview(&self.renderer.render(&m.inner.elements))
+            ),
+            QEvent::ServerNotice(m) => {
+                tracing::info!(target: "ricq::event::server_notice", "{:?}", m.inner)
+            }
+            QEvent::FriendFileReceived(m) => {
+                tracing::info!(target: "ricq::event::friend_file_received", "{:?}", m.inner)
+            }
+            QEvent::MemberInfoChange(m) => {
+                tracing::info!(target: "ricq::event::member_info_change", "{:?}", m.inner)
+            }
+            QEvent::RawGrayTip(m) => {
+                tracing::info!(target: "ricq::event::raw_gray_tip", "{:?}", m.inner)
+            }
+            QEvent::AccountInfoChange(m) => {
+                tracing::info!(target: "ricq::event::account_info_change", "{:?}", m.inner)
+            }
+            QEvent::BotMute(m) => {
+                tracing::info!(target: "ricq::event::bot_mute", "{:?}", m.inner)
+            }
+            QEvent::ConnectionUnhealthy(m) => {
+                tracing::info!(target: "ricq::event::connection_unhealthy", "{:?}", m.inner)
+            }
+            QEvent::RawPacket(m) => {
+                tracing::debug!(target: "ricq::event::raw_packet", "{} seq={}", m.inner.command, m.inner.seq)
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_tracing_handler_filter() {
+        let handler = TracingHandler::new().deny(["GroupPoke"]);
+        assert!(handler.is_enabled("GroupMessage"));
+        assert!(!handler.is_enabled("GroupPoke"));
+        let handler = TracingHandler::new().allow(["GroupMessage", "FriendMessage"]);
+        assert!(handler.is_enabled("FriendMessage"));
+        assert!(!handler.is_enabled("Login"));
+        assert_eq!(
+            TracingHandler::new().preview_len(2).preview(&"你好呀"),
+            "你好..."
+        );
+    }
+}
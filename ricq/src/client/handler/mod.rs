@@ -1,5 +1,6 @@
 use std::future::Future;
 use std::pin::Pin;
+use std::sync::Arc;
 
 use async_trait::async_trait;
 use tokio::sync::{
@@ -9,6 +10,17 @@ use tokio::sync::{
 };
 
 use crate::client::event::*;
+use crate::Client;
+
+pub use chain::*;
+pub use envelope::*;
+pub use logging::*;
+pub use router::*;
+
+mod chain;
+mod envelope;
+mod logging;
+mod router;
 
 /// 所有需要外发的数据的枚举打包
 #[derive(Clone, derivative::Derivative)]
@@ -16,6 +28,8 @@ use crate::client::event::*;
 pub enum QEvent {
     /// 登录成功事件
     Login(i64),
+    /// 登录进度
+    LoginProgress(LoginProgress),
     /// 群消息
     GroupMessage(GroupMessageEvent),
     /// 群语音
@@ -26,6 +40,8 @@ pub enum QEvent {
     FriendAudioMessage(FriendAudioMessageEvent),
     /// 群临时消息
     GroupTempMessage(GroupTempMessageEvent),
+    /// 其他来源的临时消息
+    TempMessage(TempMessageEvent),
     /// 加群申请
     GroupRequest(JoinGroupRequestEvent),
     /// 加群申请
@@ -50,6 +66,8 @@ pub enum QEvent {
     FriendPoke(FriendPokeEvent),
     /// 群成员戳一戳
     GroupPoke(GroupPokeEvent),
+    /// 群礼物
+    GroupGift(GroupGiftEvent),
     /// 群名称修改
     GroupNameUpdate(GroupNameUpdateEvent),
     /// 好友删除
@@ -65,6 +83,118 @@ pub enum QEvent {
     /// 网络原因/客户端主动掉线
     /// 可用于掉线重连
     ClientDisconnect(ClientDisconnect),
+    /// 推送包解析失败，解析失败的推送会被跳过
+    DecodeError(DecodeErrorEvent),
+    /// 本账号在其他设备发送的群消息，需要开启 `ClientOptions::self_message_events`
+    SelfGroupMessage(SelfGroupMessageEvent),
+    /// 本账号在其他设备发送的私聊消息，需要开启 `ClientOptions::self_message_events`
+    SelfFriendMessage(SelfFriendMessageEvent),
+    /// 系统灰条、服务器地址变更等服务器通知
+    ServerNotice(ServerNoticeEvent),
+    /// 好友发送的离线文件
+    FriendFileReceived(FriendFileReceivedEvent),
+    /// 群成员信息变化（群名片、头衔、权限、进退群），由成员列表比对得出
+    MemberInfoChange(MemberInfoChangeEvent),
+    /// 未识别的群灰条，可按 templ_id 自行处理
+    RawGrayTip(RawGrayTipEvent),
+    /// 自己的昵称、年龄、性别或在线状态变化，由 refresh_account_info 比对得出
+    AccountInfoChange(AccountInfoChangeEvent),
+    /// 机器人自己被禁言或解除禁言
+    BotMute(BotMuteEvent),
+    /// 心跳检测到连接异常
+    ConnectionUnhealthy(ConnectionUnhealthyEvent),
+    /// 没有对应处理的推送包
+    RawPacket(RawPacketEvent),
+}
+
+impl QEvent {
+    /// 事件类型名，与枚举变体同名
+    pub fn kind(&self) -> &'static str {
+        match self {
+            QEvent::Login(_) => "Login",
+            QEvent::LoginProgress(_) => "LoginProgress",
+            QEvent::GroupMessage(_) => "GroupMessage",
+            QEvent::GroupAudioMessage(_) => "GroupAudioMessage",
+            QEvent::FriendMessage(_) => "FriendMessage",
+            QEvent::FriendAudioMessage(_) => "FriendAudioMessage",
+            QEvent::GroupTempMessage(_) => "GroupTempMessage",
+            QEvent::TempMessage(_) => "TempMessage",
+            QEvent::GroupRequest(_) => "GroupRequest",
+            QEvent::SelfInvited(_) => "SelfInvited",
+            QEvent::NewFriendRequest(_) => "NewFriendRequest",
+            QEvent::NewMember(_) => "NewMember",
+            QEvent::GroupMute(_) => "GroupMute",
+            QEvent::FriendMessageRecall(_) => "FriendMessageRecall",
+            QEvent::GroupMessageRecall(_) => "GroupMessageRecall",
+            QEvent::NewFriend(_) => "NewFriend",
+            QEvent::GroupLeave(_) => "GroupLeave",
+            QEvent::GroupDisband(_) => "GroupDisband",
+            QEvent::FriendPoke(_) => "FriendPoke",
+            QEvent::GroupPoke(_) => "GroupPoke",
+            QEvent::GroupGift(_) => "GroupGift",
+            QEvent::GroupNameUpdate(_) => "GroupNameUpdate",
+            QEvent::DeleteFriend(_) => "DeleteFriend",
+            QEvent::MemberPermissionChange(_) => "MemberPermissionChange",
+            QEvent::KickedOffline(_) => "KickedOffline",
+            QEvent::MSFOffline(_) => "MSFOffline",
+            QEvent::ClientDisconnect(_) => "ClientDisconnect",
+            QEvent::DecodeError(_) => "DecodeError",
+            QEvent::SelfGroupMessage(_) => "SelfGroupMessage",
+            QEvent::SelfFriendMessage(_) => "SelfFriendMessage",
+            QEvent::ServerNotice(_) => "ServerNotice",
+            QEvent::FriendFileReceived(_) => "FriendFileReceived",
+            QEvent::MemberInfoChange(_) => "MemberInfoChange",
+            QEvent::RawGrayTip(_) => "RawGrayTip",
+            QEvent::AccountInfoChange(_) => "AccountInfoChange",
+            QEvent::BotMute(_) => "BotMute",
+            QEvent::ConnectionUnhealthy(_) => "ConnectionUnhealthy",
+            QEvent::RawPacket(_) => "RawPacket",
+        }
+    }
+
+    /// 事件所属的 Client，Login 事件没有
+    pub fn client(&self) -> Option<&Arc<Client>> {
+        match self {
+            QEvent::Login(_) => None,
+            QEvent::LoginProgress(_) => None,
+            QEvent::GroupMessage(e) => Some(&e.client),
+            QEvent::GroupAudioMessage(e) => Some(&e.client),
+            QEvent::FriendMessage(e) => Some(&e.client),
+            QEvent::FriendAudioMessage(e) => Some(&e.client),
+            QEvent::GroupTempMessage(e) => Some(&e.client),
+            QEvent::TempMessage(e) => Some(&e.client),
+            QEvent::GroupRequest(e) => Some(&e.client),
+            QEvent::SelfInvited(e) => Some(&e.client),
+            QEvent::NewFriendRequest(e) => Some(&e.client),
+            QEvent::NewMember(e) => Some(&e.client),
+            QEvent::GroupMute(e) => Some(&e.client),
+            QEvent::FriendMessageRecall(e) => Some(&e.client),
+            QEvent::GroupMessageRecall(e) => Some(&e.client),
+            QEvent::NewFriend(e) => Some(&e.client),
+            QEvent::GroupLeave(e) => Some(&e.client),
+            QEvent::GroupDisband(e) => Some(&e.client),
+            QEvent::FriendPoke(e) => Some(&e.client),
+            QEvent::GroupPoke(e) => Some(&e.client),
+            QEvent::GroupGift(e) => Some(&e.client),
+            QEvent::GroupNameUpdate(e) => Some(&e.client),
+            QEvent::DeleteFriend(e) => Some(&e.client),
+            QEvent::MemberPermissionChange(e) => Some(&e.client),
+            QEvent::KickedOffline(e) => Some(&e.client),
+            QEvent::MSFOffline(e) => Some(&e.client),
+            QEvent::ClientDisconnect(e) => Some(&e.client),
+            QEvent::DecodeError(e) => Some(&e.client),
+            QEvent::SelfGroupMessage(e) => Some(&e.client),
+            QEvent::SelfFriendMessage(e) => Some(&e.client),
+            QEvent::ServerNotice(e) => Some(&e.client),
+            QEvent::FriendFileReceived(e) => Some(&e.client),
+            QEvent::MemberInfoChange(e) => Some(&e.client),
+            QEvent::RawGrayTip(e) => Some(&e.client),
+            QEvent::AccountInfoChange(e) => Some(&e.client),
+            QEvent::BotMute(e) => Some(&e.client),
+            QEvent::ConnectionUnhealthy(e) => Some(&e.client),
+            QEvent::RawPacket(e) => Some(&e.client),
+        }
+    }
 }
 
 /// 处理外发数据的接口
@@ -98,6 +228,11 @@ pub enum QEvent {
 #[async_trait]
 pub trait Handler: Sync {
     async fn handle(&self, event: QEvent);
+
+    /// 是否需要该类型的事件（类型名见 `QEvent::kind`），返回 false 时跳过对应推送的解析
+    fn subscribes(&self, _kind: &str) -> bool {
+        true
+    }
 }
 
 // 这里还有一种 Fn(QEvent) -> Fut 的写法，但是会与 PartlyHandler 冲突
@@ -121,18 +256,30 @@ impl Handler for DefaultHandler {
                 tracing::info!(
                     "MESSAGE (GROUP={}): {}",
                     m.inner.group_code,
-                    m.inner.elements
+                    m.inner.elements.to_text()
                 )
             }
             QEvent::FriendMessage(m) => {
                 tracing::info!(
                     "MESSAGE (FRIEND={}): {}",
                     m.inner.from_uin,
-                    m.inner.elements
+                    m.inner.elements.to_text()
                 )
             }
             QEvent::GroupTempMessage(m) => {
-                tracing::info!("MESSAGE (TEMP={}): {}", m.inner.from_uin, m.inner.elements)
+                tracing::info!(
+                    "MESSAGE (TEMP={}): {}",
+                    m.inner.from_uin,
+                    m.inner.elements.to_text()
+                )
+            }
+            QEvent::TempMessage(m) => {
+                tracing::info!(
+                    "MESSAGE (TEMP={}, SCENE={:?}): {}",
+                    m.inner.from_uin,
+                    m.inner.session.scene,
+                    m.inner.elements.to_text()
+                )
             }
             QEvent::GroupRequest(m) => {
                 tracing::info!(
@@ -178,14 +325,50 @@ impl Handler for WatchSender<QEvent> {
     }
 }
 
+/// `Client::events` 的事件流落后时的处理方式
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum LagPolicy {
+    /// 丢弃来不及处理的事件，继续接收之后的事件
+    #[default]
+    Skip,
+    /// 结束事件流
+    Close,
+}
+
+/// 同时把事件发给用户 Handler 和 `Client::events` 的订阅者
+pub(crate) struct EventFanout<H> {
+    pub(crate) inner: H,
+    pub(crate) sender: BroadcastSender<QEvent>,
+}
+
+#[async_trait]
+impl<H> Handler for EventFanout<H>
+where
+    H: Handler + Send,
+{
+    async fn handle(&self, event: QEvent) {
+        // 没有订阅者时不 clone 事件
+        if self.sender.receiver_count() > 0 {
+            self.sender.send(event.clone()).ok();
+        }
+        self.inner.handle(event).await
+    }
+
+    fn subscribes(&self, kind: &str) -> bool {
+        self.sender.receiver_count() > 0 || self.inner.subscribes(kind)
+    }
+}
+
 #[async_trait]
 pub trait PartlyHandler: Sync {
     async fn handle_login(&self, _: i64) {}
+    async fn handle_login_progress(&self, _progress: LoginProgress) {}
     async fn handle_group_message(&self, _event: GroupMessageEvent) {}
     async fn handle_group_audio(&self, _event: GroupAudioMessageEvent) {}
     async fn handle_friend_message(&self, _event: FriendMessageEvent) {}
     async fn handle_friend_audio(&self, _event: FriendAudioMessageEvent) {}
     async fn handle_group_temp_message(&self, _event: GroupTempMessageEvent) {}
+    async fn handle_temp_message(&self, _event: TempMessageEvent) {}
     async fn handle_group_request(&self, _event: JoinGroupRequestEvent) {}
     async fn handle_self_invited(&self, _event: SelfInvitedEvent) {}
     async fn handle_friend_request(&self, _event: NewFriendRequestEvent) {}
@@ -198,12 +381,24 @@ pub trait PartlyHandler: Sync {
     async fn handle_group_disband(&self, _event: GroupDisbandEvent) {}
     async fn handle_friend_poke(&self, _event: FriendPokeEvent) {}
     async fn handle_group_poke(&self, _event: GroupPokeEvent) {}
+    async fn handle_group_gift(&self, _event: GroupGiftEvent) {}
     async fn handle_group_name_update(&self, _event: GroupNameUpdateEvent) {}
     async fn handle_delete_friend(&self, _event: DeleteFriendEvent) {}
     async fn handle_member_permission_change(&self, _event: MemberPermissionChangeEvent) {}
     async fn handle_kicked_offline(&self, _event: KickedOfflineEvent) {}
     async fn handle_msf_offline(&self, _event: MSFOfflineEvent) {}
     async fn handle_client_disconnect(&self, _event: ClientDisconnect) {}
+    async fn handle_decode_error(&self, _event: DecodeErrorEvent) {}
+    async fn handle_self_group_message(&self, _event: SelfGroupMessageEvent) {}
+    async fn handle_self_friend_message(&self, _event: SelfFriendMessageEvent) {}
+    async fn handle_server_notice(&self, _event: ServerNoticeEvent) {}
+    async fn handle_friend_file_received(&self, _event: FriendFileReceivedEvent) {}
+    async fn handle_member_info_change(&self, _event: MemberInfoChangeEvent) {}
+    async fn handle_raw_gray_tip(&self, _event: RawGrayTipEvent) {}
+    async fn handle_account_info_change(&self, _event: AccountInfoChangeEvent) {}
+    async fn handle_bot_mute(&self, _event: BotMuteEvent) {}
+    async fn handle_connection_unhealthy(&self, _event: ConnectionUnhealthyEvent) {}
+    async fn handle_raw_packet(&self, _event: RawPacketEvent) {}
 }
 
 #[async_trait]
@@ -214,11 +409,13 @@ where
     async fn handle(&self, event: QEvent) {
         match event {
             QEvent::Login(uin) => self.handle_login(uin).await,
+            QEvent::LoginProgress(p) => self.handle_login_progress(p).await,
             QEvent::GroupMessage(m) => self.handle_group_message(m).await,
             QEvent::GroupAudioMessage(m) => self.handle_group_audio(m).await,
             QEvent::FriendMessage(m) => self.handle_friend_message(m).await,
             QEvent::FriendAudioMessage(m) => self.handle_friend_audio(m).await,
             QEvent::GroupTempMessage(m) => self.handle_group_temp_message(m).await,
+            QEvent::TempMessage(m) => self.handle_temp_message(m).await,
             QEvent::GroupRequest(m) => self.handle_group_request(m).await,
             QEvent::SelfInvited(m) => self.handle_self_invited(m).await,
             QEvent::NewFriendRequest(m) => self.handle_friend_request(m).await,
@@ -231,12 +428,24 @@ where
             QEvent::GroupDisband(m) => self.handle_group_disband(m).await,
             QEvent::FriendPoke(m) => self.handle_friend_poke(m).await,
             QEvent::GroupPoke(m) => self.handle_group_poke(m).await,
+            QEvent::GroupGift(m) => self.handle_group_gift(m).await,
             QEvent::GroupNameUpdate(m) => self.handle_group_name_update(m).await,
             QEvent::DeleteFriend(m) => self.handle_delete_friend(m).await,
             QEvent::MemberPermissionChange(m) => self.handle_member_permission_change(m).await,
             QEvent::KickedOffline(m) => self.handle_kicked_offline(m).await,
             QEvent::MSFOffline(m) => self.handle_msf_offline(m).await,
             QEvent::ClientDisconnect(m) => self.handle_client_disconnect(m).await,
+            QEvent::DecodeError(m) => self.handle_decode_error(m).await,
+            QEvent::SelfGroupMessage(m) => self.handle_self_group_message(m).await,
+            QEvent::SelfFriendMessage(m) => self.handle_self_friend_message(m).await,
+            QEvent::ServerNotice(m) => self.handle_server_notice(m).await,
+            QEvent::FriendFileReceived(m) => self.handle_friend_file_received(m).await,
+            QEvent::MemberInfoChange(m) => self.handle_member_info_change(m).await,
+            QEvent::RawGrayTip(m) => self.handle_raw_gray_tip(m).await,
+            QEvent::AccountInfoChange(m) => self.handle_account_info_change(m).await,
+            QEvent::BotMute(m) => self.handle_bot_mute(m).await,
+            QEvent::ConnectionUnhealthy(m) => self.handle_connection_unhealthy(m).await,
+            QEvent::RawPacket(m) => self.handle_raw_packet(m).await,
         }
     }
 }
@@ -0,0 +1,134 @@
+use std::collections::HashSet;
+use std::future::Future;
+
+use async_trait::async_trait;
+use futures_util::future::BoxFuture;
+use futures_util::FutureExt;
+
+use super::{Handler, QEvent};
+use crate::client::event::*;
+
+type Route = Box<dyn Fn(&QEvent) -> Option<BoxFuture<'static, ()>> + Send + Sync + 'static>;
+
+/// 按事件类型注册处理函数的 Handler，每个事件只会交给对应类型的处理函数
+///
+/// # Examples
+///
+/// ```ignore
+/// let handler = EventRouter::new()
+///     .on_group_message(|e| async move { tracing::info!("{}", e.inner.elements) })
+///     .on_friend_poke(|e| async move { tracing::info!("{:?}", e.inner) });
+/// let client = Client::new(device, version, qsign_client, handler);
+/// ```
+#[derive(Default)]
+pub struct EventRouter {
+    routes: Vec<Route>,
+    /// 注册过的事件类型
+    kinds: HashSet<&'static str>,
+    /// 是否注册了 on_event
+    all: bool,
+}
+
+impl EventRouter {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    fn route<R>(mut self, kind: Option<&'static str>, route: R) -> Self
+    where
+        R: Fn(&QEvent) -> Option<BoxFuture<'static, ()>> + Send + Sync + 'static,
+    {
+        match kind {
+            Some(kind) => {
+                self.kinds.insert(kind);
+            }
+            None => self.all = true,
+        }
+        self.routes.push(Box::new(route));
+        self
+    }
+
+    /// 所有事件
+    pub fn on_event<F, Fut>(self, f: F) -> Self
+    where
+        F: Fn(QEvent) -> Fut + Send + Sync + 'static,
+        Fut: Future<Output = ()> + Send + 'static,
+    {
+        self.route(None, move |e| Some(f(e.clone()).boxed()))
+    }
+}
+
+macro_rules! event_routes {
+    ($($method: ident => $variant: ident($ty: ty);)*) => {
+        impl EventRouter {
+            $(
+                pub fn $method<F, Fut>(self, f: F) -> Self
+                where
+                    F: Fn($ty) -> Fut + Send + Sync + 'static,
+                    Fut: Future<Output = ()> + Send + 'static,
+                {
+                    self.route(Some(stringify!($variant)), move |e| match e {
+                        QEvent::$variant(e) => Some(f(e.clone()).boxed()),
+                        _ => None,
+                    })
+                }
+            )*
+        }
+    };
+}
+
+event_routes! {
+    on_login => Login(i64);
+    on_login_progress => LoginProgress(LoginProgress);
+    on_group_message => GroupMessage(GroupMessageEvent);
+    on_group_audio => GroupAudioMessage(GroupAudioMessageEvent);
+    on_friend_message => FriendMessage(FriendMessageEvent);
+    on_friend_audio => FriendAudioMessage(FriendAudioMessageEvent);
+    on_group_temp_message => GroupTempMessage(GroupTempMessageEvent);
+    on_temp_message => TempMessage(TempMessageEvent);
+    on_group_request => GroupRequest(JoinGroupRequestEvent);
+    on_self_invited => SelfInvited(SelfInvitedEvent);
+    on_friend_request => NewFriendRequest(NewFriendRequestEvent);
+    on_new_member => NewMember(NewMemberEvent);
+    on_group_mute => GroupMute(GroupMuteEvent);
+    on_friend_message_recall => FriendMessageRecall(FriendMessageRecallEvent);
+    on_group_message_recall => GroupMessageRecall(GroupMessageRecallEvent);
+    on_new_friend => NewFriend(NewFriendEvent);
+    on_group_leave => GroupLeave(GroupLeaveEvent);
+    on_group_disband => GroupDisband(GroupDisbandEvent);
+    on_friend_poke => FriendPoke(FriendPokeEvent);
+    on_group_poke => GroupPoke(GroupPokeEvent);
+    on_group_gift => GroupGift(GroupGiftEvent);
+    on_group_name_update => GroupNameUpdate(GroupNameUpdateEvent);
+    on_delete_friend => DeleteFriend(DeleteFriendEvent);
+    on_member_permission_change => MemberPermissionChange(MemberPermissionChangeEvent);
+    on_kicked_offline => KickedOffline(KickedOfflineEvent);
+    on_msf_offline => MSFOffline(MSFOfflineEvent);
+    on_client_disconnect => ClientDisconnect(ClientDisconnect);
+    on_decode_error => DecodeError(DecodeErrorEvent);
+    on_self_group_message => SelfGroupMessage(SelfGroupMessageEvent);
+    on_self_friend_message => SelfFriendMessage(SelfFriendMessageEvent);
+    on_server_notice => ServerNotice(ServerNoticeEvent);
+    on_friend_file_received => FriendFileReceived(FriendFileReceivedEvent);
+    on_member_info_change => MemberInfoChange(MemberInfoChangeEvent);
+    on_raw_gray_tip => RawGrayTip(RawGrayTipEvent);
+    on_account_info_change => AccountInfoChange(AccountInfoChangeEvent);
+    on_bot_mute => BotMute(BotMuteEvent);
+    on_connection_unhealthy => ConnectionUnhealthy(ConnectionUnhealthyEvent);
+    on_raw_packet => RawPacket(RawPacketEvent);
+}
+
+#[async_trait]
+impl Handler for EventRouter {
+    fn subscribes(&self, kind: &str) -> bool {
+        self.all || self.kinds.contains(kind)
+    }
+
+    async fn handle(&self, event: QEvent) {
+        for route in self.routes.iter() {
+            if let Some(fut) = route(&event) {
+                fut.await
+            }
+        }
+    }
+}
@@ -0,0 +1,79 @@
+use std::collections::HashSet;
+use std::net::SocketAddr;
+
+use ricq_core::common::RQAddr;
+
+use crate::{Client, RQError, RQResult};
+
+impl Client {
+    /// 设置优先使用的 highway 地址，排在服务器下发的地址之前，只支持 IPv4
+    pub async fn set_preferred_highway_addrs(&self, addrs: Vec<SocketAddr>) -> RQResult<()> {
+        if let Some(addr) = addrs.iter().find(|a| !a.is_ipv4()) {
+            return Err(RQError::Other(format!("highway addr must be ipv4: {addr}")));
+        }
+        *self.highway_preferred.write().await = addrs;
+        Ok(())
+    }
+
+    /// 不再使用该 highway 地址，用于上传失败的服务器
+    pub async fn block_highway_addr(&self, addr: SocketAddr) {
+        self.highway_blocked.write().await.insert(addr);
+    }
+
+    /// 恢复使用 block_highway_addr 屏蔽的地址，返回是否曾被屏蔽
+    pub async fn unblock_highway_addr(&self, addr: &SocketAddr) -> bool {
+        self.highway_blocked.write().await.remove(addr)
+    }
+
+    /// 按使用顺序返回可用的 highway 地址
+    pub async fn highway_candidates(&self) -> Vec<SocketAddr> {
+        let server: Vec<SocketAddr> = if self.options.highway.only_preferred {
+            Vec::new()
+        } else {
+            let addrs = self.highway_addrs.read().await;
+            addrs.iter().map(|addr| (*addr).into()).collect()
+        };
+        merge_highway_addrs(
+            &self.highway_preferred.read().await,
+            &server,
+            &self.highway_blocked.read().await,
+        )
+    }
+
+    /// 上传时使用的 highway 地址
+    pub async fn select_highway_addr(&self) -> Option<RQAddr> {
+        self.highway_candidates()
+            .await
+            .first()
+            .map(|addr| RQAddr::from(*addr))
+    }
+}
+
+fn merge_highway_addrs(
+    preferred: &[SocketAddr],
+    server: &[SocketAddr],
+    blocked: &HashSet<SocketAddr>,
+) -> Vec<SocketAddr> {
+    let mut addrs: Vec<SocketAddr> = Vec::new();
+    for addr in preferred.iter().chain(server) {
+        if !blocked.contains(addr) && !addrs.contains(addr) {
+            addrs.push(*addr);
+        }
+    }
+    addrs
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_merge_highway_addrs() {
+        let a: SocketAddr = "1.1.1.1:80".parse().unwrap();
+        let b: SocketAddr = "2.2.2.2:80".parse().unwrap();
+        let c: SocketAddr = "3.3.3.3:443".parse().unwrap();
+        let blocked = HashSet::from([b]);
+        assert_eq!(merge_highway_addrs(&[c], &[a, b, c], &blocked), vec![c, a]);
+        assert!(merge_highway_addrs(&[], &[b], &blocked).is_empty());
+    }
+}
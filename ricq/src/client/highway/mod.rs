@@ -1,8 +1,12 @@
 use bytes::Bytes;
 
+mod addrs;
 mod codec;
 mod net;
 
+pub use net::stream_md5;
+pub(crate) use net::stream_md5_into;
+
 pub struct HighwayFrame {
     pub head: Bytes,
     pub body: Bytes,
@@ -1,10 +1,13 @@
+use std::io::SeekFrom;
 use std::net::SocketAddr;
 use std::time::Duration;
 
 use bytes::Bytes;
 use futures_util::{SinkExt, StreamExt};
+use tokio::io::{AsyncRead, AsyncReadExt, AsyncSeek, AsyncSeekExt};
 use tokio::net::TcpStream;
 use tokio_util::codec::Framed;
+use tokio_util::sync::CancellationToken;
 
 use ricq_core::command::common::PbToBytes;
 use ricq_core::crypto::qqtea_encrypt;
@@ -20,8 +23,37 @@ impl Client {
     pub async fn highway_upload_bdh(
         &self,
         addr: SocketAddr,
-        mut input: BdhInput,
+        input: BdhInput,
+        data: &[u8],
+    ) -> RQResult<Bytes> {
+        self.highway_upload_bdh_with_cancel(addr, input, data, &CancellationToken::new())
+            .await
+    }
+
+    /// 可取消的上传，每个分片发送前检查 `cancel`，取消后返回 `RQError::Cancelled`
+    pub async fn highway_upload_bdh_with_cancel(
+        &self,
+        addr: SocketAddr,
+        input: BdhInput,
         data: &[u8],
+        cancel: &CancellationToken,
+    ) -> RQResult<Bytes> {
+        let sum = md5::compute(data).to_vec();
+        self.highway_upload_bdh_reader(addr, input, data, data.len() as u64, sum, cancel)
+            .await
+    }
+
+    /// 从 reader 分片读取并上传，不需要把整个文件读入内存
+    ///
+    /// size 和 file_md5 需要预先计算，可以使用 [`stream_md5`]
+    pub async fn highway_upload_bdh_reader<R: AsyncRead + Unpin>(
+        &self,
+        addr: SocketAddr,
+        mut input: BdhInput,
+        mut reader: R,
+        size: u64,
+        file_md5: Vec<u8>,
+        cancel: &CancellationToken,
     ) -> RQResult<Bytes> {
         if input.encrypt {
             let session_key = self.highway_session.read().await.session_key.clone();
@@ -32,9 +64,6 @@ impl Client {
             .map_err(RQError::IO)?;
         let mut stream = Framed::new(stream, HighwayCodec);
         // send heartbeat
-        let sum = md5::compute(data).to_vec();
-        let length = data.len();
-
         if input.send_echo {
             stream
                 .send(HighwayFrame {
@@ -46,13 +75,15 @@ impl Client {
         }
         let mut ticket = input.ticket;
         let mut rsp_ext = Bytes::new();
-        let data = Bytes::copy_from_slice(data);
-        let len = data.len();
-        let chunk_size = input.chunk_size;
+        let chunk_size = input.chunk_size as u64;
 
-        for i in (0..len).step_by(chunk_size) {
-            let min = std::cmp::min(i + chunk_size, len);
-            let chunk = data.slice(i..min);
+        for offset in (0..size).step_by(chunk_size as usize) {
+            if cancel.is_cancelled() {
+                return Err(RQError::Cancelled);
+            }
+            let mut chunk = vec![0; std::cmp::min(chunk_size, size - offset) as usize];
+            reader.read_exact(&mut chunk).await.map_err(RQError::IO)?;
+            let chunk = Bytes::from(chunk);
             let head = pb::ReqDataHighwayHead {
                 msg_basehead: Some(self.highway_session.read().await.build_basehead(
                     "PicUp.DataUp".into(),
@@ -61,11 +92,11 @@ impl Client {
                     2052,
                 )),
                 msg_seghead: Some(self.highway_session.read().await.build_seghead(
-                    length as i64,
-                    i as i64,
+                    size as i64,
+                    offset as i64,
                     &chunk,
                     ticket.clone(),
-                    sum.clone(),
+                    file_md5.clone(),
                 )),
                 req_extendinfo: input.ext.clone(),
                 ..Default::default()
@@ -102,6 +133,41 @@ impl Client {
     }
 }
 
+/// 计算 reader 从当前位置到结尾的 md5 和长度，计算后回到原来的位置
+pub async fn stream_md5<R: AsyncRead + AsyncSeek + Unpin>(
+    reader: &mut R,
+) -> RQResult<(Vec<u8>, u64)> {
+    let mut ctx = [md5::Context::new()];
+    let size = stream_md5_into(reader, &mut ctx).await?;
+    let [ctx] = ctx;
+    Ok((ctx.compute().to_vec(), size))
+}
+
+/// 把 reader 剩余的数据写入多个 md5 context，只读取一遍，结束后回到原来的位置
+pub(crate) async fn stream_md5_into<R: AsyncRead + AsyncSeek + Unpin>(
+    reader: &mut R,
+    ctxs: &mut [md5::Context],
+) -> RQResult<u64> {
+    let start = reader.stream_position().await.map_err(RQError::IO)?;
+    let mut buf = vec![0; 64 * 1024];
+    let mut size = 0;
+    loop {
+        let n = reader.read(&mut buf).await.map_err(RQError::IO)?;
+        if n == 0 {
+            break;
+        }
+        for ctx in ctxs.iter_mut() {
+            ctx.consume(&buf[..n]);
+        }
+        size += n as u64;
+    }
+    reader
+        .seek(SeekFrom::Start(start))
+        .await
+        .map_err(RQError::IO)?;
+    Ok(size)
+}
+
 async fn read_response(stream: &mut Framed<TcpStream, HighwayCodec>) -> RQResult<HighwayFrame> {
     loop {
         if let Some(resp) = stream.next().await {
@@ -109,3 +175,21 @@ async fn read_response(stream: &mut Framed<TcpStream, HighwayCodec>) -> RQResult
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use std::io::Cursor;
+
+    use super::*;
+
+    #[tokio::test]
+    async fn test_stream_md5() {
+        let data = vec![7u8; 200 * 1024];
+        let mut reader = Cursor::new(data.clone());
+        reader.set_position(10);
+        let (sum, size) = stream_md5(&mut reader).await.unwrap();
+        assert_eq!(sum, md5::compute(&data[10..]).to_vec());
+        assert_eq!(size, data.len() as u64 - 10);
+        assert_eq!(reader.position(), 10);
+    }
+}
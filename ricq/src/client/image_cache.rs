@@ -0,0 +1,103 @@
+use std::sync::Mutex;
+
+use cached::{Cached, SizedCache};
+use serde::{Deserialize, Serialize};
+
+use ricq_core::msg::elem::{FriendImage, GroupImage};
+
+/// 已上传图片的缓存，相同的图片再次发送时跳过 image store 请求
+///
+/// 可以自行实现以持久化到文件或数据库，通过 `Client::set_image_cache` 设置
+pub trait ImageCache: Send + Sync {
+    fn get_group_image(&self, group_code: i64, md5: &[u8]) -> Option<GroupImage>;
+    fn put_group_image(&self, group_code: i64, image: &GroupImage);
+    fn get_friend_image(&self, target: i64, md5: &[u8]) -> Option<FriendImage>;
+    fn put_friend_image(&self, target: i64, image: &FriendImage);
+}
+
+/// 导出的缓存内容，用于持久化
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct ImageCacheEntries {
+    pub group: Vec<(i64, GroupImage)>,
+    pub friend: Vec<(i64, FriendImage)>,
+}
+
+/// 默认的内存缓存，超出容量时淘汰最久未使用的图片
+pub struct MemoryImageCache {
+    group: Mutex<SizedCache<(i64, Vec<u8>), GroupImage>>,
+    friend: Mutex<SizedCache<(i64, Vec<u8>), FriendImage>>,
+}
+
+impl MemoryImageCache {
+    pub fn new(capacity: usize) -> Self {
+        Self {
+            group: Mutex::new(SizedCache::with_size(capacity)),
+            friend: Mutex::new(SizedCache::with_size(capacity)),
+        }
+    }
+
+    /// 导出缓存内容
+    pub fn export(&self) -> ImageCacheEntries {
+        ImageCacheEntries {
+            group: entries(&self.group.lock().unwrap()),
+            friend: entries(&self.friend.lock().unwrap()),
+        }
+    }
+
+    /// 导入之前导出的缓存内容
+    pub fn import(&self, entries: ImageCacheEntries) {
+        // 导出顺序为最近使用的在前，倒序插入以保持淘汰顺序
+        for (group_code, image) in entries.group.into_iter().rev() {
+            self.put_group_image(group_code, &image);
+        }
+        for (target, image) in entries.friend.into_iter().rev() {
+            self.put_friend_image(target, &image);
+        }
+    }
+}
+
+fn entries<T: Clone>(cache: &SizedCache<(i64, Vec<u8>), T>) -> Vec<(i64, T)> {
+    cache
+        .key_order()
+        .zip(cache.value_order())
+        .map(|((target, _), image)| (*target, image.clone()))
+        .collect()
+}
+
+impl Default for MemoryImageCache {
+    fn default() -> Self {
+        Self::new(256)
+    }
+}
+
+impl ImageCache for MemoryImageCache {
+    fn get_group_image(&self, group_code: i64, md5: &[u8]) -> Option<GroupImage> {
+        self.group
+            .lock()
+            .unwrap()
+            .cache_get(&(group_code, md5.to_vec()))
+            .cloned()
+    }
+
+    fn put_group_image(&self, group_code: i64, image: &GroupImage) {
+        self.group
+            .lock()
+            .unwrap()
+            .cache_set((group_code, image.md5.clone()), image.clone());
+    }
+
+    fn get_friend_image(&self, target: i64, md5: &[u8]) -> Option<FriendImage> {
+        self.friend
+            .lock()
+            .unwrap()
+            .cache_get(&(target, md5.to_vec()))
+            .cloned()
+    }
+
+    fn put_friend_image(&self, target: i64, image: &FriendImage) {
+        self.friend
+            .lock()
+            .unwrap()
+            .cache_set((target, image.md5.clone()), image.clone());
+    }
+}
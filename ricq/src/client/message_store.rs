@@ -0,0 +1,69 @@
+use std::sync::Mutex;
+
+use cached::{Cached, SizedCache};
+
+use ricq_core::structs::{FriendMessage, GroupMessage};
+
+/// 收到的消息存储，撤回事件到达时取出原消息填充到 `GroupMessageRecall::message` 等字段
+///
+/// 可以自行实现以持久化到文件或数据库，通过 `Client::set_message_store` 设置
+pub trait MessageStore: Send + Sync {
+    fn put_group_message(&self, message: &GroupMessage);
+    fn get_group_message(&self, group_code: i64, seq: i32) -> Option<GroupMessage>;
+    fn put_friend_message(&self, message: &FriendMessage);
+    fn get_friend_message(&self, friend_uin: i64, seq: i32) -> Option<FriendMessage>;
+}
+
+/// 内存中的消息存储，超出容量时淘汰最早的消息
+pub struct MemoryMessageStore {
+    group: Mutex<SizedCache<(i64, i32), GroupMessage>>,
+    friend: Mutex<SizedCache<(i64, i32), FriendMessage>>,
+}
+
+impl MemoryMessageStore {
+    pub fn new(capacity: usize) -> Self {
+        Self {
+            group: Mutex::new(SizedCache::with_size(capacity)),
+            friend: Mutex::new(SizedCache::with_size(capacity)),
+        }
+    }
+}
+
+impl Default for MemoryMessageStore {
+    fn default() -> Self {
+        Self::new(4096)
+    }
+}
+
+impl MessageStore for MemoryMessageStore {
+    fn put_group_message(&self, message: &GroupMessage) {
+        let mut group = self.group.lock().unwrap();
+        // 分片消息的每个 seq 都可以被撤回
+        for seq in &message.seqs {
+            group.cache_set((message.group_code, *seq), message.clone());
+        }
+    }
+
+    fn get_group_message(&self, group_code: i64, seq: i32) -> Option<GroupMessage> {
+        self.group
+            .lock()
+            .unwrap()
+            .cache_get(&(group_code, seq))
+            .cloned()
+    }
+
+    fn put_friend_message(&self, message: &FriendMessage) {
+        let mut friend = self.friend.lock().unwrap();
+        for seq in &message.seqs {
+            friend.cache_set((message.from_uin, *seq), message.clone());
+        }
+    }
+
+    fn get_friend_message(&self, friend_uin: i64, seq: i32) -> Option<FriendMessage> {
+        self.friend
+            .lock()
+            .unwrap()
+            .cache_get(&(friend_uin, seq))
+            .cloned()
+    }
+}
@@ -1,38 +1,62 @@
 use bytes::Bytes;
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
+use std::net::SocketAddr;
 use std::sync::atomic::{AtomicBool, AtomicI64, AtomicU8, Ordering};
 use std::sync::Arc;
 use std::time::UNIX_EPOCH;
 
 use cached::Cached;
-use futures_util::StreamExt;
+use futures_util::{Stream, StreamExt};
+use serde::{Deserialize, Serialize};
 use tokio::sync::{broadcast, RwLock};
 use tokio::sync::{oneshot, Mutex};
 use tokio::time::{sleep, Duration};
 
-pub use net::{Connector, DefaultConnector};
+pub use net::{Connector, DefaultConnector, PacketPriority};
 use ricq_core::command::common::PbToBytes;
+use ricq_core::command::friendlist::FriendListResponse;
 use ricq_core::command::online_push::GroupMessagePart;
 use ricq_core::command::profile_service::GroupSystemMessages;
 use ricq_core::common::RQAddr;
 use ricq_core::hex::decode_hex;
 use ricq_core::protocol::version::Version;
 use ricq_core::protocol::{device::Device, packet::Packet};
-use ricq_core::structs::{AccountInfo, AddressInfo, OtherClientInfo};
+use ricq_core::structs::{
+    AccountInfo, AddressInfo, GroupMemberInfo, MessageReceipt, MessageTarget, OtherClientInfo,
+    Status,
+};
 use ricq_core::Engine;
-pub use ricq_core::Token;
-
+pub use highway::stream_md5;
+pub use ricq_core::{A1Token, Token};
+
+use crate::client::capture::{Direction, PacketRecorder};
+use crate::client::energy::EnergyProvider;
+use crate::client::event::{ConnectionUnhealthy, ConnectionUnhealthyEvent, UnhealthyReason};
+use crate::client::image_cache::{ImageCache, MemoryImageCache};
+use crate::client::message_store::MessageStore;
+use crate::client::resolver::Resolver;
+use crate::config::ClientOptions;
+use crate::handler::{LagPolicy, QEvent};
 use crate::qsign::{QSignClient, QSignResponse, RequestCallback, SignData};
 use crate::{RQError, RQResult};
 
 mod api;
+pub mod capture;
+pub mod energy;
 pub mod event;
 pub mod handler;
 mod highway;
+pub mod image_cache;
+pub mod message_store;
 pub(crate) mod net;
 mod processor;
 pub mod qimei;
+pub mod resolver;
+mod server_list;
 mod tcp;
+pub mod throttle;
+
+const SIG_REFRESH_MIN_INTERVAL: Duration = Duration::from_secs(60);
 
 const SIGN_COMMANDS: &str = r#"ConnAuthSvr.fast_qq_login
 ConnAuthSvr.sdk_auth_api
@@ -122,6 +146,11 @@ wtlogin.trans_emp
 wtlogin_device.login
 wtlogin_device.tran_sim_emp"#;
 
+type PacketMatcher = (
+    Box<dyn Fn(&Packet) -> bool + Send + Sync + 'static>,
+    oneshot::Sender<Packet>,
+);
+
 pub struct Client {
     /// QEvent Handler 调用 handle 方法外发 QEvent
     handler: Box<dyn handler::Handler + Sync + Send + 'static>,
@@ -136,14 +165,25 @@ pub struct Client {
     pub online: AtomicBool,
     /// 心跳包是否已启用
     pub heartbeat_enabled: AtomicBool,
+    /// 自动刷新登录凭证是否已启用
+    pub sig_refresh_enabled: AtomicBool,
+    /// set_online_status 设置的在线状态，重新注册后恢复
+    online_status: RwLock<Option<Status>>,
 
     // 包相关
     /// 外发包 Sender
     out_pkt_sender: net::OutPktSender,
     /// send_and_wait WaitMap
     packet_promises: RwLock<HashMap<i32, oneshot::Sender<Packet>>>,
+    /// send_and_wait_matching / wait_packet 注册的匹配器，按注册顺序匹配
+    packet_matchers: Mutex<Vec<PacketMatcher>>,
     /// 当前客户端发送消息后使用 cache 避免上报自身消息事件
     receipt_waiters: Mutex<cached::TimedCache<i32, oneshot::Sender<i32>>>,
+    /// 音乐、链接分享没有 rand，按发送目标等待自身消息的推送
+    share_waiters: Mutex<cached::TimedCache<MessageTarget, oneshot::Sender<MessageReceipt>>>,
+    /// 等待回执超时的自身消息，之后收到的推送直接丢弃，不作为消息事件上报
+    late_receipts: Mutex<cached::TimedCache<i32, ()>>,
+    late_shares: Mutex<cached::TimedCache<MessageTarget, ()>>,
 
     // account info
     pub account_info: RwLock<AccountInfo>,
@@ -160,17 +200,67 @@ pub struct Client {
 
     /// 群消息 builder 寄存 <div_seq, parts> : parts is sorted by pkg_index
     group_message_builder: RwLock<cached::TimedCache<i32, Vec<GroupMessagePart>>>,
+    /// 私聊消息 builder 寄存 <(from_uin, div_seq), parts>
+    friend_message_builder:
+        RwLock<cached::TimedCache<(i64, i32), Vec<ricq_core::pb::msg::Message>>>,
     /// 每个 28 Byte
     c2c_cache: RwLock<cached::TimedCache<(i64, i64, i32, i64), ()>>,
     push_req_cache: RwLock<cached::TimedCache<(i16, i64), ()>>,
     push_trans_cache: RwLock<cached::TimedCache<(i32, i64), ()>>,
     group_sys_message_cache: RwLock<GroupSystemMessages>,
+    /// 群成员列表缓存 <group_code, members>
+    group_member_cache: RwLock<cached::TimedCache<i64, Arc<Vec<GroupMemberInfo>>>>,
+    /// 同一个群同时只加载一次成员列表
+    group_member_loading: Mutex<HashMap<i64, Arc<Mutex<()>>>>,
+    /// 好友列表缓存
+    friend_cache: RwLock<cached::TimedCache<(), Arc<FriendListResponse>>>,
+    /// 好友列表同时只加载一次
+    friend_loading: Mutex<()>,
+    /// 引用回复的原消息 <(是否群消息, group_code 或好友 QQ, seq), elements>
+    quoted_cache: RwLock<cached::TimedCache<(bool, i64, i32), ricq_core::msg::MessageChain>>,
+    /// 需要比对成员列表的群
+    member_diff: RwLock<HashMap<i64, api::MemberDiffState>>,
 
     pub highway_session: RwLock<ricq_core::highway::Session>,
     pub highway_addrs: RwLock<Vec<RQAddr>>,
+    /// 优先使用的 highway 地址，初始为 `HighwayOptions::preferred`
+    highway_preferred: RwLock<Vec<SocketAddr>>,
+    /// 不再使用的 highway 地址
+    highway_blocked: RwLock<HashSet<SocketAddr>>,
 
     packet_handler: RwLock<HashMap<String, broadcast::Sender<Packet>>>,
+    /// 已上传图片缓存，为 None 时不缓存
+    image_cache: RwLock<Option<Arc<dyn ImageCache>>>,
+    /// 收到的消息存储，用于撤回事件，为 None 时不保存
+    message_store: RwLock<Option<Arc<dyn MessageStore>>>,
+    /// 发消息频率限制
+    send_limiter: throttle::SendLimiter,
+    throttle_callback: RwLock<Option<throttle::ThrottleCallback>>,
+    /// 本地 T544 实现，为 None 时使用 qsign_client
+    energy_provider: RwLock<Option<Arc<dyn EnergyProvider>>>,
+    /// 抓包，为 None 时不记录
+    packet_recorder: RwLock<Option<Arc<dyn PacketRecorder>>>,
+    /// 推送包处理任务池，见 `PacketWorkers`
+    packet_workers: std::sync::Mutex<Option<Arc<processor::PacketWorkers>>>,
+    /// 登录后第一次同步消息的处理方式
+    pub(crate) offline_sync: processor::message_svc::OfflineSync,
+    /// 域名解析，为 None 时使用系统 DNS
+    resolver: RwLock<Option<Arc<dyn Resolver>>>,
+    /// 共用的 http client，设置 resolver 时重新创建
+    http: RwLock<reqwest::Client>,
+    /// 服务器下发的 SSO 地址，优先于内置地址
+    sso_servers: RwLock<Vec<SocketAddr>>,
+    /// SSO 地址测速结果
+    server_latency: RwLock<Option<server_list::ServerLatency>>,
+    /// 上次获取服务器列表失败的时间
+    sso_fetch_failed_at: RwLock<Option<std::time::Instant>>,
+    /// get_cookies 刷新凭证时持有，避免并发调用重复刷新
+    sig_refreshing: Mutex<()>,
     pub qsign_client: Arc<QSignClient>,
+    /// Client::events 事件流 Sender
+    event_sender: broadcast::Sender<QEvent>,
+    /// 创建时的运行参数
+    pub options: ClientOptions,
 }
 
 impl super::Client {
@@ -186,51 +276,105 @@ impl super::Client {
     where
         H: crate::client::handler::Handler + 'static + Sync + Send,
     {
-        let (out_pkt_sender, _) = tokio::sync::broadcast::channel(1024);
+        Self::new_with_config(crate::Config::new(device, version), qsign_client, handler)
+    }
+
+    /// 新建 Clinet，配置不合法时 panic，需要返回错误时使用 `ClientBuilder`
+    ///
+    /// **Notice: 该方法仅新建 Client 需要调用 start 方法连接到服务器**
+    pub fn new_with_config<H>(
+        config: crate::Config,
+        qsign_client: Arc<QSignClient>,
+        handler: H,
+    ) -> Self
+    where
+        H: crate::client::handler::Handler + 'static + Sync + Send,
+    {
+        let crate::Config {
+            device,
+            version,
+            options,
+        } = config;
+        if let Err(err) = options.validate() {
+            panic!("invalid client options: {err}");
+        }
         let (disconnect_signal, _) = tokio::sync::broadcast::channel(8);
+        let (event_sender, _) = tokio::sync::broadcast::channel(options.event_stream_capacity);
 
         Client {
-            handler: Box::new(handler),
+            handler: Box::new(handler::EventFanout {
+                inner: handler,
+                sender: event_sender.clone(),
+            }),
             engine: RwLock::new(Engine::new(device, version)),
             status: AtomicU8::new(NetworkStatus::Unknown as u8),
             heartbeat_enabled: AtomicBool::new(false),
+            sig_refresh_enabled: AtomicBool::new(false),
             online: AtomicBool::new(false),
-            out_pkt_sender,
+            online_status: Default::default(),
+            out_pkt_sender: Default::default(),
             disconnect_signal,
             // out_going_packet_session_id: RwLock::new(Bytes::from_static(&[0x02, 0xb0, 0x5b, 0x8b])),
             packet_promises: Default::default(),
+            packet_matchers: Default::default(),
             receipt_waiters: Mutex::new(cached::TimedCache::with_lifespan(60)),
+            share_waiters: Mutex::new(cached::TimedCache::with_lifespan(60)),
+            late_receipts: Mutex::new(cached::TimedCache::with_lifespan(60)),
+            late_shares: Mutex::new(cached::TimedCache::with_lifespan(60)),
             account_info: Default::default(),
             address: Default::default(),
             online_clients: Default::default(),
             last_message_time: Default::default(),
             start_time: UNIX_EPOCH.elapsed().unwrap().as_secs() as i32,
-            group_message_builder: RwLock::new(cached::TimedCache::with_lifespan(600)),
-            c2c_cache: RwLock::new(cached::TimedCache::with_lifespan(3600)),
-            push_req_cache: RwLock::new(cached::TimedCache::with_lifespan(30)),
-            push_trans_cache: RwLock::new(cached::TimedCache::with_lifespan(15)),
+            group_message_builder: RwLock::new(cached::TimedCache::with_lifespan(
+                options.cache.group_message,
+            )),
+            friend_message_builder: RwLock::new(cached::TimedCache::with_lifespan(
+                options.cache.friend_message,
+            )),
+            c2c_cache: RwLock::new(cached::TimedCache::with_lifespan(options.cache.c2c_message)),
+            push_req_cache: RwLock::new(cached::TimedCache::with_lifespan(options.cache.push_req)),
+            push_trans_cache: RwLock::new(cached::TimedCache::with_lifespan(
+                options.cache.push_trans,
+            )),
             group_sys_message_cache: RwLock::new(Default::default()),
+            group_member_cache: RwLock::new(cached::TimedCache::with_lifespan(
+                options.cache.group_member,
+            )),
+            group_member_loading: Default::default(),
+            friend_cache: RwLock::new(cached::TimedCache::with_lifespan(
+                options.cache.friend_list,
+            )),
+            friend_loading: Default::default(),
+            quoted_cache: RwLock::new(cached::TimedCache::with_lifespan(
+                options.cache.quoted_message,
+            )),
+            member_diff: Default::default(),
             highway_session: RwLock::new(Default::default()),
             highway_addrs: RwLock::new(Default::default()),
+            highway_preferred: RwLock::new(options.highway.preferred.clone()),
+            highway_blocked: Default::default(),
             packet_handler: Default::default(),
+            image_cache: RwLock::new(Some(Arc::new(MemoryImageCache::default()))),
+            message_store: RwLock::new(None),
+            send_limiter: Default::default(),
+            throttle_callback: RwLock::new(None),
+            energy_provider: RwLock::new(None),
+            packet_recorder: RwLock::new(None),
+            packet_workers: Default::default(),
+            offline_sync: Default::default(),
+            resolver: RwLock::new(None),
+            http: RwLock::new(resolver::build_http_client(None)),
+            sso_servers: Default::default(),
+            server_latency: RwLock::new(None),
+            sso_fetch_failed_at: RwLock::new(None),
+            sig_refreshing: Mutex::new(()),
             qsign_client,
+            event_sender,
+            options,
         }
     }
 
-    /// 新建 Clinet
-    ///
-    /// **Notice: 该方法仅新建 Client 需要调用 start 方法连接到服务器**
-    pub fn new_with_config<H>(
-        config: crate::Config,
-        qsign_client: Arc<QSignClient>,
-        handler: H,
-    ) -> Self
-    where
-        H: crate::client::handler::Handler + 'static + Sync + Send,
-    {
-        Self::new(config.device, config.version, qsign_client, handler)
-    }
-
     /// 获取当前 Client uin
     pub async fn uin(&self) -> i64 {
         self.engine.read().await.uin.load(Ordering::Relaxed)
@@ -330,16 +474,18 @@ impl super::Client {
             .await;
     }
 
-    /// 向服务器发包
+    /// 向服务器发包，写入连接后返回包的长度，未连接或写入失败时返回错误
     pub async fn send(&self, pkt: Packet) -> RQResult<usize> {
         tracing::trace!("sending pkt {}-{},", pkt.command_name, pkt.seq_id);
+        self.record_packet(Direction::Outgoing, &pkt).await;
+        let priority = PacketPriority::of(&pkt.command_name);
         let data = self.engine.read().await.transport.encode_packet(pkt);
-        self.out_pkt_sender
-            .send(data)
-            .map_err(|_| RQError::Other("failed to send out_pkt".into()))
+        let len = data.len();
+        self.out_pkt_sender.send(data, priority).await?;
+        Ok(len)
     }
 
-    /// 向服务器发包并等待接收返回的包，15 秒后超时返回 `Err(RQError::Timeout)`
+    /// 向服务器发包并等待接收返回的包，超时（默认 15 秒）返回 `Err(RQError::Timeout)`
     #[async_recursion::async_recursion]
     pub async fn send_and_wait(&self, mut pkt: Packet) -> RQResult<Packet> {
         let callbacks = self.sign_packet(&mut pkt).await;
@@ -352,18 +498,20 @@ impl super::Client {
         tracing::trace!("send_and_waitting pkt {}-{},", pkt.command_name, pkt.seq_id);
         let seq = pkt.seq_id;
         let expect = pkt.command_name.clone();
+        self.record_packet(Direction::Outgoing, &pkt).await;
+        let priority = PacketPriority::of(&expect);
         let data = self.engine.read().await.transport.encode_packet(pkt);
         let (sender, receiver) = oneshot::channel();
         {
             let mut packet_promises = self.packet_promises.write().await;
             packet_promises.insert(seq, sender);
         }
-        if self.out_pkt_sender.send(data).is_err() {
+        if let Err(err) = self.out_pkt_sender.send(data, priority).await {
             let mut packet_promises = self.packet_promises.write().await;
             packet_promises.remove(&seq);
-            return Err(RQError::Network);
+            return Err(err);
         }
-        let packet_future = tokio::time::timeout(std::time::Duration::from_secs(15), receiver);
+        let packet_future = tokio::time::timeout(self.options.request_timeout, receiver);
 
         let (resp, _) = tokio::join!(packet_future, callback_future);
         match resp {
@@ -376,27 +524,184 @@ impl super::Client {
         }
     }
 
+    /// 可取消的 send_and_wait，取消后不再等待响应，返回 `Err(RQError::Cancelled)`
+    pub async fn send_and_wait_with_cancel(
+        &self,
+        pkt: Packet,
+        cancel: &tokio_util::sync::CancellationToken,
+    ) -> RQResult<Packet> {
+        let seq = pkt.seq_id;
+        tokio::select! {
+            resp = self.send_and_wait(pkt) => resp,
+            _ = cancel.cancelled() => {
+                self.packet_promises.write().await.remove(&seq);
+                Err(RQError::Cancelled)
+            }
+        }
+    }
+
+    async fn add_packet_matcher<F>(&self, matcher: F) -> oneshot::Receiver<Packet>
+    where
+        F: Fn(&str, i32, &[u8]) -> bool + Send + Sync + 'static,
+    {
+        let (sender, receiver) = oneshot::channel();
+        self.packet_matchers.lock().await.push((
+            Box::new(move |pkt: &Packet| matcher(&pkt.command_name, pkt.seq_id, &pkt.body)),
+            sender,
+        ));
+        receiver
+    }
+
+    /// 等待第一个满足 `matcher(command, seq, body)` 的包，超时返回 `Err(RQError::Timeout)`
+    ///
+    /// 匹配到的包不会再交给其他处理流程
+    pub async fn wait_packet<F>(&self, matcher: F, timeout: Duration) -> RQResult<Packet>
+    where
+        F: Fn(&str, i32, &[u8]) -> bool + Send + Sync + 'static,
+    {
+        let receiver = self.add_packet_matcher(matcher).await;
+        match tokio::time::timeout(timeout, receiver).await {
+            Ok(Ok(pkt)) => Ok(pkt),
+            _ => Err(RQError::Timeout),
+        }
+    }
+
+    /// 向服务器发包并等待第一个满足 `matcher(command, seq, body)` 的包，用于返回包与请求 seq 不同的场景
+    ///
+    /// 匹配到的包不会再交给其他处理流程
+    pub async fn send_and_wait_matching<F>(
+        &self,
+        mut pkt: Packet,
+        matcher: F,
+        timeout: Duration,
+    ) -> RQResult<Packet>
+    where
+        F: Fn(&str, i32, &[u8]) -> bool + Send + Sync + 'static,
+    {
+        let callbacks = self.sign_packet(&mut pkt).await;
+        if let Err(ref err) = callbacks {
+            tracing::error!("failed to sign packet, err: {err}");
+        }
+        let callbacks = callbacks.unwrap_or_default().data.request_callback;
+        let callback_future = self.process_sign_callback(callbacks);
+
+        self.record_packet(Direction::Outgoing, &pkt).await;
+        let priority = PacketPriority::of(&pkt.command_name);
+        let data = self.engine.read().await.transport.encode_packet(pkt);
+        let receiver = self.add_packet_matcher(matcher).await;
+        self.out_pkt_sender.send(data, priority).await?;
+        let (resp, _) = tokio::join!(tokio::time::timeout(timeout, receiver), callback_future);
+        match resp {
+            Ok(Ok(pkt)) => Ok(pkt),
+            _ => Err(RQError::Timeout),
+        }
+    }
+
     /// 向服务器发送心跳包，并自动注册客户端
     ///
+    /// 连续多次心跳失败或注册失败时按 `ClientOptions::heartbeat_policy` 重新注册或断开连接。
+    /// **Notice: 默认策略下定时注册失败会断开连接（`HeartbeatPolicy::disconnect` 为 true），
+    /// 之前只停止心跳，需要旧行为时设置为 false**
+    ///
+    /// 该方法不发出 ConnectionUnhealthy 事件，需要事件时使用 `ext::common::start_heartbeat`
+    ///
     /// 该方法会阻塞当前协程，通常 spawn 使用
     pub async fn do_heartbeat(&self) {
+        self.heartbeat_loop(|_| None).await
+    }
+
+    /// 同 `do_heartbeat`，连接异常时发出 ConnectionUnhealthy 事件
+    pub(crate) async fn do_heartbeat_with_events(self: &Arc<Self>) {
+        self.heartbeat_loop(|inner| {
+            Some(QEvent::ConnectionUnhealthy(ConnectionUnhealthyEvent {
+                client: self.clone(),
+                inner: Arc::new(inner),
+            }))
+        })
+        .await
+    }
+
+    async fn heartbeat_loop<F>(&self, on_unhealthy: F)
+    where
+        F: Fn(ConnectionUnhealthy) -> Option<QEvent>,
+    {
         self.heartbeat_enabled.store(true, Ordering::SeqCst);
+        let policy = self.options.heartbeat_policy.clone();
         let mut times = 0;
+        let mut missed = 0;
         while self.online.load(Ordering::SeqCst) {
-            sleep(Duration::from_secs(30)).await;
-            if self.heartbeat().await.is_ok() {
-                times += 1;
-                if times >= 7 {
-                    if self.register_client().await.is_err() {
-                        break;
+            sleep(self.options.heartbeat_interval).await;
+            let reason = match self.heartbeat().await {
+                Ok(_) => {
+                    missed = 0;
+                    times += 1;
+                    if times < 7 {
+                        continue;
                     }
                     times = 0;
+                    match self.register_client().await {
+                        Ok(_) => continue,
+                        Err(err) => UnhealthyReason::RegisterFailed(err.to_string()),
+                    }
                 }
+                Err(_) => {
+                    missed += 1;
+                    if missed < policy.max_missed {
+                        continue;
+                    }
+                    UnhealthyReason::HeartbeatMissed(missed)
+                }
+            };
+            // 心跳超时后先尝试重新注册，注册失败时不再重试
+            let recovered = matches!(reason, UnhealthyReason::HeartbeatMissed(_))
+                && policy.reregister
+                && self.register_client().await.is_ok();
+            let disconnecting = !recovered && policy.disconnect;
+            tracing::warn!(
+                "connection unhealthy: {:?}, recovered: {}, disconnecting: {}",
+                reason,
+                recovered,
+                disconnecting
+            );
+            if let Some(event) = on_unhealthy(ConnectionUnhealthy {
+                reason,
+                disconnecting,
+            }) {
+                self.handler.handle(event).await;
+            }
+            missed = 0;
+            if disconnecting {
+                // net_loop 结束后按网络原因掉线处理，触发 ClientDisconnect
+                self.disconnect();
+                break;
             }
         }
         self.heartbeat_enabled.store(false, Ordering::SeqCst);
     }
 
+    /// 在 skey 过期前主动刷新登录凭证，提前量为 `ClientOptions::sig_refresh_ahead`
+    ///
+    /// 该方法会阻塞当前协程，通常 spawn 使用，离线后退出
+    pub async fn do_refresh_sig(&self) {
+        let ahead = match self.options.sig_refresh_ahead {
+            Some(ahead) => ahead,
+            None => return,
+        };
+        self.sig_refresh_enabled.store(true, Ordering::SeqCst);
+        while self.online.load(Ordering::SeqCst) {
+            let expires_in = self.engine.read().await.s_key_expires_in();
+            // 服务器返回的过期时间没有变化时避免连续刷新
+            sleep(expires_in.saturating_sub(ahead).max(SIG_REFRESH_MIN_INTERVAL)).await;
+            if !self.online.load(Ordering::SeqCst) {
+                break;
+            }
+            if let Err(err) = self.refresh_sig().await {
+                tracing::warn!("failed to refresh sig: {}", err);
+            }
+        }
+        self.sig_refresh_enabled.store(false, Ordering::SeqCst);
+    }
+
     /// 生成 token
     pub async fn gen_token(&self) -> Token {
         self.engine.read().await.gen_token()
@@ -407,6 +712,37 @@ impl super::Client {
         self.engine.write().await.load_token(token)
     }
 
+    /// 生成快速登录凭证，需要在登录成功后调用，用于 fast_login
+    pub async fn gen_a1_token(&self) -> A1Token {
+        self.engine.read().await.gen_a1_token()
+    }
+
+    /// PbGetMsg 的同步进度，保存后下次启动可以从这里继续同步
+    pub async fn sync_cookie(&self) -> Vec<u8> {
+        self.engine.read().await.transport.sig.sync_cookie.to_vec()
+    }
+
+    /// 恢复 PbGetMsg 的同步进度，需要在登录前设置
+    pub async fn set_sync_cookie(&self, cookie: Vec<u8>) {
+        self.engine.write().await.transport.sig.sync_cookie = Bytes::from(cookie);
+    }
+
+    /// 公众号消息的同步进度
+    pub async fn pub_account_cookie(&self) -> Vec<u8> {
+        self.engine
+            .read()
+            .await
+            .transport
+            .sig
+            .pub_account_cookie
+            .to_vec()
+    }
+
+    /// 恢复公众号消息的同步进度，需要在登录前设置
+    pub async fn set_pub_account_cookie(&self, cookie: Vec<u8>) {
+        self.engine.write().await.transport.sig.pub_account_cookie = Bytes::from(cookie);
+    }
+
     pub async fn device(&self) -> Device {
         self.engine.read().await.transport.device.clone()
     }
@@ -415,10 +751,69 @@ impl super::Client {
         self.engine.read().await.transport.version.clone()
     }
 
+    /// 切换协议，之后发送的登录包使用新的协议，已有的登录凭证不会改变
+    pub async fn set_version(&self, version: Version) {
+        self.engine.write().await.transport.version = version;
+    }
+
     pub async fn get_highway_session_key(&self) -> Vec<u8> {
         self.highway_session.read().await.session_key.to_vec()
     }
 
+    /// 以 Stream 的形式订阅事件，可以代替实现 Handler，落后时丢弃旧事件
+    ///
+    /// 只会收到调用之后产生的事件，构造 Client 时传入的 Handler 仍会照常收到所有事件
+    pub fn events(&self) -> impl Stream<Item = QEvent> + Send + 'static {
+        self.events_with_policy(LagPolicy::Skip)
+    }
+
+    /// 以 Stream 的形式订阅事件，缓冲区满后按 `policy` 处理
+    pub fn events_with_policy(
+        &self,
+        policy: LagPolicy,
+    ) -> impl Stream<Item = QEvent> + Send + 'static {
+        futures_util::stream::unfold(self.event_sender.subscribe(), move |mut rx| async move {
+            loop {
+                match rx.recv().await {
+                    Ok(event) => return Some((event, rx)),
+                    Err(broadcast::error::RecvError::Lagged(n)) => {
+                        tracing::warn!("event stream lagged, {} events skipped", n);
+                        if policy == LagPolicy::Close {
+                            return None;
+                        }
+                    }
+                    Err(broadcast::error::RecvError::Closed) => return None,
+                }
+            }
+        })
+    }
+
+    /// 设置已上传图片缓存，默认使用容量为 256 的 MemoryImageCache，传入 None 关闭缓存
+    pub async fn set_image_cache(&self, cache: Option<Arc<dyn ImageCache>>) {
+        *self.image_cache.write().await = cache;
+    }
+
+    /// 设置消息存储，撤回事件会附带存储中的原消息，默认为 None 不保存
+    pub async fn set_message_store(&self, store: Option<Arc<dyn MessageStore>>) {
+        *self.message_store.write().await = store;
+    }
+
+    /// 设置本地 T544 实现，传入 None 时恢复使用签名服务器
+    pub async fn set_energy_provider(&self, provider: Option<Arc<dyn EnergyProvider>>) {
+        *self.energy_provider.write().await = provider;
+    }
+
+    /// 设置抓包记录，传入 None 停止记录
+    pub async fn set_packet_recorder(&self, recorder: Option<Arc<dyn PacketRecorder>>) {
+        *self.packet_recorder.write().await = recorder;
+    }
+
+    pub(crate) async fn record_packet(&self, direction: Direction, pkt: &Packet) {
+        if let Some(recorder) = self.packet_recorder.read().await.as_ref() {
+            recorder.record(direction, pkt);
+        }
+    }
+
     /// 监听指定 command 数据包
     pub async fn listen_command<S: ToString>(&self, command: S) -> broadcast::Receiver<Packet> {
         self.packet_handler
@@ -435,7 +830,7 @@ impl Drop for Client {
     }
 }
 
-#[derive(Copy, Clone, Debug)]
+#[derive(Copy, Clone, Debug, Serialize, Deserialize)]
 #[repr(u8)]
 pub enum NetworkStatus {
     // 未启动
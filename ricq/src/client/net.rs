@@ -1,24 +1,123 @@
+use std::collections::HashSet;
 use std::net::SocketAddr;
 use std::sync::atomic::Ordering;
-use std::sync::Arc;
-use std::time::Duration;
+use std::sync::{Arc, RwLock};
 
-use crate::client::event::{ClientDisconnect, DisconnectReason};
+use crate::client::capture::Direction;
+use crate::client::event::{ClientDisconnect, DisconnectReason, LoginStage};
 use async_trait::async_trait;
 use bytes::Bytes;
 use futures_util::{SinkExt, StreamExt};
 use tokio::io::{self, AsyncRead, AsyncWrite};
 use tokio::net::TcpStream;
-use tokio::sync::broadcast;
+use tokio::sync::{mpsc, oneshot};
 use tokio_util::codec::LengthDelimitedCodec;
 
-use crate::client::tcp::tcp_connect_fastest;
 use crate::client::NetworkStatus;
+use crate::ext::connect::{Proxy, ProxyConnector, ProxyTarget};
 use crate::handler::QEvent;
+use crate::{RQError, RQResult};
 
 use super::Client;
 
-pub type OutPktSender = broadcast::Sender<Bytes>;
+/// 外发包优先级，连接空闲时按 High、Normal、Low 的顺序写入
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub enum PacketPriority {
+    /// 心跳、注册、推送回执等，延迟过高会被服务器断开
+    High,
+    /// 发消息等交互请求
+    Normal,
+    /// 图片上传、合并转发、历史消息等批量请求
+    Low,
+}
+
+impl PacketPriority {
+    /// 根据命令名判断优先级，未列出的命令为 Normal
+    pub fn of(command: &str) -> Self {
+        match command {
+            "Heartbeat.Alive"
+            | "StatSvc.register"
+            | "OnlinePush.RespPush"
+            | "OnlinePush.SidTicketExpired"
+            | "ConfigPushSvc.PushResp"
+            | "MessageSvc.PbDeleteMsg"
+            | "StatSvc.RspMSFForceOffline" => Self::High,
+            "ImgStore.GroupPicUp"
+            | "LongConn.OffPicUp"
+            | "PttStore.GroupPttUp"
+            | "MultiMsg.ApplyUp"
+            | "MultiMsg.ApplyDown"
+            | "MessageSvc.PbGetGroupMsg"
+            | "MessageSvc.PbGetOneDayRoamMsg"
+            | "MessageSvc.PbGetRoamMsg"
+            | "OidbSvc.0x6d8_1" => Self::Low,
+            _ if command.starts_with("wtlogin.") => Self::High,
+            _ => Self::Normal,
+        }
+    }
+}
+
+/// 外发包，写入连接后通过 result 通知发送方
+struct OutPacket {
+    data: Bytes,
+    result: oneshot::Sender<io::Result<()>>,
+}
+
+type OutQueue = [mpsc::Sender<OutPacket>; 3];
+
+/// 外发包队列，由当前连接持有接收端，没有连接时发送返回 `RQError::Network`
+#[derive(Default)]
+pub struct OutPktSender {
+    sender: RwLock<Option<OutQueue>>,
+}
+
+impl OutPktSender {
+    /// 新连接建立时调用，替换掉旧连接的队列，每个优先级一个队列
+    fn attach(&self, capacity: usize) -> [mpsc::Receiver<OutPacket>; 3] {
+        let (high, high_rx) = mpsc::channel(capacity);
+        let (normal, normal_rx) = mpsc::channel(capacity);
+        let (low, low_rx) = mpsc::channel(capacity);
+        *self.sender.write().unwrap() = Some([high, normal, low]);
+        [high_rx, normal_rx, low_rx]
+    }
+
+    /// 连接断开后调用，接收端已经释放时才清除，不影响新连接
+    fn detach(&self) {
+        let mut sender = self.sender.write().unwrap();
+        if sender.as_ref().is_some_and(|s| s[0].is_closed()) {
+            *sender = None;
+        }
+    }
+
+    /// 发送到当前连接，队列满时等待，写入连接后返回
+    pub async fn send(&self, data: Bytes, priority: PacketPriority) -> RQResult<()> {
+        let sender = self.sender.read().unwrap().as_ref().map(|queue| {
+            // 下标与 attach 中的队列顺序一致
+            queue[priority as usize].clone()
+        });
+        let sender = sender.ok_or(RQError::Network)?;
+        let (result, receiver) = oneshot::channel();
+        sender
+            .send(OutPacket { data, result })
+            .await
+            .map_err(|_| RQError::Network)?;
+        // 连接断开时队列中未写入的包会被丢弃，result 随之释放
+        receiver.await.map_err(|_| RQError::Network)??;
+        Ok(())
+    }
+}
+
+/// 内置服务器地址
+const BUILD_IN_ADDRS: [([u8; 4], u16); 6] = [
+    ([42, 81, 172, 81], 80),
+    ([114, 221, 148, 59], 14000),
+    ([42, 81, 172, 147], 443),
+    ([125, 94, 60, 146], 80),
+    ([114, 221, 144, 215], 80),
+    ([42, 81, 172, 22], 80),
+];
+/// 需要域名解析的服务器地址
+const MSF_HOST: (&str, u16) = ("msfwifi.3g.qq.com", 8080);
 
 #[async_trait]
 pub trait Connector<T: AsyncRead + AsyncWrite> {
@@ -29,30 +128,43 @@ pub struct DefaultConnector;
 
 #[async_trait]
 impl Connector<TcpStream> for DefaultConnector {
+    /// 设置了 `ClientOptions::proxy` 时通过代理连接，否则连接延迟最低的服务器
     async fn connect(&self, client: &Client) -> io::Result<TcpStream> {
-        tcp_connect_fastest(client.get_address_list().await, Duration::from_secs(5)).await
+        match client.options.proxy.as_deref() {
+            Some(proxy) => {
+                let proxy = Proxy::parse(proxy)
+                    .map_err(|e| io::Error::new(io::ErrorKind::InvalidInput, e.to_string()))?;
+                ProxyConnector::new(proxy).connect(client).await
+            }
+            None => client.connect_fastest().await,
+        }
     }
 }
 
 impl crate::Client {
-    /// 获取服务器地址
+    /// 获取服务器地址，依次为服务器下发的地址、内置地址和域名解析的地址
     pub async fn get_address_list(&self) -> Vec<SocketAddr> {
-        const BUILD_IN: [([u8; 4], u16); 6] = [
-            ([42, 81, 172, 81], 80),
-            ([114, 221, 148, 59], 14000),
-            ([42, 81, 172, 147], 443),
-            ([125, 94, 60, 146], 80),
-            ([114, 221, 144, 215], 80),
-            ([42, 81, 172, 22], 80),
-        ];
-        let mut addrs: Vec<_> = BUILD_IN.into_iter().map(SocketAddr::from).collect();
-        if let Ok(res) = tokio::net::lookup_host(("msfwifi.3g.qq.com", 8080)).await {
+        let mut addrs = self.sso_servers.read().await.clone();
+        addrs.extend(BUILD_IN_ADDRS.into_iter().map(SocketAddr::from));
+        if let Ok(res) = self.resolve_host(MSF_HOST.0, MSF_HOST.1).await {
             addrs.extend(res);
         }
-        // TODO: src/client/processor/config_push_svc.rs
+        let mut seen = HashSet::new();
+        addrs.retain(|addr| seen.insert(*addr));
         addrs
     }
 
+    /// 与 get_address_list 相同，但域名不在本地解析，交给代理解析
+    pub async fn get_proxy_targets(&self) -> Vec<ProxyTarget> {
+        let mut addrs = self.sso_servers.read().await.clone();
+        addrs.extend(BUILD_IN_ADDRS.into_iter().map(SocketAddr::from));
+        let mut seen = HashSet::new();
+        addrs.retain(|addr| seen.insert(*addr));
+        let mut targets: Vec<ProxyTarget> = addrs.into_iter().map(ProxyTarget::from).collect();
+        targets.push(ProxyTarget::Host(MSF_HOST.0.into(), MSF_HOST.1));
+        targets
+    }
+
     /// 获取网络状态
     pub fn get_status(&self) -> u8 {
         self.status.load(Ordering::Relaxed)
@@ -64,6 +176,7 @@ impl crate::Client {
     pub async fn start(self: &Arc<Self>, stream: impl AsyncRead + AsyncWrite) {
         self.status
             .store(NetworkStatus::Running as u8, Ordering::Relaxed);
+        self.report_login_progress(LoginStage::Connected).await;
         self.net_loop(stream).await; // 阻塞到断开
         self.disconnect();
         self.online.store(false, Ordering::Relaxed);
@@ -78,7 +191,7 @@ impl crate::Client {
                 self.handler
                     .handle(QEvent::ClientDisconnect(ClientDisconnect {
                         client: Arc::clone(self),
-                        inner: DisconnectReason::Network,
+                        inner: Arc::new(DisconnectReason::Network),
                     }))
                     .await;
             }
@@ -86,7 +199,7 @@ impl crate::Client {
                 self.handler
                     .handle(QEvent::ClientDisconnect(ClientDisconnect {
                         client: Arc::clone(self),
-                        inner: {
+                        inner: Arc::new({
                             let network = match status {
                                 0 => NetworkStatus::Unknown,
                                 1 => NetworkStatus::Running,
@@ -98,7 +211,7 @@ impl crate::Client {
                             };
 
                             DisconnectReason::Actively(network)
-                        },
+                        }),
                     }))
                     .await;
             }
@@ -111,7 +224,7 @@ impl crate::Client {
         self.online.store(false, Ordering::Relaxed);
     }
 
-    fn disconnect(&self) {
+    pub(crate) fn disconnect(&self) {
         // TODO dispatch disconnect event
         // don't unwrap (Err means there is no receiver.)
         self.disconnect_signal.send(()).ok();
@@ -123,15 +236,41 @@ impl crate::Client {
             .length_adjustment(-4)
             .new_framed(stream)
             .split();
-        // 外发包 Channel Receiver
-        let mut rx = self.out_pkt_sender.subscribe();
+        // 外发包队列，断开时释放
+        let [mut high, mut normal, mut low] =
+            self.out_pkt_sender.attach(self.options.out_queue_capacity);
         let mut disconnect_signal = self.disconnect_signal.subscribe();
+        let workers = self.packet_workers();
+        // 持续收包时限制连续读取的次数，大量普通请求时同样限制，避免其他分支一直得不到处理
+        let mut read_budget = Budget::new(READ_BUDGET);
+        let mut normal_budget = Budget::new(NORMAL_BUDGET);
         loop {
+            // 没有等待写入的包时不限制
+            if normal.is_empty() && low.is_empty() {
+                read_budget.reset();
+            }
+            if low.is_empty() {
+                normal_budget.reset();
+            }
             tokio::select! {
-                input = read_half.next() => {
+                biased;
+                _ = disconnect_signal.recv() => {
+                    break;
+                }
+                // 心跳等高优先级的包在收包之前写入
+                Some(output) = high.recv() => {
+                    if !write_out_packet(&mut write_half, output).await {
+                        break;
+                    }
+                }
+                input = read_half.next(), if read_budget.allow() => {
+                    read_budget.spend();
                     if let Some(Ok(mut input)) = input {
                         if let Ok(pkt) = self.engine.read().await.transport.decode_packet(&mut input) {
-                            self.process_income_packet(pkt).await;
+                            self.record_packet(Direction::Incoming, &pkt).await;
+                            if let Some(pkt) = self.intercept_packet(pkt).await {
+                                workers.dispatch(pkt);
+                            }
                         } else {
                             self.status.store(NetworkStatus::MsfOffline as u8, Ordering::Relaxed);
                             break;
@@ -140,15 +279,66 @@ impl crate::Client {
                         break;
                     }
                 }
-                output = rx.recv() => {
-                    if let Ok(output) = output && write_half.send(output).await.is_err() {
+                Some(output) = normal.recv(), if normal_budget.allow() => {
+                    read_budget.reset();
+                    normal_budget.spend();
+                    if !write_out_packet(&mut write_half, output).await {
                         break;
                     }
                 }
-                _ = disconnect_signal.recv() => {
-                    break;
+                Some(output) = low.recv() => {
+                    read_budget.reset();
+                    normal_budget.reset();
+                    if !write_out_packet(&mut write_half, output).await {
+                        break;
+                    }
                 }
             }
         }
+        drop((high, normal, low));
+        self.out_pkt_sender.detach();
     }
 }
+
+/// 有外发包等待时最多连续收包的次数
+const READ_BUDGET: u32 = 16;
+/// 有批量请求等待时最多连续写入普通包的次数
+const NORMAL_BUDGET: u32 = 8;
+
+/// 连续处理次数限制，用完后让出给其他分支
+struct Budget {
+    remaining: u32,
+    max: u32,
+}
+
+impl Budget {
+    fn new(max: u32) -> Self {
+        Self {
+            remaining: max,
+            max,
+        }
+    }
+
+    fn allow(&self) -> bool {
+        self.remaining > 0
+    }
+
+    fn spend(&mut self) {
+        self.remaining = self.remaining.saturating_sub(1);
+    }
+
+    fn reset(&mut self) {
+        self.remaining = self.max;
+    }
+}
+
+/// 写入一个外发包并通知发送方，返回是否写入成功
+async fn write_out_packet<W>(write_half: &mut W, output: OutPacket) -> bool
+where
+    W: futures_util::Sink<Bytes, Error = io::Error> + Unpin,
+{
+    let result = write_half.send(output.data).await;
+    let ok = result.is_ok();
+    output.result.send(result).ok();
+    ok
+}
@@ -0,0 +1,44 @@
+use std::sync::Arc;
+
+use bytes::Bytes;
+use prost::Message;
+
+use ricq_core::structs::FriendFile;
+use ricq_core::{pb, RQError, RQResult};
+
+use crate::client::event::FriendFileReceivedEvent;
+use crate::handler::QEvent;
+use crate::Client;
+
+impl Client {
+    // msg_type 529, c2c_cmd 4
+    pub(crate) async fn process_friend_file(
+        self: &Arc<Self>,
+        msg: pb::msg::Message,
+    ) -> RQResult<()> {
+        let head = msg.head.ok_or(RQError::EmptyField("msg.head"))?;
+        if head.c2c_cmd() != 4 {
+            return Ok(());
+        }
+        let content = msg
+            .body
+            .and_then(|b| b.msg_content)
+            .ok_or(RQError::EmptyField("msg_content"))?;
+        let file = pb::msg::SubMsgType0x4Body::decode(&*content)?
+            .not_online_file
+            .ok_or(RQError::EmptyField("not_online_file"))?;
+        self.handle_event(QEvent::FriendFileReceived(FriendFileReceivedEvent {
+            client: self.clone(),
+            inner: Arc::new(FriendFile {
+                sender: head.from_uin(),
+                file_name: String::from_utf8_lossy(file.file_name()).into_owned(),
+                file_size: file.file_size(),
+                file_uuid: Bytes::from(file.file_uuid.unwrap_or_default()),
+                file_md5: Bytes::from(file.file_md5.unwrap_or_default()),
+                expire_time: file.expire_time(),
+            }),
+        }))
+        .await;
+        Ok(())
+    }
+}
@@ -2,10 +2,12 @@ use cached::Cached;
 use std::sync::Arc;
 
 use ricq_core::msg::MessageChain;
-use ricq_core::structs::{FriendAudio, FriendAudioMessage, FriendMessage};
-use ricq_core::{pb, RQResult};
+use ricq_core::structs::{
+    FriendAudio, FriendAudioMessage, FriendMessage, MessageReceipt, MessageTarget,
+};
+use ricq_core::{pb, RQError, RQResult};
 
-use crate::client::event::{FriendAudioMessageEvent, FriendMessageEvent};
+use crate::client::event::{FriendAudioMessageEvent, FriendMessageEvent, SelfFriendMessageEvent};
 use crate::handler::QEvent;
 use crate::Client;
 
@@ -13,23 +15,30 @@ impl Client {
     pub(crate) async fn process_friend_message(
         self: &Arc<Self>,
         mut msg: pb::msg::Message,
+        offline: bool,
     ) -> RQResult<()> {
         fn take_ptt(msg: &mut pb::msg::Message) -> Option<pb::msg::Ptt> {
             msg.body.as_mut()?.rich_text.as_mut()?.ptt.take()
         }
         if let Some(ptt) = take_ptt(&mut msg) {
             // TODO self friend audio
-            self.handler
-                .handle(QEvent::FriendAudioMessage(FriendAudioMessageEvent {
-                    client: self.clone(),
-                    inner: parse_friend_audio_message(msg, ptt)?,
-                }))
-                .await;
+            self.handle_event(QEvent::FriendAudioMessage(FriendAudioMessageEvent {
+                client: self.clone(),
+                inner: Arc::new(parse_friend_audio_message(msg, ptt)?),
+            }))
+            .await;
             return Ok(());
         }
 
-        let message = parse_friend_message(msg)?;
-        if message.from_uin == self.uin().await {
+        let parts = match self.merge_friend_message(msg).await {
+            Some(parts) => parts,
+            // wait for more parts
+            None => return Ok(()),
+        };
+        let mut message = parse_friend_message_parts(parts)?;
+        message.offline = offline;
+        let from_self = message.from_uin == self.uin().await;
+        if from_self {
             if let Some(tx) = self
                 .receipt_waiters
                 .lock()
@@ -39,33 +48,113 @@ impl Client {
                 let _ = tx.send(message.seqs.first().cloned().unwrap_or_default());
                 return Ok(());
             }
+            let receipt = MessageReceipt {
+                target: MessageTarget::Friend(message.target),
+                seqs: message.seqs.clone(),
+                rands: message.rands.clone(),
+                time: message.time as i64,
+            };
+            if self
+                .complete_share(receipt, message.elements.0.iter())
+                .await
+            {
+                return Ok(());
+            }
+        }
+        if self.options.resolve_quoted_messages {
+            let peer = if from_self {
+                message.target
+            } else {
+                message.from_uin
+            };
+            message.quoted = self.resolve_quoted(peer, &message.elements, false).await;
+            self.cache_quoted(peer, &message.seqs, &message.elements, false)
+                .await;
         }
-        self.handler
-            .handle(QEvent::FriendMessage(FriendMessageEvent {
+        if from_self && self.options.self_message_events {
+            // 其他设备发送的消息
+            self.handle_event(QEvent::SelfFriendMessage(SelfFriendMessageEvent {
                 client: self.clone(),
-                inner: message,
+                inner: Arc::new(message),
             }))
             .await;
+            return Ok(());
+        }
+        if let Some(store) = self.message_store.read().await.as_ref() {
+            store.put_friend_message(&message);
+        }
+        self.handle_event(QEvent::FriendMessage(FriendMessageEvent {
+            client: self.clone(),
+            inner: Arc::new(message),
+        }))
+        .await;
         Ok(())
     }
+
+    /// 长消息会拆成多个包，按 (from_uin, div_seq) 寄存，收齐后返回全部分片
+    async fn merge_friend_message(&self, msg: pb::msg::Message) -> Option<Vec<pb::msg::Message>> {
+        let (pkg_num, div_seq) = match &msg.content {
+            Some(content) if content.pkg_num() > 1 => (content.pkg_num(), content.div_seq()),
+            // single-part
+            _ => return Some(vec![msg]),
+        };
+        let from_uin = msg.head.as_ref().map(|h| h.from_uin()).unwrap_or_default();
+        let key = (from_uin, div_seq);
+        let mut builder = self.friend_message_builder.write().await;
+        if builder.cache_misses().unwrap_or_default() > 100 {
+            builder.flush();
+            builder.cache_reset_metrics();
+        }
+        let parts = builder.cache_get_or_set_with(key, Vec::new);
+        parts.push(msg);
+        if parts.len() < pkg_num as usize {
+            None
+        } else {
+            builder.cache_remove(&key)
+        }
+    }
 }
 
-pub fn parse_friend_message(msg: pb::msg::Message) -> RQResult<FriendMessage> {
-    let head = msg.head.unwrap();
+/// 合并同一条消息的多个分片，分片顺序由 pkg_index 决定
+pub fn parse_friend_message_parts(mut parts: Vec<pb::msg::Message>) -> RQResult<FriendMessage> {
+    parts.sort_by_key(|p| {
+        p.content
+            .as_ref()
+            .map(|c| c.pkg_index())
+            .unwrap_or_default()
+    });
+    let head = parts
+        .first()
+        .and_then(|p| p.head.clone())
+        .ok_or(RQError::EmptyField("head"))?;
+    let mut seqs = Vec::with_capacity(parts.len());
+    let mut rands = Vec::with_capacity(parts.len());
+    let mut elements = Vec::new();
+    for part in parts {
+        seqs.push(part.head.as_ref().map(|h| h.msg_seq()).unwrap_or_default());
+        let rich_text = part
+            .body
+            .and_then(|b| b.rich_text)
+            .ok_or(RQError::EmptyField("rich_text"))?;
+        rands.push(
+            rich_text
+                .attr
+                .as_ref()
+                .map(|a| a.random())
+                .unwrap_or_default(),
+        );
+        elements.extend(rich_text.elems);
+    }
     Ok(FriendMessage {
-        seqs: vec![head.msg_seq()],
-        target: head.to_uin.unwrap(),
-        time: head.msg_time.unwrap(),
+        seqs,
+        target: head.to_uin.ok_or(RQError::EmptyField("to_uin"))?,
+        time: head.msg_time.ok_or(RQError::EmptyField("msg_time"))?,
         from_uin: head.from_uin.unwrap_or_default(),
         from_nick: head.from_nick.unwrap_or_default(),
-        rands: vec![
-            if let Some(attr) = &msg.body.as_ref().unwrap().rich_text.as_ref().unwrap().attr {
-                attr.random()
-            } else {
-                0
-            },
-        ],
-        elements: MessageChain::from(msg.body.unwrap().rich_text.unwrap().elems), // todo ptt_store
+        rands,
+        elements: MessageChain::from(elements), // todo ptt_store
+        offline: false,
+        quoted: None,
     })
 }
 
@@ -73,20 +162,22 @@ pub fn parse_friend_audio_message(
     msg: pb::msg::Message,
     ptt: pb::msg::Ptt,
 ) -> RQResult<FriendAudioMessage> {
-    let head = msg.head.unwrap();
+    let head = msg.head.ok_or(RQError::EmptyField("head"))?;
+    let rich_text = msg
+        .body
+        .and_then(|b| b.rich_text)
+        .ok_or(RQError::EmptyField("rich_text"))?;
     Ok(FriendAudioMessage {
         seqs: vec![head.msg_seq()],
-        target: head.to_uin.unwrap(),
-        time: head.msg_time.unwrap(),
+        target: head.to_uin.ok_or(RQError::EmptyField("to_uin"))?,
+        time: head.msg_time.ok_or(RQError::EmptyField("msg_time"))?,
         from_uin: head.from_uin.unwrap_or_default(),
         from_nick: head.from_nick.unwrap_or_default(),
-        rands: vec![
-            if let Some(attr) = &msg.body.as_ref().unwrap().rich_text.as_ref().unwrap().attr {
-                attr.random()
-            } else {
-                0
-            },
-        ],
+        rands: vec![rich_text
+            .attr
+            .as_ref()
+            .map(|a| a.random())
+            .unwrap_or_default()],
         audio: FriendAudio(ptt),
     })
 }
@@ -10,12 +10,11 @@ impl Client {
         msgs: FriendSystemMessages,
     ) {
         for request in msgs.requests {
-            self.handler
-                .handle(QEvent::NewFriendRequest(NewFriendRequestEvent {
-                    client: self.clone(),
-                    inner: request,
-                }))
-                .await;
+            self.handle_event(QEvent::NewFriendRequest(NewFriendRequestEvent {
+                client: self.clone(),
+                inner: Arc::new(request),
+            }))
+            .await;
         }
     }
 }
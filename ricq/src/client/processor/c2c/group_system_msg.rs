@@ -8,33 +8,32 @@ use crate::Client;
 
 impl Client {
     pub(crate) async fn process_group_system_messages(self: &Arc<Self>, msgs: GroupSystemMessages) {
-        for request in msgs.self_invited.clone() {
+        // 只复制需要上报的新请求，完整列表直接存入缓存
+        for request in msgs.self_invited.iter() {
             if self
                 .self_invited_exists(request.msg_seq, request.msg_time)
                 .await
             {
                 continue;
             }
-            self.handler
-                .handle(QEvent::SelfInvited(SelfInvitedEvent {
-                    client: self.clone(),
-                    inner: request,
-                }))
-                .await;
+            self.handle_event(QEvent::SelfInvited(SelfInvitedEvent {
+                client: self.clone(),
+                inner: Arc::new(request.clone()),
+            }))
+            .await;
         }
-        for request in msgs.join_group_requests.clone() {
+        for request in msgs.join_group_requests.iter() {
             if self
                 .join_group_request_exists(request.msg_seq, request.msg_time)
                 .await
             {
                 continue;
             }
-            self.handler
-                .handle(QEvent::GroupRequest(JoinGroupRequestEvent {
-                    client: self.clone(),
-                    inner: request,
-                }))
-                .await;
+            self.handle_event(QEvent::GroupRequest(JoinGroupRequestEvent {
+                client: self.clone(),
+                inner: Arc::new(request.clone()),
+            }))
+            .await;
         }
         let mut cache = self.group_sys_message_cache.write().await;
         *cache = msgs
@@ -1,3 +1,4 @@
+pub mod friend_file;
 pub mod friend_msg;
 pub mod friend_system_msg;
 pub mod group_system_msg;
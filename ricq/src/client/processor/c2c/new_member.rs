@@ -17,15 +17,14 @@ impl Client {
         let group_code = group_uin2code(head.from_uin());
         let member_uin = head.auth_uin();
 
-        self.handler
-            .handle(QEvent::NewMember(NewMemberEvent {
-                client: self.clone(),
-                inner: NewMember {
-                    group_code,
-                    member_uin,
-                },
-            }))
-            .await;
+        self.handle_event(QEvent::NewMember(NewMemberEvent {
+            client: self.clone(),
+            inner: Arc::new(NewMember {
+                group_code,
+                member_uin,
+            }),
+        }))
+        .await;
 
         Ok(())
     }
@@ -1,10 +1,12 @@
 use std::sync::Arc;
 
+use bytes::Bytes;
+use ricq_core::common::group_uin2code;
 use ricq_core::msg::MessageChain;
-use ricq_core::structs::GroupTempMessage;
+use ricq_core::structs::{GroupTempMessage, TempMessage, TempSession, TempSessionScene};
 use ricq_core::{pb, RQError, RQResult};
 
-use crate::client::event::GroupTempMessageEvent;
+use crate::client::event::{GroupTempMessageEvent, TempMessageEvent};
 use crate::handler::QEvent;
 use crate::Client;
 
@@ -12,37 +14,74 @@ impl Client {
     pub(crate) async fn process_temp_message(
         self: &Arc<Self>,
         msg: pb::msg::Message,
+        offline: bool,
     ) -> RQResult<()> {
-        let message = parse_temp_message(msg)?;
-        self.handler
-            .handle(QEvent::GroupTempMessage(GroupTempMessageEvent {
+        let mut message = parse_temp_message(msg)?;
+        message.offline = offline;
+        if message.session.scene == TempSessionScene::Group {
+            self.handle_event(QEvent::GroupTempMessage(GroupTempMessageEvent {
                 client: self.clone(),
-                inner: message,
+                inner: Arc::new(GroupTempMessage {
+                    seqs: message.seqs,
+                    rands: message.rands,
+                    from_uin: message.from_uin,
+                    from_nick: message.from_nick,
+                    time: message.time,
+                    elements: message.elements,
+                    group_code: message.session.group_code,
+                    session: message.session,
+                    offline,
+                }),
             }))
             .await;
+        } else {
+            self.handle_event(QEvent::TempMessage(TempMessageEvent {
+                client: self.clone(),
+                inner: Arc::new(message),
+            }))
+            .await;
+        }
         Ok(())
     }
 }
 
-pub fn parse_temp_message(msg: pb::msg::Message) -> RQResult<GroupTempMessage> {
-    let head = msg.head.unwrap();
+pub fn parse_temp_message(msg: pb::msg::Message) -> RQResult<TempMessage> {
+    let head = msg.head.ok_or(RQError::EmptyField("head"))?;
     let tmp_head = head
         .c2c_tmp_msg_head
         .ok_or(RQError::EmptyField("c2c_tmp_msg_head"))?;
+    let rich_text = msg
+        .body
+        .and_then(|b| b.rich_text)
+        .ok_or(RQError::EmptyField("rich_text"))?;
+    // 部分临时会话只带 group_uin
+    let group_code = match tmp_head.group_code {
+        Some(code) if code != 0 => code,
+        _ => group_uin2code(tmp_head.group_uin.unwrap_or_default()),
+    };
+    let from_uin = head.from_uin.unwrap_or_default();
 
-    Ok(GroupTempMessage {
+    Ok(TempMessage {
         seqs: vec![head.msg_seq.unwrap_or_default()],
-        rands: vec![
-            if let Some(attr) = &msg.body.as_ref().unwrap().rich_text.as_ref().unwrap().attr {
-                attr.random()
-            } else {
-                0
-            },
-        ],
-        time: head.msg_time.unwrap(),
-        from_uin: head.from_uin.unwrap_or_default(),
+        rands: vec![rich_text
+            .attr
+            .as_ref()
+            .map(|attr| attr.random())
+            .unwrap_or_default()],
+        time: head.msg_time.unwrap_or_default(),
+        from_uin,
         from_nick: head.from_nick.unwrap_or_default(),
-        elements: MessageChain::from(msg.body.unwrap().rich_text.unwrap().elems), // todo ptt_store
-        group_code: tmp_head.group_code.unwrap_or_default(),
+        elements: MessageChain::from(rich_text.elems), // todo ptt_store
+        session: TempSession {
+            scene: TempSessionScene::from(tmp_head.service_type.unwrap_or_default()),
+            uin: from_uin,
+            c2c_type: tmp_head.c2c_type.unwrap_or_default(),
+            service_type: tmp_head.service_type.unwrap_or_default(),
+            group_code,
+            sig: Bytes::from(tmp_head.sig.unwrap_or_default()),
+            from_phone: tmp_head.from_phone.unwrap_or_default(),
+            to_phone: tmp_head.to_phone.unwrap_or_default(),
+        },
+        offline: false,
     })
 }
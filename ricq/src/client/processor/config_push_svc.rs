@@ -1,3 +1,4 @@
+use std::sync::Arc;
 use std::time::Duration;
 
 use bytes::Bytes;
@@ -5,6 +6,7 @@ use bytes::Bytes;
 use ricq_core::command::config_push_svc::ConfigPushBody;
 use ricq_core::command::config_push_svc::ConfigPushReq;
 use ricq_core::common::RQAddr;
+use ricq_core::structs::{ServerNotice, ServerNoticeKind};
 
 use crate::client::tcp::sort_addrs;
 use crate::client::Client;
@@ -12,7 +14,7 @@ use crate::RQError;
 
 impl Client {
     pub(crate) async fn process_config_push_req(
-        &self,
+        self: &Arc<Self>,
         config_push_req: ConfigPushReq,
     ) -> Result<(), RQError> {
         // send response to server
@@ -25,7 +27,22 @@ impl Client {
         self.send(response).await?;
         match config_push_req.body {
             ConfigPushBody::Unknown => {}
-            ConfigPushBody::SsoServers { .. } => {}
+            ConfigPushBody::SsoServers { servers } => {
+                // 服务器要求更换地址，记录下来供之后连接使用，是否重连交给使用者决定
+                let content = servers
+                    .iter()
+                    .map(|s| format!("{}:{}", s.server, s.port))
+                    .collect::<Vec<_>>()
+                    .join(",");
+                let addrs = self.resolve_sso_servers(servers).await;
+                self.set_sso_servers(addrs).await;
+                self.handle_server_notice(ServerNotice {
+                    kind: ServerNoticeKind::ServerChange,
+                    content,
+                    ..Default::default()
+                })
+                .await;
+            }
             ConfigPushBody::FileStorageInfo { info: _, rsp_body } => {
                 let mut session = self.highway_session.write().await;
                 if let Some(rsp_body) = rsp_body {
@@ -0,0 +1,231 @@
+use std::sync::Arc;
+
+use ricq_core::pb;
+use ricq_core::structs::{GroupGift, GroupPoke, RawGrayTip, ServerNotice, ServerNoticeKind};
+
+use crate::client::event::{GroupGiftEvent, GroupPokeEvent, RawGrayTipEvent};
+use crate::client::handler::QEvent;
+use crate::client::Client;
+
+// 群戳一戳灰条的模板 id
+const POKE_TEMPL_IDS: [u64; 6] = [1132, 1133, 1134, 1135, 1136, 10043];
+// 系统重置昵称 / 群名片灰条的模板 id
+const NICKNAME_RESET_TEMPL_IDS: [u64; 2] = [10051, 10052];
+// 账号安全提醒灰条的业务类型
+const SECURITY_WARNING_BUSI_TYPES: [u64; 2] = [17, 18];
+
+/// 群灰条按模板分类后的结果
+#[derive(Debug)]
+enum GroupGrayTip {
+    Gift(GroupGift),
+    Notice(ServerNotice),
+    Poke(GroupPoke),
+    Raw(RawGrayTip),
+}
+
+impl Client {
+    /// 群灰条分发，已知模板转为对应事件，其他的作为 RawGrayTip 交给使用者处理
+    pub(crate) async fn dispatch_group_gray_tip(
+        self: &Arc<Self>,
+        group_code: i64,
+        tip: pb::notify::GeneralGrayTipInfo,
+    ) {
+        match classify_group_gray_tip(group_code, &tip) {
+            GroupGrayTip::Gift(gift) => {
+                self.handle_event(QEvent::GroupGift(GroupGiftEvent {
+                    client: self.clone(),
+                    inner: Arc::new(gift),
+                }))
+                .await
+            }
+            GroupGrayTip::Notice(notice) => self.handle_server_notice(notice).await,
+            GroupGrayTip::Poke(poke) => {
+                self.handle_event(QEvent::GroupPoke(GroupPokeEvent {
+                    client: self.clone(),
+                    inner: Arc::new(poke),
+                }))
+                .await
+            }
+            GroupGrayTip::Raw(raw) => {
+                self.handle_event(QEvent::RawGrayTip(RawGrayTipEvent {
+                    client: self.clone(),
+                    inner: Arc::new(raw),
+                }))
+                .await
+            }
+        }
+    }
+}
+
+fn classify_group_gray_tip(group_code: i64, tip: &pb::notify::GeneralGrayTipInfo) -> GroupGrayTip {
+    if let Some(gift) = parse_group_gift(group_code, tip) {
+        return GroupGrayTip::Gift(gift);
+    }
+    let param = |name: &str| {
+        tip.msg_templ_param
+            .iter()
+            .find(|p| p.name == name)
+            .map(|p| p.value.as_str())
+    };
+    let sender: i64 = param("uin_str1")
+        .and_then(|v| v.parse().ok())
+        .unwrap_or_default();
+    // 部分客户端版本的戳一戳使用其他模板，但都带有动作名称
+    let is_poke = POKE_TEMPL_IDS.contains(&tip.templ_id) || param("action_str").is_some();
+    if sender != 0 && is_poke {
+        return GroupGrayTip::Poke(GroupPoke {
+            group_code,
+            sender,
+            receiver: param("uin_str2")
+                .and_then(|v| v.parse().ok())
+                .unwrap_or_default(),
+        });
+    }
+    let notice = parse_server_notice(group_code, tip);
+    if notice.kind != ServerNoticeKind::Other || (sender == 0 && !notice.content.is_empty()) {
+        return GroupGrayTip::Notice(notice);
+    }
+    GroupGrayTip::Raw(RawGrayTip {
+        group_code,
+        templ_id: tip.templ_id,
+        busi_type: tip.busi_type,
+        busi_id: tip.busi_id,
+        content: tip.content.clone(),
+        templ_params: tip
+            .msg_templ_param
+            .iter()
+            .map(|p| (p.name.clone(), p.value.clone()))
+            .collect(),
+    })
+}
+
+/// 礼物灰条带有礼物名称参数，据此与戳一戳等灰条区分
+fn parse_group_gift(group_code: i64, tip: &pb::notify::GeneralGrayTipInfo) -> Option<GroupGift> {
+    let param = |names: &[&str]| {
+        tip.msg_templ_param
+            .iter()
+            .find(|p| names.contains(&p.name.as_str()))
+            .map(|p| p.value.clone())
+    };
+    let gift_name = param(&["gift_name", "giftName"])?;
+    Some(GroupGift {
+        group_code,
+        sender: param(&["uin_str1"])?.parse().unwrap_or_default(),
+        receiver: param(&["uin_str2"])
+            .and_then(|v| v.parse().ok())
+            .unwrap_or_default(),
+        gift_name,
+        gift_count: param(&["gift_count", "giftCount", "count"])
+            .and_then(|v| v.parse().ok())
+            .unwrap_or(1),
+        templ_params: tip
+            .msg_templ_param
+            .iter()
+            .map(|p| (p.name.clone(), p.value.clone()))
+            .collect(),
+    })
+}
+
+/// 按模板 id 和业务类型区分昵称重置和安全提醒，其他为 Other
+pub(super) fn parse_server_notice(
+    group_code: i64,
+    tip: &pb::notify::GeneralGrayTipInfo,
+) -> ServerNotice {
+    let mut content = tip.content.clone();
+    for p in &tip.msg_templ_param {
+        content = content.replace(&format!("{{{}}}", p.name), &p.value);
+    }
+    let kind = if NICKNAME_RESET_TEMPL_IDS.contains(&tip.templ_id) {
+        ServerNoticeKind::NicknameReset
+    } else if SECURITY_WARNING_BUSI_TYPES.contains(&tip.busi_type) {
+        ServerNoticeKind::SecurityWarning
+    } else {
+        ServerNoticeKind::Other
+    };
+    ServerNotice {
+        kind,
+        group_code,
+        content,
+        templ_id: tip.templ_id,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn templ_param(name: &str, value: &str) -> pb::notify::TemplParam {
+        pb::notify::TemplParam {
+            name: name.into(),
+            value: value.into(),
+        }
+    }
+
+    #[test]
+    fn test_parse_server_notice() {
+        let tip = pb::notify::GeneralGrayTipInfo {
+            templ_id: NICKNAME_RESET_TEMPL_IDS[0],
+            content: "{nick}的群昵称已被系统重置".into(),
+            msg_templ_param: vec![templ_param("nick", "abc")],
+            ..Default::default()
+        };
+        let notice = parse_server_notice(1, &tip);
+        assert_eq!(notice.kind, ServerNoticeKind::NicknameReset);
+        assert_eq!(notice.content, "abc的群昵称已被系统重置");
+        assert_eq!(notice.templ_id, NICKNAME_RESET_TEMPL_IDS[0]);
+
+        // 内容中的关键字不影响分类
+        let tip = pb::notify::GeneralGrayTipInfo {
+            templ_id: 1,
+            content: "群名片已重置，注意账号安全".into(),
+            ..Default::default()
+        };
+        assert_eq!(parse_server_notice(1, &tip).kind, ServerNoticeKind::Other);
+    }
+
+    #[test]
+    fn test_classify_group_gray_tip() {
+        let poke = pb::notify::GeneralGrayTipInfo {
+            templ_id: 1134,
+            msg_templ_param: vec![templ_param("uin_str1", "2"), templ_param("uin_str2", "3")],
+            ..Default::default()
+        };
+        match classify_group_gray_tip(1, &poke) {
+            GroupGrayTip::Poke(p) => assert_eq!((p.sender, p.receiver), (2, 3)),
+            other => panic!("unexpected {other:?}"),
+        }
+
+        // 戳一戳的后缀中带有“安全”等字样时仍然是戳一戳
+        let poke = pb::notify::GeneralGrayTipInfo {
+            templ_id: 1134,
+            content: "{uin_str1}戳了戳{uin_str2}{suffix_str}".into(),
+            msg_templ_param: vec![
+                templ_param("uin_str1", "2"),
+                templ_param("uin_str2", "3"),
+                templ_param("suffix_str", "的安全帽"),
+            ],
+            ..Default::default()
+        };
+        assert!(matches!(
+            classify_group_gray_tip(1, &poke),
+            GroupGrayTip::Poke(_)
+        ));
+
+        // 群荣誉等未识别的模板不再被当作戳一戳
+        let honor = pb::notify::GeneralGrayTipInfo {
+            templ_id: 1052,
+            busi_type: 12,
+            content: "{uin_str1}获得龙王标识".into(),
+            msg_templ_param: vec![templ_param("uin_str1", "2")],
+            ..Default::default()
+        };
+        match classify_group_gray_tip(1, &honor) {
+            GroupGrayTip::Raw(raw) => {
+                assert_eq!(raw.templ_id, 1052);
+                assert_eq!(raw.param("uin_str1"), Some("2"));
+                assert_eq!(raw.content, "{uin_str1}获得龙王标识");
+            }
+            other => panic!("unexpected {other:?}"),
+        }
+    }
+}
@@ -1,11 +1,15 @@
+use std::sync::atomic::{AtomicBool, Ordering};
 use std::sync::Arc;
 use std::time::UNIX_EPOCH;
 
 use cached::Cached;
+use futures_util::FutureExt;
+use tokio::sync::Mutex;
 
-use ricq_core::{jce, pb};
+use ricq_core::{jce, pb, RQResult};
 
 use crate::client::event::KickedOfflineEvent;
+use crate::client::processor::conversation_key;
 use crate::client::{Client, NetworkStatus};
 use crate::handler::QEvent;
 
@@ -39,64 +43,124 @@ impl Client {
             }
         }
         // pull friend msg and other, then process
-        let all_message = self.sync_all_message().await;
-        match all_message {
+        self.sync_pushed_messages().await;
+    }
+
+    /// PushNotify 后拉取消息，注册后的第一次同步按离线消息处理
+    async fn sync_pushed_messages(self: &Arc<Self>) {
+        let _guard = self.offline_sync.lock.lock().await;
+        let offline = self.offline_sync.take();
+        match self.sync_all_message().await {
             Ok(msgs) => {
-                self.process_message_sync(msgs).await;
+                self.process_message_sync(msgs, offline).await;
             }
             Err(err) => {
+                if offline {
+                    self.offline_sync.arm(true);
+                }
                 tracing::warn!("failed to sync message {}", err);
             }
         }
     }
 
+    /// 注册后还没有 PushNotify 触发同步时，主动拉取离线消息
+    pub(crate) async fn sync_pending_offline_messages(self: &Arc<Self>) -> RQResult<Option<usize>> {
+        let _guard = self.offline_sync.lock.lock().await;
+        if !self.offline_sync.take() {
+            return Ok(None);
+        }
+        let msgs = self.sync_all_message().await?;
+        let count = msgs.len();
+        self.process_message_sync(msgs, true).await;
+        Ok(Some(count))
+    }
+
     pub(crate) async fn process_push_force_offline(
         self: &Arc<Self>,
         offline: jce::RequestPushForceOffline,
     ) {
         self.stop(NetworkStatus::KickedOffline);
-        self.handler
-            .handle(QEvent::KickedOffline(KickedOfflineEvent {
-                client: self.clone(),
-                inner: offline,
-            }))
-            .await;
+        self.handle_event(QEvent::KickedOffline(KickedOfflineEvent {
+            client: self.clone(),
+            inner: Arc::new(offline),
+        }))
+        .await;
     }
 
-    pub(crate) async fn process_message_sync(self: &Arc<Self>, msgs: Vec<pb::msg::Message>) {
+    /// offline 为 true 时处理的是登录前的消息，不按时间过滤
+    pub(crate) async fn process_message_sync(
+        self: &Arc<Self>,
+        msgs: Vec<pb::msg::Message>,
+        offline: bool,
+    ) {
         for msg in msgs {
-            let head = msg.head.clone().unwrap();
-            if self.msg_exists(&head).await {
+            let head = match msg.head.clone() {
+                Some(head) => head,
+                None => {
+                    tracing::warn!("skip message without head");
+                    continue;
+                }
+            };
+            if self.msg_exists(&head, offline).await {
                 continue;
             }
-            match msg.head.as_ref().unwrap().msg_type() {
-                9 | 10 | 31 | 79 | 97 | 120 | 132 | 133 | 166 | 167 => {
-                    if let Err(err) = self.process_friend_message(msg).await {
-                        tracing::error!("failed to process friend message {err}");
-                    }
+            // 同一个会话的消息按顺序处理，不同会话并发
+            let key = conversation_key(head.from_uin(), head.to_uin());
+            let client = self.clone();
+            self.packet_workers().run(
+                key,
+                async move {
+                    client
+                        .process_sync_message(head.msg_type(), msg, offline)
+                        .await
                 }
-                33 => {
-                    if let Err(err) = self.process_join_group(msg).await {
-                        tracing::error!("failed to process join group {err}");
-                    }
+                .boxed(),
+            );
+        }
+    }
+
+    async fn process_sync_message(
+        self: &Arc<Self>,
+        msg_type: i32,
+        msg: pb::msg::Message,
+        offline: bool,
+    ) {
+        match msg_type {
+            9 | 10 | 31 | 79 | 97 | 120 | 132 | 133 | 166 | 167 => {
+                if let Err(err) = self.process_friend_message(msg, offline).await {
+                    tracing::error!("failed to process friend message {err}");
                 }
-                140 | 141 => {
-                    if let Err(err) = self.process_temp_message(msg).await {
-                        tracing::error!("failed to process temp message {err}");
-                    }
+            }
+            33 => {
+                if let Err(err) = self.process_join_group(msg).await {
+                    tracing::error!("failed to process join group {err}");
                 }
-                208 => {
-                    // friend ptt_store
+            }
+            140 | 141 => {
+                if let Err(err) = self.process_temp_message(msg, offline).await {
+                    tracing::error!("failed to process temp message {err}");
+                }
+            }
+            529 => {
+                if let Err(err) = self.process_friend_file(msg).await {
+                    tracing::error!("failed to process friend file {err}");
                 }
-                _ => tracing::warn!("unhandled sync message type"),
             }
+            208 => {
+                // friend ptt_store
+            }
+            _ => tracing::warn!("unhandled sync message type"),
         }
     }
 
-    async fn msg_exists(&self, head: &pb::msg::MessageHead) -> bool {
+    async fn msg_exists(&self, head: &pb::msg::MessageHead, offline: bool) -> bool {
         let now = UNIX_EPOCH.elapsed().unwrap().as_secs() as i32;
-        let msg_time = head.msg_time.unwrap_or_default();
-        if now - msg_time > 60 || self.start_time > msg_time {
+        if is_stale(
+            now,
+            self.start_time,
+            head.msg_time.unwrap_or_default(),
+            offline,
+        ) {
             return true;
         }
         let mut c2c_cache = self.c2c_cache.write().await;
@@ -117,3 +181,48 @@ impl Client {
         false
     }
 }
+
+/// 登录前的消息，只有按离线消息处理时保留
+fn is_stale(now: i32, start_time: i32, msg_time: i32, offline: bool) -> bool {
+    !offline && (now - msg_time > 60 || start_time > msg_time)
+}
+
+/// 注册客户端后服务器会推送 PushNotify，第一次同步如果不按离线消息处理，
+/// 离线消息会被过滤并从服务器删除
+#[derive(Default)]
+pub(crate) struct OfflineSync {
+    pending: AtomicBool,
+    /// 同步消息会删除服务器上的消息并更新 sync_cookie，同时只进行一次
+    pub(crate) lock: Mutex<()>,
+}
+
+impl OfflineSync {
+    /// 注册客户端之前调用
+    pub(crate) fn arm(&self, enabled: bool) {
+        self.pending.store(enabled, Ordering::SeqCst);
+    }
+
+    /// 这次同步是否按离线消息处理
+    pub(crate) fn take(&self) -> bool {
+        self.pending.swap(false, Ordering::SeqCst)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_offline_sync_order() {
+        let sync = OfflineSync::default();
+        sync.arm(true);
+        // 注册后的第一次同步保留登录前的消息，之后恢复按时间过滤
+        assert!(sync.take());
+        assert!(!is_stale(1000, 900, 100, true));
+        assert!(!sync.take());
+        assert!(is_stale(1000, 900, 100, false));
+        assert!(!is_stale(1000, 900, 990, false));
+        sync.arm(false);
+        assert!(!sync.take());
+    }
+}
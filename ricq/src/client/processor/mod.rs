@@ -3,15 +3,23 @@ use std::sync::Arc;
 use bytes::Bytes;
 
 use ricq_core::protocol::packet::Packet;
+use ricq_core::structs::{PacketDecodeError, RawPacket};
+
+use crate::client::event::{DecodeErrorEvent, RawPacketEvent};
+use crate::handler::QEvent;
 
 pub mod c2c;
 pub mod config_push_svc;
+mod gray_tip;
 pub mod message_svc;
 pub mod online_push;
 pub mod reg_prxy_svc;
 pub mod stat_svc;
+mod workers;
 pub mod wtlogin;
 
+pub(crate) use workers::{conversation_key, PacketWorkers};
+
 macro_rules! log_error {
     ($process: expr, $info: expr) => {
         if let Err(e) = $process {
@@ -21,186 +29,250 @@ macro_rules! log_error {
 }
 
 impl super::Client {
-    /// 接收到的 Packet 统一分发
+    /// handler 是否需要其中任一类型的事件，不需要时可以跳过解析
+    pub(crate) fn subscribes(&self, kinds: &[&str]) -> bool {
+        kinds.iter().any(|kind| self.handler.subscribes(kind))
+    }
+
+    /// 推送解析失败时记录日志并上报 DecodeError 事件，调用方跳过这条推送
+    pub(crate) async fn report_decode_error(
+        self: &Arc<Self>,
+        command: &str,
+        reason: impl std::fmt::Display,
+        payload: Bytes,
+    ) {
+        tracing::warn!("failed to decode [{}]: {}", command, reason);
+        if self.subscribes(&["DecodeError"]) {
+            self.handle_event(QEvent::DecodeError(DecodeErrorEvent {
+                client: self.clone(),
+                inner: Arc::new(PacketDecodeError {
+                    command: command.to_string(),
+                    reason: reason.to_string(),
+                    payload,
+                }),
+            }))
+            .await;
+        }
+    }
+
+    /// 接收到的 Packet 统一分发，与 net_loop 使用同一个任务池，同一会话的包按顺序处理
     pub async fn process_income_packet(self: &Arc<Self>, pkt: Packet) {
+        if let Some(pkt) = self.intercept_packet(pkt).await {
+            self.packet_workers().dispatch(pkt);
+        }
+    }
+
+    /// 交给 send_and_wait / wait_packet / listen_command，被截流时返回 None
+    pub(crate) async fn intercept_packet(&self, pkt: Packet) -> Option<Packet> {
         tracing::trace!("received pkt: {}", &pkt.command_name);
         // response, send_and_wait 的包将会在此被截流
         {
             if let Some(sender) = self.packet_promises.write().await.remove(&pkt.seq_id) {
-                sender.send(pkt).unwrap();
-                return;
+                // send_and_wait 已经超时
+                sender.send(pkt).ok();
+                return None;
             }
         }
 
         tracing::trace!("pkt: {} passed packet_promises", &pkt.command_name);
 
+        {
+            let mut packet_matchers = self.packet_matchers.lock().await;
+            // 等待超时的匹配器
+            packet_matchers.retain(|(_, sender)| !sender.is_closed());
+            if let Some(index) = packet_matchers
+                .iter()
+                .position(|(matcher, _)| matcher(&pkt))
+            {
+                let (_, sender) = packet_matchers.remove(index);
+                sender.send(pkt).ok();
+                return None;
+            }
+        }
+
         {
             if let Some(handler) = self.packet_handler.read().await.get(&pkt.command_name) {
                 let _ = handler.send(pkt.clone());
             }
         }
 
-        let cli = self.clone();
-        tokio::spawn(async move {
-            match pkt.command_name.as_ref() {
-                "OnlinePush.PbPushGroupMsg" => {
-                    let p = cli
-                        .engine
-                        .read()
-                        .await
-                        .decode_group_message_packet(pkt.body);
-                    match p {
-                        Ok(part) => {
-                            log_error!(
-                                cli.process_group_message_part(part).await,
-                                "process_group_message_part error: {:?}"
-                            )
-                        }
-                        Err(err) => {
-                            tracing::warn!("failed to decode [OnlinePush.PbPushGroupMsg]: {}", err);
-                        }
+        if pkt.command_name == "OnlinePush.PbPushGroupMsg"
+            && self.complete_own_group_message(&pkt.body).await
+        {
+            return None;
+        }
+
+        Some(pkt)
+    }
+
+    /// 解析并处理推送包
+    pub(crate) async fn dispatch_packet(self: &Arc<Self>, pkt: Packet) {
+        match pkt.command_name.as_ref() {
+            "OnlinePush.PbPushGroupMsg" => {
+                let p = self
+                    .engine
+                    .read()
+                    .await
+                    .decode_group_message_packet(pkt.body);
+                match p {
+                    Ok(part) => {
+                        log_error!(
+                            self.process_group_message_part(part).await,
+                            "process_group_message_part error: {:?}"
+                        )
                     }
-                }
-                "ConfigPushSvc.PushReq" => {
-                    let req = cli.engine.read().await.decode_push_req_packet(pkt.body);
-                    match req {
-                        Ok(req) => {
-                            log_error!(
-                                cli.process_config_push_req(req).await,
-                                "process_config_push_req error: {:?}"
-                            )
-                        }
-                        Err(err) => {
-                            tracing::warn!("failed to decode [ConfigPushSvc.PushReq]: {}", err);
-                        }
+                    Err(err) => {
+                        tracing::warn!("failed to decode [OnlinePush.PbPushGroupMsg]: {}", err);
                     }
                 }
-                "RegPrxySvc.PushParam" => {
-                    let other_clients = cli.engine.read().await.decode_push_param_packet(&pkt.body);
-                    match other_clients {
-                        Ok(other_clients) => {
-                            log_error!(
-                                cli.process_push_param(other_clients).await,
-                                "process_push_param error: {:?}"
-                            )
-                        }
-                        Err(err) => {
-                            tracing::warn!("failed to decode [RegPrxySvc.PushParam]: {}", err);
-                        }
+            }
+            "ConfigPushSvc.PushReq" => {
+                let req = self.engine.read().await.decode_push_req_packet(pkt.body);
+                match req {
+                    Ok(req) => {
+                        log_error!(
+                            self.process_config_push_req(req).await,
+                            "process_config_push_req error: {:?}"
+                        )
                     }
-                }
-                "MessageSvc.PushNotify" => {
-                    // c2c流程：
-                    // 1. Server 发送 PushNotify 到 Client, 表示有通知需要 Client 拉取 (不带具体内容)
-                    // 2. Client 根据 msg_type 发送请求拉取具体通知内容
-                    // 类型：好友申请、群申请、私聊消息、其他?
-                    let resp = cli.engine.read().await.decode_svc_notify(pkt.body);
-                    match resp {
-                        Ok(notify) => {
-                            cli.process_push_notify(notify).await;
-                        }
-                        Err(err) => {
-                            tracing::warn!("failed to decode [MessageSvc.PushNotify]: {}", err);
-                        }
+                    Err(err) => {
+                        tracing::warn!("failed to decode [ConfigPushSvc.PushReq]: {}", err);
                     }
                 }
-                "OnlinePush.ReqPush" => {
-                    let resp = cli
-                        .engine
-                        .read()
-                        .await
-                        .decode_online_push_req_packet(pkt.body);
-                    match resp {
-                        Ok(resp) => {
-                            log_error!(
-                                cli.delete_online_push(
-                                    resp.uin,
-                                    0,
-                                    Bytes::new(),
-                                    pkt.seq_id as u16,
-                                    resp.msg_infos.clone(),
-                                )
-                                .await,
-                                "delete_online_push error: {:?}"
-                            );
-                            cli.process_push_req(resp.msg_infos).await;
-                        }
-                        Err(err) => {
-                            tracing::warn!("failed to decode [OnlinePush.ReqPush]: {}", err);
-                        }
+            }
+            "RegPrxySvc.PushParam" => {
+                let other_clients = self.engine.read().await.decode_push_param_packet(&pkt.body);
+                match other_clients {
+                    Ok(other_clients) => {
+                        log_error!(
+                            self.process_push_param(other_clients).await,
+                            "process_push_param error: {:?}"
+                        )
+                    }
+                    Err(err) => {
+                        tracing::warn!("failed to decode [RegPrxySvc.PushParam]: {}", err);
                     }
                 }
-                "OnlinePush.PbPushTransMsg" => {
-                    let online_push_trans = cli
-                        .engine
-                        .read()
-                        .await
-                        .decode_online_push_trans_packet(pkt.body);
-                    match online_push_trans {
-                        Ok(online_push_trans) => {
-                            cli.process_push_trans(online_push_trans).await;
-                        }
-                        Err(err) => {
-                            tracing::warn!("failed to decode [OnlinePush.PbPushTransMsg]: {}", err);
-                        }
+            }
+            "MessageSvc.PushNotify" => {
+                // c2c流程：
+                // 1. Server 发送 PushNotify 到 Client, 表示有通知需要 Client 拉取 (不带具体内容)
+                // 2. Client 根据 msg_type 发送请求拉取具体通知内容
+                // 类型：好友申请、群申请、私聊消息、其他?
+                let resp = self.engine.read().await.decode_svc_notify(pkt.body);
+                match resp {
+                    Ok(notify) => {
+                        self.process_push_notify(notify).await;
+                    }
+                    Err(err) => {
+                        tracing::warn!("failed to decode [MessageSvc.PushNotify]: {}", err);
                     }
                 }
-                "MessageSvc.PushForceOffline" => {
-                    let offline = cli.engine.read().await.decode_force_offline(pkt.body);
-                    match offline {
-                        Ok(offline) => {
-                            cli.process_push_force_offline(offline).await;
-                        }
-                        Err(err) => {
-                            tracing::warn!(
-                                "failed to decode [MessageSvc.PushForceOffline]: {}",
-                                err
-                            );
-                        }
+            }
+            "OnlinePush.ReqPush" => {
+                let resp = self
+                    .engine
+                    .read()
+                    .await
+                    .decode_online_push_req_packet(pkt.body);
+                match resp {
+                    Ok(resp) => {
+                        log_error!(
+                            self.delete_online_push(
+                                resp.uin,
+                                0,
+                                Bytes::new(),
+                                pkt.seq_id as u16,
+                                resp.msg_infos.clone(),
+                            )
+                            .await,
+                            "delete_online_push error: {:?}"
+                        );
+                        self.process_push_req(resp.msg_infos).await;
+                    }
+                    Err(err) => {
+                        tracing::warn!("failed to decode [OnlinePush.ReqPush]: {}", err);
                     }
                 }
-                "StatSvc.ReqMSFOffline" => {
-                    let offline = cli.engine.read().await.decode_msf_force_offline(pkt.body);
-                    match offline {
-                        Ok(offline) => {
-                            cli.process_msf_force_offline(offline).await;
-                        }
-                        Err(err) => {
-                            tracing::warn!("failed to decode [StatSvc.ReqMSFOffline]: {}", err);
-                        }
+            }
+            "OnlinePush.PbPushTransMsg" => {
+                let online_push_trans = self
+                    .engine
+                    .read()
+                    .await
+                    .decode_online_push_trans_packet(pkt.body);
+                match online_push_trans {
+                    Ok(online_push_trans) => {
+                        self.process_push_trans(online_push_trans).await;
+                    }
+                    Err(err) => {
+                        tracing::warn!("failed to decode [OnlinePush.PbPushTransMsg]: {}", err);
                     }
                 }
-                "OnlinePush.PbC2CMsgSync" => {
-                    // 其他设备发送消息，同步
-                    let push = cli.engine.read().await.decode_c2c_sync_packet(pkt.body);
-                    match push {
-                        Ok(push) => {
-                            log_error!(
-                                cli.process_c2c_sync(pkt.seq_id, push).await,
-                                "process_c2c_sync error: {:?}"
-                            )
-                        }
-                        Err(err) => {
-                            tracing::warn!("failed to decode [OnlinePush.PbC2CMsgSync]: {}", err);
-                        }
+            }
+            "MessageSvc.PushForceOffline" => {
+                let offline = self.engine.read().await.decode_force_offline(pkt.body);
+                match offline {
+                    Ok(offline) => {
+                        self.process_push_force_offline(offline).await;
+                    }
+                    Err(err) => {
+                        tracing::warn!("failed to decode [MessageSvc.PushForceOffline]: {}", err);
                     }
                 }
-                "OnlinePush.SidTicketExpired" => {
-                    log_error!(
-                        cli.process_sid_ticket_expired(pkt.seq_id).await,
-                        "process_sid_ticket_expired error: {:?}"
-                    )
+            }
+            "StatSvc.ReqMSFOffline" => {
+                let offline = self.engine.read().await.decode_msf_force_offline(pkt.body);
+                match offline {
+                    Ok(offline) => {
+                        self.process_msf_force_offline(offline).await;
+                    }
+                    Err(err) => {
+                        tracing::warn!("failed to decode [StatSvc.ReqMSFOffline]: {}", err);
+                    }
                 }
-                "RegPrxySvc.GetMsgV2"
-                | "RegPrxySvc.PbGetMsg"
-                | "RegPrxySvc.NoticeEnd"
-                | "MessageSvc.PushReaded" => {
-                    tracing::trace!("ignore pkt: {}", &pkt.command_name);
+            }
+            "OnlinePush.PbC2CMsgSync" => {
+                // 其他设备发送消息，同步
+                let push = self.engine.read().await.decode_c2c_sync_packet(pkt.body);
+                match push {
+                    Ok(push) => {
+                        log_error!(
+                            self.process_c2c_sync(pkt.seq_id, push).await,
+                            "process_c2c_sync error: {:?}"
+                        )
+                    }
+                    Err(err) => {
+                        tracing::warn!("failed to decode [OnlinePush.PbC2CMsgSync]: {}", err);
+                    }
                 }
-                _ => {
-                    tracing::debug!("unhandled pkt: {}", &pkt.command_name);
+            }
+            "OnlinePush.SidTicketExpired" => {
+                log_error!(
+                    self.process_sid_ticket_expired(pkt.seq_id).await,
+                    "process_sid_ticket_expired error: {:?}"
+                )
+            }
+            "RegPrxySvc.GetMsgV2"
+            | "RegPrxySvc.PbGetMsg"
+            | "RegPrxySvc.NoticeEnd"
+            | "MessageSvc.PushReaded" => {
+                tracing::trace!("ignore pkt: {}", &pkt.command_name);
+            }
+            _ => {
+                tracing::debug!("unhandled pkt: {}", &pkt.command_name);
+                if self.subscribes(&["RawPacket"]) {
+                    self.handle_event(QEvent::RawPacket(RawPacketEvent {
+                        client: self.clone(),
+                        inner: Arc::new(RawPacket {
+                            command: pkt.command_name,
+                            seq: pkt.seq_id,
+                            body: pkt.body,
+                        }),
+                    }))
+                    .await;
                 }
             }
-        });
+        }
     }
 }
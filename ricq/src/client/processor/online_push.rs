@@ -5,58 +5,120 @@ use bytes::{Buf, Bytes};
 use cached::Cached;
 
 use prost::Message;
+use ricq_core::command::online_push::decoder::peek_from_uin;
 use ricq_core::command::online_push::GroupMessagePart;
 use ricq_core::command::online_push::{OnlinePushTrans, PushTransInfo};
 use ricq_core::msg::MessageChain;
 use ricq_core::structs::{
-    DeleteFriend, FriendInfo, FriendMessageRecall, FriendPoke, GroupAudio, GroupAudioMessage,
-    GroupLeave, GroupMessage, GroupMessageRecall, GroupMute, GroupNameUpdate, GroupPoke,
+    BotMute, DeleteFriend, FriendInfo, FriendMessageRecall, FriendPoke, GroupAudio,
+    GroupAudioMessage, GroupLeave, GroupMessage, GroupMessageRecall, GroupMute, GroupNameUpdate,
+    MessageReceipt, MessageTarget, ServerNotice, ServerNoticeKind,
 };
 use ricq_core::{jce, pb};
 
 use crate::client::event::{
-    DeleteFriendEvent, FriendMessageRecallEvent, FriendPokeEvent, GroupAudioMessageEvent,
-    GroupDisbandEvent, GroupLeaveEvent, GroupMessageEvent, GroupMessageRecallEvent, GroupMuteEvent,
-    GroupNameUpdateEvent, GroupPokeEvent, MemberPermissionChangeEvent, NewFriendEvent,
+    BotMuteEvent, DeleteFriendEvent, FriendMessageRecallEvent, FriendPokeEvent,
+    GroupAudioMessageEvent, GroupDisbandEvent, GroupLeaveEvent, GroupMessageEvent,
+    GroupMessageRecallEvent, GroupMuteEvent, GroupNameUpdateEvent, MemberPermissionChangeEvent,
+    NewFriendEvent, SelfGroupMessageEvent, ServerNoticeEvent,
 };
 use crate::client::handler::QEvent;
 use crate::client::Client;
 use crate::RQResult;
 
+use super::gray_tip::parse_server_notice;
+
+const PUSH_REQ: &str = "OnlinePush.ReqPush";
+
+// 解析失败时上报 DecodeError 并跳过这条推送
+macro_rules! decode_or_continue {
+    ($client: expr, $decode: expr, $payload: expr) => {
+        match $decode {
+            Ok(v) => v,
+            Err(err) => {
+                $client
+                    .report_decode_error(PUSH_REQ, err, $payload.clone())
+                    .await;
+                continue;
+            }
+        }
+    };
+}
+
 impl Client {
+    /// 自身发送的群消息推送交给等待回执的发送方，返回是否被处理，
+    /// 等待超时后才收到的推送也会被丢弃，避免作为普通消息上报
+    ///
+    /// 在分发给 worker 之前调用，handler 中发送消息时回执不会排在 handler 后面
+    pub(crate) async fn complete_own_group_message(&self, body: &Bytes) -> bool {
+        let uin = self.uin().await;
+        if peek_from_uin(body) != Some(uin) {
+            return false;
+        }
+        let part = match self
+            .engine
+            .read()
+            .await
+            .decode_group_message_packet(body.clone())
+        {
+            Ok(part) => part,
+            Err(_) => return false,
+        };
+        if let Some(tx) = self.receipt_waiters.lock().await.cache_remove(&part.rand) {
+            let _ = tx.send(part.seq);
+            return true;
+        }
+        if self
+            .late_receipts
+            .lock()
+            .await
+            .cache_remove(&part.rand)
+            .is_some()
+        {
+            return true;
+        }
+        let receipt = MessageReceipt {
+            target: MessageTarget::Group(part.group_code),
+            seqs: vec![part.seq],
+            rands: vec![part.rand],
+            time: part.time as i64,
+        };
+        self.complete_share(receipt, part.elems.iter().filter_map(|e| e.elem.as_ref()))
+            .await
+    }
+
     pub(crate) async fn process_group_message_part(
         self: &Arc<Self>,
         group_message_part: GroupMessagePart,
     ) -> RQResult<()> {
-        // receipt message
-        if group_message_part.from_uin == self.uin().await {
-            if let Some(tx) = self
-                .receipt_waiters
-                .lock()
-                .await
-                .cache_remove(&group_message_part.rand)
-            {
-                let _ = tx.send(group_message_part.seq);
-                return Ok(());
-            }
+        // 等待回执的自身消息已经在 intercept_packet 中处理
+        let from_self = group_message_part.from_uin == self.uin().await;
+
+        // 没有订阅消息事件时仍然需要写入 message_store，供撤回事件使用
+        let wants_event =
+            self.subscribes(&["GroupMessage", "GroupAudioMessage", "SelfGroupMessage"]);
+        if !wants_event && self.message_store.read().await.is_none() {
+            return Ok(());
         }
 
         if let Some(ptt) = group_message_part.ptt {
-            self.handler
-                .handle(QEvent::GroupAudioMessage(GroupAudioMessageEvent {
-                    client: self.clone(),
-                    inner: GroupAudioMessage {
-                        seqs: vec![group_message_part.seq],
-                        rands: vec![group_message_part.rand],
-                        group_code: group_message_part.group_code,
-                        group_name: group_message_part.group_name,
-                        group_card: group_message_part.group_card,
-                        from_uin: group_message_part.from_uin,
-                        time: group_message_part.time,
-                        audio: GroupAudio(ptt),
-                    },
-                }))
-                .await;
+            if !wants_event {
+                return Ok(());
+            }
+            self.handle_event(QEvent::GroupAudioMessage(GroupAudioMessageEvent {
+                client: self.clone(),
+                inner: Arc::new(GroupAudioMessage {
+                    seqs: vec![group_message_part.seq],
+                    rands: vec![group_message_part.rand],
+                    group_code: group_message_part.group_code,
+                    group_name: group_message_part.group_name,
+                    group_card: group_message_part.group_card,
+                    from_uin: group_message_part.from_uin,
+                    time: group_message_part.time,
+                    audio: GroupAudio(ptt),
+                }),
+            }))
+            .await;
             return Ok(());
         }
 
@@ -86,12 +148,29 @@ impl Client {
         // handle message
         if let Some(group_msg) = group_msg {
             // message is finish
-            self.handler
-                .handle(QEvent::GroupMessage(GroupMessageEvent {
-                    client: self.clone(),
-                    inner: self.parse_group_message(group_msg).await?,
-                }))
-                .await; //todo
+            let mut message = self.parse_group_message(group_msg).await?;
+            if self.options.resolve_quoted_messages {
+                message.quoted = self
+                    .resolve_quoted(message.group_code, &message.elements, true)
+                    .await;
+                self.cache_quoted(message.group_code, &message.seqs, &message.elements, true)
+                    .await;
+            }
+            if let Some(store) = self.message_store.read().await.as_ref() {
+                store.put_group_message(&message);
+            }
+            if !wants_event {
+                return Ok(());
+            }
+            let inner = Arc::new(message);
+            let client = self.clone();
+            let event = if from_self && self.options.self_message_events {
+                // 其他设备发送的消息
+                QEvent::SelfGroupMessage(SelfGroupMessageEvent { client, inner })
+            } else {
+                QEvent::GroupMessage(GroupMessageEvent { client, inner })
+            };
+            self.handle_event(event).await;
         }
         Ok(())
     }
@@ -133,6 +212,7 @@ impl Client {
             from_uin,
             time,
             elements: MessageChain(elements),
+            quoted: None,
         })
 
         // TODO: extInfo
@@ -147,35 +227,74 @@ impl Client {
             }
             match info.msg_type {
                 732 => {
+                    let payload = info.v_msg.clone();
                     let mut r = info.v_msg;
+                    if r.remaining() < 6 {
+                        self.report_decode_error(PUSH_REQ, "732 too short", payload)
+                            .await;
+                        continue;
+                    }
                     let group_code = r.get_u32() as i64;
                     let i_type = r.get_u8();
                     r.get_u8();
                     match i_type {
-                        0x0c => {
+                        0x0c if self.subscribes(&["GroupMute", "BotMute"]) => {
+                            if r.remaining() < 18 {
+                                self.report_decode_error(PUSH_REQ, "732 0x0c too short", payload)
+                                    .await;
+                                continue;
+                            }
                             let operator = r.get_u32() as i64;
-                            if operator == self.uin().await {
+                            let self_uin = self.uin().await;
+                            if operator == self_uin {
                                 continue;
                             }
                             r.advance(6);
                             let target = r.get_u32() as i64;
                             let duration = Duration::from_secs(r.get_u32() as u64);
-                            self.handler
-                                .handle(QEvent::GroupMute(GroupMuteEvent {
+                            if target == self_uin {
+                                self.handle_event(QEvent::BotMute(BotMuteEvent {
                                     client: self.clone(),
-                                    inner: GroupMute {
+                                    inner: Arc::new(BotMute {
                                         group_code,
                                         operator_uin: operator,
-                                        target_uin: target,
                                         duration,
-                                    },
+                                    }),
                                 }))
                                 .await;
+                            }
+                            self.handle_event(QEvent::GroupMute(GroupMuteEvent {
+                                client: self.clone(),
+                                inner: Arc::new(GroupMute {
+                                    group_code,
+                                    operator_uin: operator,
+                                    target_uin: target,
+                                    duration,
+                                }),
+                            }))
+                            .await;
                         }
-                        0x10 | 0x11 | 0x14 | 0x15 => {
+                        0x10 | 0x11 | 0x14 | 0x15
+                            if self.subscribes(&[
+                                "GroupMessageRecall",
+                                "GroupGift",
+                                "GroupPoke",
+                                "ServerNotice",
+                                "RawGrayTip",
+                            ]) =>
+                        {
                             // group notify msg
+                            if !r.has_remaining() {
+                                self.report_decode_error(PUSH_REQ, "732 notify too short", payload)
+                                    .await;
+                                continue;
+                            }
                             r.advance(1);
-                            let b = pb::notify::NotifyMsgBody::decode(&*r).unwrap();
+                            let b = decode_or_continue!(
+                                self,
+                                pb::notify::NotifyMsgBody::decode(&*r),
+                                payload
+                            );
                             if let Some(opt_msg_recall) = b.opt_msg_recall {
                                 let operator_uin = opt_msg_recall.uin;
                                 // use map iterator here will produce massive asm code
@@ -183,136 +302,161 @@ impl Client {
                                     if rm.msg_type == 2 {
                                         continue;
                                     }
-                                    self.handler
-                                        .handle(QEvent::GroupMessageRecall(
-                                            GroupMessageRecallEvent {
-                                                client: self.clone(),
-                                                inner: GroupMessageRecall {
-                                                    msg_seq: rm.seq,
-                                                    group_code,
-                                                    operator_uin,
-                                                    author_uin: rm.author_uin,
-                                                    time: rm.time,
-                                                },
-                                            },
-                                        ))
-                                        .await;
-                                }
-                            }
-
-                            if let Some(t) = b.opt_general_gray_tip {
-                                let mut sender: i64 = 0;
-                                let mut receiver: i64 = 0;
-                                for templ in t.msg_templ_param {
-                                    match &*templ.name {
-                                        "uin_str1" => {
-                                            sender = templ.value.parse().unwrap_or_default()
-                                        }
-                                        "uin_str2" => {
-                                            receiver = templ.value.parse().unwrap_or_default()
-                                        }
-                                        _ => {}
-                                    }
-                                }
-                                if sender != 0 {
-                                    self.handler
-                                        .handle(QEvent::GroupPoke(GroupPokeEvent {
+                                    let message = match self.message_store.read().await.as_ref() {
+                                        Some(store) => store.get_group_message(group_code, rm.seq),
+                                        None => None,
+                                    };
+                                    self.handle_event(QEvent::GroupMessageRecall(
+                                        GroupMessageRecallEvent {
                                             client: self.clone(),
-                                            inner: GroupPoke {
+                                            inner: Arc::new(GroupMessageRecall {
+                                                msg_seq: rm.seq,
                                                 group_code,
-                                                sender,
-                                                receiver,
-                                            },
-                                        }))
-                                        .await;
+                                                operator_uin,
+                                                author_uin: rm.author_uin,
+                                                time: rm.time,
+                                                message,
+                                            }),
+                                        },
+                                    ))
+                                    .await;
                                 }
                             }
+
+                            if let Some(tip) = b.opt_general_gray_tip {
+                                self.dispatch_group_gray_tip(group_code, tip).await;
+                            }
                             // TODO 一些没什么用的 event 暂时没写
                         }
                         _ => {}
                     }
                 }
                 528 => {
+                    let payload = info.v_msg.clone();
                     let mut v_msg = info.v_msg;
-                    let msg: jce::MsgType0x210 = jcers::from_buf(&mut v_msg).unwrap();
+                    let msg: jce::MsgType0x210 =
+                        decode_or_continue!(self, jcers::from_buf(&mut v_msg), payload);
+                    // 好友增删时好友列表缓存失效
+                    if msg.sub_msg_type == 0xB3 || msg.sub_msg_type == 0x27 {
+                        self.friend_cache.write().await.cache_clear();
+                    }
                     match msg.sub_msg_type {
-                        0x8A | 0x8B => {
-                            let s8a = pb::Sub8A::decode(&*msg.v_protobuf).unwrap();
+                        0x8A | 0x8B if self.subscribes(&["FriendMessageRecall"]) => {
+                            let s8a = decode_or_continue!(
+                                self,
+                                pb::Sub8A::decode(&*msg.v_protobuf),
+                                payload
+                            );
                             for m in s8a.msg_info {
-                                self.handler
-                                    .handle(QEvent::FriendMessageRecall(FriendMessageRecallEvent {
+                                let message = match self.message_store.read().await.as_ref() {
+                                    Some(store) => store.get_friend_message(m.from_uin, m.msg_seq),
+                                    None => None,
+                                };
+                                self.handle_event(QEvent::FriendMessageRecall(
+                                    FriendMessageRecallEvent {
                                         client: self.clone(),
-                                        inner: FriendMessageRecall {
+                                        inner: Arc::new(FriendMessageRecall {
                                             msg_seq: m.msg_seq,
                                             friend_uin: m.from_uin,
                                             time: m.msg_time,
-                                        },
-                                    }))
-                                    .await;
+                                            message,
+                                        }),
+                                    },
+                                ))
+                                .await;
                             }
                         }
-                        0xB3 => {
-                            let msg_add_frd_notify = pb::SubB3::decode(&*msg.v_protobuf).unwrap();
+                        0xB3 if self.subscribes(&["NewFriend"]) => {
+                            let msg_add_frd_notify = decode_or_continue!(
+                                self,
+                                pb::SubB3::decode(&*msg.v_protobuf),
+                                payload
+                            );
                             if let Some(f) = msg_add_frd_notify.msg_add_frd_notify {
-                                self.handler
-                                    .handle(QEvent::NewFriend(NewFriendEvent {
-                                        client: self.clone(),
-                                        inner: FriendInfo {
-                                            uin: f.uin,
-                                            nick: f.nick,
-                                            ..Default::default()
-                                        },
-                                    }))
-                                    .await;
-                            }
-                        }
-                        0xD4 => {
-                            let d4 = pb::SubD4::decode(&*msg.v_protobuf).unwrap();
-                            self.handler
-                                .handle(QEvent::GroupLeave(GroupLeaveEvent {
+                                self.handle_event(QEvent::NewFriend(NewFriendEvent {
                                     client: self.clone(),
-                                    inner: GroupLeave {
-                                        group_code: d4.uin,
-                                        member_uin: self.uin().await,
-                                        operator_uin: None,
-                                    },
+                                    inner: Arc::new(FriendInfo {
+                                        uin: f.uin,
+                                        nick: f.nick,
+                                        ..Default::default()
+                                    }),
                                 }))
                                 .await;
+                            }
                         }
-                        0x122 | 0x123 => {
-                            let t =
-                                pb::notify::GeneralGrayTipInfo::decode(&*msg.v_protobuf).unwrap();
-                            let mut sender: i64 = 0;
-                            let mut receiver: i64 = 0;
+                        0xD4 if self.subscribes(&["GroupLeave"]) => {
+                            let d4 = decode_or_continue!(
+                                self,
+                                pb::SubD4::decode(&*msg.v_protobuf),
+                                payload
+                            );
+                            self.handle_event(QEvent::GroupLeave(GroupLeaveEvent {
+                                client: self.clone(),
+                                inner: Arc::new(GroupLeave {
+                                    group_code: d4.uin,
+                                    member_uin: self.uin().await,
+                                    operator_uin: None,
+                                }),
+                            }))
+                            .await;
+                        }
+                        0x122 | 0x123 if self.subscribes(&["FriendPoke", "ServerNotice"]) => {
+                            let t = decode_or_continue!(
+                                self,
+                                pb::notify::GeneralGrayTipInfo::decode(&*msg.v_protobuf),
+                                payload
+                            );
+                            let notice = parse_server_notice(0, &t);
+                            let mut poke = FriendPoke {
+                                item_id: t.templ_id,
+                                ..Default::default()
+                            };
                             for templ in t.msg_templ_param {
-                                if templ.name == "uin_str1" {
-                                    sender = templ.value.parse().unwrap_or_default()
-                                } else if templ.name == "uin_str2" {
-                                    receiver = templ.value.parse().unwrap_or_default()
+                                match &*templ.name {
+                                    "uin_str1" => {
+                                        poke.sender = templ.value.parse().unwrap_or_default()
+                                    }
+                                    "uin_str2" => {
+                                        poke.receiver = templ.value.parse().unwrap_or_default()
+                                    }
+                                    "action_str" | "alt_str1" if poke.action.is_empty() => {
+                                        poke.action = templ.value
+                                    }
+                                    "suffix_str" => poke.suffix = templ.value,
+                                    _ => {}
                                 }
                             }
-                            if sender != 0 {
-                                self.handler
-                                    .handle(QEvent::FriendPoke(FriendPokeEvent {
-                                        client: self.clone(),
-                                        inner: FriendPoke { sender, receiver },
-                                    }))
-                                    .await;
+                            if poke.sender != 0 {
+                                if !self.subscribes(&["FriendPoke"]) {
+                                    continue;
+                                }
+                                self.handle_event(QEvent::FriendPoke(FriendPokeEvent {
+                                    client: self.clone(),
+                                    inner: Arc::new(poke),
+                                }))
+                                .await;
+                            } else if notice.kind != ServerNoticeKind::Other
+                                || !notice.content.is_empty()
+                            {
+                                self.handle_server_notice(notice).await;
                             }
                         }
-                        0x27 => {
-                            let s27 =
-                                pb::msgtype0x210::SubMsg0x27Body::decode(&*msg.v_protobuf).unwrap();
+                        0x27 if self.subscribes(&["GroupNameUpdate", "DeleteFriend"]) => {
+                            let s27 = decode_or_continue!(
+                                self,
+                                pb::msgtype0x210::SubMsg0x27Body::decode(&*msg.v_protobuf),
+                                payload
+                            );
                             for mod_info in s27.mod_infos {
                                 if let Some(mod_group_profile) = mod_info.mod_group_profile {
                                     for profile_info in mod_group_profile.group_profile_infos {
                                         if profile_info.field.unwrap_or_default() != 1 {
                                             continue;
                                         }
-                                        self.handler
-                                            .handle(QEvent::GroupNameUpdate(GroupNameUpdateEvent {
+                                        self.handle_event(QEvent::GroupNameUpdate(
+                                            GroupNameUpdateEvent {
                                                 client: self.clone(),
-                                                inner: GroupNameUpdate {
+                                                inner: Arc::new(GroupNameUpdate {
                                                     group_code: mod_group_profile
                                                         .group_code
                                                         .unwrap_or_default()
@@ -325,19 +469,21 @@ impl Client {
                                                         profile_info.value(),
                                                     )
                                                     .into_owned(),
-                                                },
-                                            }))
-                                            .await;
+                                                }),
+                                            },
+                                        ))
+                                        .await;
                                     }
                                 }
                                 if let Some(del_friend) = mod_info.del_friend {
                                     for uin in del_friend.uins {
-                                        self.handler
-                                            .handle(QEvent::DeleteFriend(DeleteFriendEvent {
+                                        self.handle_event(QEvent::DeleteFriend(
+                                            DeleteFriendEvent {
                                                 client: self.clone(),
-                                                inner: DeleteFriend { uin: uin as i64 },
-                                            }))
-                                            .await;
+                                                inner: Arc::new(DeleteFriend { uin: uin as i64 }),
+                                            },
+                                        ))
+                                        .await;
                                     }
                                 }
                             }
@@ -378,30 +524,27 @@ impl Client {
         }
         match push_trans.info {
             PushTransInfo::MemberLeave(leave) => {
-                self.handler
-                    .handle(QEvent::GroupLeave(GroupLeaveEvent {
-                        client: self.clone(),
-                        inner: leave,
-                    }))
-                    .await;
+                self.handle_event(QEvent::GroupLeave(GroupLeaveEvent {
+                    client: self.clone(),
+                    inner: Arc::new(leave),
+                }))
+                .await;
             }
             PushTransInfo::MemberPermissionChange(change) => {
-                self.handler
-                    .handle(QEvent::MemberPermissionChange(
-                        MemberPermissionChangeEvent {
-                            client: self.clone(),
-                            inner: change,
-                        },
-                    ))
-                    .await;
+                self.handle_event(QEvent::MemberPermissionChange(
+                    MemberPermissionChangeEvent {
+                        client: self.clone(),
+                        inner: Arc::new(change),
+                    },
+                ))
+                .await;
             }
             PushTransInfo::GroupDisband(disband) => {
-                self.handler
-                    .handle(QEvent::GroupDisband(GroupDisbandEvent {
-                        client: self.clone(),
-                        inner: disband,
-                    }))
-                    .await;
+                self.handle_event(QEvent::GroupDisband(GroupDisbandEvent {
+                    client: self.clone(),
+                    inner: Arc::new(disband),
+                }))
+                .await;
             }
         }
     }
@@ -438,14 +581,21 @@ impl Client {
         );
         let _ = self.send(req).await?;
         if let Some(msg) = push.msg {
-            self.process_message_sync(vec![msg]).await;
+            self.process_message_sync(vec![msg], false).await;
         }
         Ok(())
     }
 
+    pub(crate) async fn handle_server_notice(self: &Arc<Self>, notice: ServerNotice) {
+        self.handle_event(QEvent::ServerNotice(ServerNoticeEvent {
+            client: self.clone(),
+            inner: Arc::new(notice),
+        }))
+        .await;
+    }
+
     pub(crate) async fn process_sid_ticket_expired(self: &Arc<Self>, seq: i32) -> RQResult<()> {
-        self.request_change_sig(Some(3554528)).await?;
-        self.register_client().await?;
+        self.refresh_sig().await?;
         self.send_sid_ticket_expired_response(seq).await?;
         Ok(())
     }
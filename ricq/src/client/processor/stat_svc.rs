@@ -16,11 +16,10 @@ impl Client {
             .await
             .ok();
         self.stop(NetworkStatus::MsfOffline);
-        self.handler
-            .handle(QEvent::MSFOffline(MSFOfflineEvent {
-                client: self.clone(),
-                inner: offline,
-            }))
-            .await;
+        self.handle_event(QEvent::MSFOffline(MSFOfflineEvent {
+            client: self.clone(),
+            inner: Arc::new(offline),
+        }))
+        .await;
     }
 }
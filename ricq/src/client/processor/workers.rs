@@ -0,0 +1,214 @@
+use std::collections::hash_map::DefaultHasher;
+use std::hash::{Hash, Hasher};
+use std::sync::{Arc, Weak};
+
+use futures_util::future::BoxFuture;
+use futures_util::FutureExt;
+use tokio::sync::mpsc;
+
+use ricq_core::command::online_push::decoder::{peek_c2c_uins, peek_group_code};
+use ricq_core::protocol::packet::Packet;
+
+use crate::handler::QEvent;
+use crate::Client;
+
+type Job = BoxFuture<'static, ()>;
+
+tokio::task_local! {
+    /// 当前 worker 任务的顺序 key，解析过程中产生的事件按这个 key 交给 handler
+    static ORDERING_KEY: u64;
+}
+
+/// 推送包处理任务池
+///
+/// 同一个会话（同一个群，或同一个好友 / 临时会话）的任务交给同一个 worker 按顺序处理，
+/// 不同会话并发处理。workers 为 0 时每个任务单独 spawn，不保证顺序。
+///
+/// 解析产生的事件交给另一组 handler worker，同一会话的事件仍然按顺序处理，
+/// 但 handler 不会阻塞推送包的解析：handler 中发送消息等待自身消息的推送时不会死锁，
+/// 慢的 handler 也不会拖住心跳、回执等其他推送。
+pub(crate) struct PacketWorkers {
+    /// 由 Client 持有，这里不能持有 Arc，否则 Client 不会被释放
+    client: Weak<Client>,
+    senders: Vec<mpsc::UnboundedSender<Job>>,
+    handlers: Vec<mpsc::UnboundedSender<Job>>,
+}
+
+impl PacketWorkers {
+    pub(crate) fn new(client: &Arc<Client>, workers: usize) -> Self {
+        Self::with_client(Arc::downgrade(client), workers)
+    }
+
+    fn with_client(client: Weak<Client>, workers: usize) -> Self {
+        Self {
+            client,
+            senders: spawn_workers(workers),
+            handlers: spawn_workers(workers),
+        }
+    }
+
+    /// 解析并处理推送包
+    pub(crate) fn dispatch(&self, pkt: Packet) {
+        let client = match self.client.upgrade() {
+            Some(client) => client,
+            None => return,
+        };
+        let key = ordering_key(&pkt);
+        self.run(
+            key,
+            async move { client.dispatch_packet(pkt).await }.boxed(),
+        );
+    }
+
+    /// key 相同的任务按提交顺序执行
+    pub(crate) fn run(&self, key: u64, job: Job) {
+        submit(&self.senders, key, ORDERING_KEY.scope(key, job).boxed());
+    }
+
+    /// 把事件交给 handler worker，key 相同的事件按提交顺序处理
+    fn emit(&self, key: u64, event: QEvent) {
+        let client = match self.client.upgrade() {
+            Some(client) => client,
+            None => return,
+        };
+        submit(
+            &self.handlers,
+            key,
+            async move { client.handler.handle(event).await }.boxed(),
+        );
+    }
+}
+
+fn spawn_workers(workers: usize) -> Vec<mpsc::UnboundedSender<Job>> {
+    (0..workers)
+        .map(|_| {
+            let (tx, mut rx) = mpsc::unbounded_channel::<Job>();
+            // Client 释放时 sender 随之释放，处理完剩余的任务后退出
+            tokio::spawn(async move {
+                while let Some(job) = rx.recv().await {
+                    job.await;
+                }
+            });
+            tx
+        })
+        .collect()
+}
+
+fn submit(senders: &[mpsc::UnboundedSender<Job>], key: u64, job: Job) {
+    if senders.is_empty() {
+        tokio::spawn(job);
+        return;
+    }
+    let index = key as usize % senders.len();
+    if senders[index].send(job).is_err() {
+        tracing::warn!("packet worker closed, drop job");
+    }
+}
+
+impl Client {
+    /// 上报事件，在 worker 中解析时交给同一会话的 handler worker，不等待 handler 处理完成
+    pub(crate) async fn handle_event(&self, event: QEvent) {
+        if let Ok(key) = ORDERING_KEY.try_with(|key| *key) {
+            let workers = self.packet_workers.lock().unwrap().clone();
+            if let Some(workers) = workers {
+                workers.emit(key, event);
+                return;
+            }
+        }
+        self.handler.handle(event).await;
+    }
+
+    /// 所有推送包和同步消息共用的任务池，第一次使用时创建
+    pub(crate) fn packet_workers(self: &Arc<Self>) -> Arc<PacketWorkers> {
+        self.packet_workers
+            .lock()
+            .unwrap()
+            .get_or_insert_with(|| Arc::new(PacketWorkers::new(self, self.options.packet_workers)))
+            .clone()
+    }
+}
+
+/// 群消息的顺序 key
+pub(crate) fn group_key(group_code: i64) -> u64 {
+    let mut hasher = DefaultHasher::new();
+    ("group", group_code).hash(&mut hasher);
+    hasher.finish()
+}
+
+/// 私聊会话的顺序 key，与消息方向无关
+pub(crate) fn conversation_key(from_uin: i64, to_uin: i64) -> u64 {
+    let mut hasher = DefaultHasher::new();
+    ("c2c", from_uin.min(to_uin), from_uin.max(to_uin)).hash(&mut hasher);
+    hasher.finish()
+}
+
+/// 需要保证顺序的包返回相同的 key
+fn ordering_key(pkt: &Packet) -> u64 {
+    match pkt.command_name.as_str() {
+        "OnlinePush.PbPushGroupMsg" => {
+            if let Some(group_code) = peek_group_code(&pkt.body) {
+                return group_key(group_code);
+            }
+        }
+        "OnlinePush.PbC2CMsgSync" => {
+            if let Some((from_uin, to_uin)) = peek_c2c_uins(&pkt.body) {
+                return conversation_key(from_uin, to_uin);
+            }
+        }
+        _ => {}
+    }
+    let mut hasher = DefaultHasher::new();
+    pkt.command_name.hash(&mut hasher);
+    hasher.finish()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_conversation_key() {
+        // 收到和发出的消息在同一个会话
+        assert_eq!(conversation_key(1, 2), conversation_key(2, 1));
+        assert_ne!(conversation_key(1, 2), conversation_key(1, 3));
+        assert_ne!(group_key(1), conversation_key(1, 1));
+    }
+
+    #[tokio::test]
+    async fn test_handler_does_not_block_packets() {
+        let workers = PacketWorkers::with_client(Weak::new(), 2);
+        let key = group_key(1);
+        // handler 等待同一个群后面的推送，例如回复消息后等待自身消息的回执
+        let (echo_tx, echo_rx) = tokio::sync::oneshot::channel::<()>();
+        let (done_tx, done_rx) = tokio::sync::oneshot::channel::<()>();
+        let (key_tx, key_rx) = tokio::sync::oneshot::channel();
+        workers.run(
+            key,
+            async move {
+                key_tx.send(ORDERING_KEY.try_with(|k| *k).ok()).ok();
+            }
+            .boxed(),
+        );
+        assert_eq!(key_rx.await.unwrap(), Some(key));
+        submit(
+            &workers.handlers,
+            key,
+            async move {
+                echo_rx.await.ok();
+                done_tx.send(()).ok();
+            }
+            .boxed(),
+        );
+        workers.run(
+            key,
+            async move {
+                echo_tx.send(()).ok();
+            }
+            .boxed(),
+        );
+        tokio::time::timeout(std::time::Duration::from_secs(1), done_rx)
+            .await
+            .expect("handler blocked the packet worker")
+            .unwrap();
+    }
+}
@@ -1,3 +1,4 @@
+use crate::client::event::{LoginProgress, LoginStage};
 use crate::handler::QEvent;
 use crate::Client;
 use ricq_core::command::wtlogin::*;
@@ -16,7 +17,19 @@ impl Client {
             .write()
             .await
             .process_login_response(login_response);
-        self.handler.handle(QEvent::Login(self.uin().await)).await;
+        if let Some(stage) = LoginStage::from_response(login_response) {
+            self.report_login_progress(stage).await;
+        }
+        self.handle_event(QEvent::Login(self.uin().await)).await;
+    }
+
+    pub(crate) async fn report_login_progress(&self, stage: LoginStage) {
+        if !self.subscribes(&["LoginProgress"]) {
+            return;
+        }
+        let uin = self.uin().await;
+        self.handle_event(QEvent::LoginProgress(LoginProgress { uin, stage }))
+            .await;
     }
 
     pub(crate) async fn process_trans_emp_response(&self, qrcode_state: &QRCodeState) {
@@ -8,10 +8,20 @@ pub async fn get_qimei<RNG: RngCore + CryptoRng>(
     rng: &mut RNG,
     device: &Device,
     version: &Version,
+) -> RQResult<Qimei> {
+    get_qimei_with_client(&reqwest::Client::new(), rng, device, version).await
+}
+
+/// 使用指定的 http client 获取 qimei，如 `Client::http_client`
+pub async fn get_qimei_with_client<RNG: RngCore + CryptoRng>(
+    http: &reqwest::Client,
+    rng: &mut RNG,
+    device: &Device,
+    version: &Version,
 ) -> RQResult<Qimei> {
     let crypt_key = "0123456789abcdef".as_bytes();
-    let req = QimeiRequest::new(rng, device, version, crypt_key).unwrap();
-    let resp: QimeiResponse = reqwest::Client::new()
+    let req = QimeiRequest::new(rng, device, version, crypt_key)?;
+    let resp: QimeiResponse = http
         .post("https://snowflake.qq.com/ola/android")
         .json(&req)
         .send()
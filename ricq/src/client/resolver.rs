@@ -0,0 +1,72 @@
+use std::io;
+use std::net::SocketAddr;
+use std::sync::Arc;
+
+use async_trait::async_trait;
+
+use crate::Client;
+
+/// 域名解析，用于服务器地址、web 接口、文件下载等
+///
+/// 默认使用系统 DNS，可以实现为 DoH 等，通过 `Client::set_resolver` 设置
+#[async_trait]
+pub trait Resolver: Send + Sync {
+    async fn resolve(&self, host: &str, port: u16) -> io::Result<Vec<SocketAddr>>;
+}
+
+/// 系统 DNS
+pub struct SystemResolver;
+
+#[async_trait]
+impl Resolver for SystemResolver {
+    async fn resolve(&self, host: &str, port: u16) -> io::Result<Vec<SocketAddr>> {
+        Ok(tokio::net::lookup_host((host, port)).await?.collect())
+    }
+}
+
+impl Client {
+    /// 设置域名解析，为 None 时使用系统 DNS
+    pub async fn set_resolver(&self, resolver: Option<Arc<dyn Resolver>>) {
+        let http = build_http_client(resolver.clone());
+        self.qsign_client.set_http_client(http.clone());
+        *self.http.write().await = http;
+        *self.resolver.write().await = resolver;
+    }
+
+    /// 解析域名
+    pub async fn resolve_host(&self, host: &str, port: u16) -> io::Result<Vec<SocketAddr>> {
+        match self.resolver.read().await.as_ref() {
+            Some(resolver) => resolver.resolve(host, port).await,
+            None => SystemResolver.resolve(host, port).await,
+        }
+    }
+
+    /// web 接口、文件下载等使用的 http client，设置了 resolver 时由它解析域名
+    pub async fn http_client(&self) -> reqwest::Client {
+        self.http.read().await.clone()
+    }
+}
+
+/// 把 Resolver 交给 reqwest 使用，端口由 reqwest 按 url 设置
+struct ReqwestResolver(Arc<dyn Resolver>);
+
+impl reqwest::dns::Resolve for ReqwestResolver {
+    fn resolve(&self, name: reqwest::dns::Name) -> reqwest::dns::Resolving {
+        let resolver = self.0.clone();
+        Box::pin(async move {
+            let addrs = resolver.resolve(name.as_str(), 0).await?;
+            let addrs: reqwest::dns::Addrs = Box::new(addrs.into_iter());
+            Ok::<_, Box<dyn std::error::Error + Send + Sync>>(addrs)
+        })
+    }
+}
+
+/// 创建 http client，resolver 为 None 时使用系统 DNS
+pub(crate) fn build_http_client(resolver: Option<Arc<dyn Resolver>>) -> reqwest::Client {
+    let builder = reqwest::Client::builder();
+    let builder = match resolver {
+        Some(resolver) => builder.dns_resolver(Arc::new(ReqwestResolver(resolver))),
+        None => builder,
+    };
+    builder.build().unwrap_or_default()
+}
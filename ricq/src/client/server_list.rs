@@ -0,0 +1,142 @@
+use std::net::{IpAddr, SocketAddr};
+use std::time::{Duration, Instant};
+
+use tokio::io;
+use tokio::net::TcpStream;
+
+use ricq_core::command::config_push_svc::SSO_SERVER_LIST_URL;
+use ricq_core::jce::SsoServerInfo;
+
+use crate::client::tcp::{measure_addrs, tcp_connect_fastest};
+use crate::{Client, RQError, RQResult};
+
+/// 测速结果的有效期，过期后 connect_fastest 重新测速
+const LATENCY_TTL: Duration = Duration::from_secs(30 * 60);
+/// 测速和连接的超时时间
+const CONNECT_TIMEOUT: Duration = Duration::from_secs(5);
+/// connect_fastest 同时尝试的地址数
+const RACE_COUNT: usize = 3;
+/// 获取服务器列表的超时时间
+const FETCH_TIMEOUT: Duration = Duration::from_secs(10);
+/// 获取服务器列表失败后，这段时间内重连不再重新获取
+const FETCH_RETRY_INTERVAL: Duration = Duration::from_secs(10 * 60);
+
+pub(crate) struct ServerLatency {
+    measured_at: Instant,
+    /// 按延迟从低到高排序，不含连接失败的地址
+    servers: Vec<(SocketAddr, Duration)>,
+}
+
+impl Client {
+    /// 通过 HTTP 获取 SSO 服务器列表，之后 get_address_list 优先返回这些地址
+    pub async fn fetch_sso_servers(&self) -> RQResult<Vec<SocketAddr>> {
+        let body = self.engine.read().await.build_sso_server_list_request();
+        let rsp = self
+            .http_client()
+            .await
+            .post(SSO_SERVER_LIST_URL)
+            .timeout(FETCH_TIMEOUT)
+            .body(body)
+            .send()
+            .await
+            .map_err(|e| RQError::Other(e.to_string()))?
+            .bytes()
+            .await
+            .map_err(|e| RQError::Other(e.to_string()))?;
+        let servers = self
+            .engine
+            .read()
+            .await
+            .decode_sso_server_list_response(&rsp)?;
+        let addrs = self.resolve_sso_servers(servers).await;
+        self.set_sso_servers(addrs.clone()).await;
+        Ok(addrs)
+    }
+
+    /// 更新服务器下发的地址，清除测速结果
+    pub(crate) async fn set_sso_servers(&self, addrs: Vec<SocketAddr>) {
+        *self.sso_servers.write().await = addrs;
+        *self.server_latency.write().await = None;
+    }
+
+    /// 解析服务器列表中的域名，忽略解析失败的地址
+    pub(crate) async fn resolve_sso_servers(&self, servers: Vec<SsoServerInfo>) -> Vec<SocketAddr> {
+        let mut addrs: Vec<SocketAddr> = Vec::new();
+        for server in servers {
+            let port = match u16::try_from(server.port) {
+                Ok(port) => port,
+                Err(_) => continue,
+            };
+            let resolved = match server.server.parse::<IpAddr>() {
+                Ok(ip) => vec![SocketAddr::new(ip, port)],
+                Err(_) => self
+                    .resolve_host(&server.server, port)
+                    .await
+                    .unwrap_or_default(),
+            };
+            for addr in resolved {
+                if !addrs.contains(&addr) {
+                    addrs.push(addr);
+                }
+            }
+        }
+        addrs
+    }
+
+    /// 对 get_address_list 的所有地址测速，返回按延迟排序的结果并缓存
+    ///
+    /// 还没有获取过服务器列表时先调用 fetch_sso_servers，获取失败后一段时间内不再重试
+    pub async fn measure_servers(&self) -> Vec<(SocketAddr, Duration)> {
+        let recently_failed = self
+            .sso_fetch_failed_at
+            .read()
+            .await
+            .is_some_and(|t| t.elapsed() < FETCH_RETRY_INTERVAL);
+        if self.sso_servers.read().await.is_empty() && !recently_failed {
+            match self.fetch_sso_servers().await {
+                Ok(_) => *self.sso_fetch_failed_at.write().await = None,
+                Err(err) => {
+                    tracing::warn!("failed to fetch sso servers: {}", err);
+                    *self.sso_fetch_failed_at.write().await = Some(Instant::now());
+                }
+            }
+        }
+        let servers = measure_addrs(self.get_address_list().await, CONNECT_TIMEOUT).await;
+        *self.server_latency.write().await = Some(ServerLatency {
+            measured_at: Instant::now(),
+            servers: servers.clone(),
+        });
+        servers
+    }
+
+    /// 缓存的测速结果，没有测速或已过期时返回 None
+    pub async fn server_latencies(&self) -> Option<Vec<(SocketAddr, Duration)>> {
+        self.server_latency
+            .read()
+            .await
+            .as_ref()
+            .filter(|l| l.measured_at.elapsed() < LATENCY_TTL)
+            .map(|l| l.servers.clone())
+    }
+
+    /// 连接延迟最低的服务器，没有可用的测速结果时先测速
+    pub async fn connect_fastest(&self) -> io::Result<TcpStream> {
+        let servers = match self.server_latencies().await {
+            Some(servers) if !servers.is_empty() => servers,
+            _ => self.measure_servers().await,
+        };
+        let fastest: Vec<SocketAddr> = servers
+            .iter()
+            .take(RACE_COUNT)
+            .map(|(addr, _)| *addr)
+            .collect();
+        if !fastest.is_empty() {
+            if let Ok(stream) = tcp_connect_fastest(fastest, CONNECT_TIMEOUT).await {
+                return Ok(stream);
+            }
+            // 测速结果中的地址都不可用，下次重新测速
+            *self.server_latency.write().await = None;
+        }
+        tcp_connect_fastest(self.get_address_list().await, CONNECT_TIMEOUT).await
+    }
+}
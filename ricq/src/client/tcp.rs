@@ -1,5 +1,5 @@
 use std::net::SocketAddr;
-use std::time::Duration;
+use std::time::{Duration, Instant};
 
 use tokio::net::TcpStream;
 use tokio::task::JoinSet;
@@ -45,6 +45,23 @@ where
     ret
 }
 
+/// 测量连接延迟，返回按延迟排序的结果，不含连接失败的地址
+pub async fn measure_addrs(
+    addrs: Vec<SocketAddr>,
+    timeout: Duration,
+) -> Vec<(SocketAddr, Duration)> {
+    let start = Instant::now();
+    let mut join_set = race_addrs(addrs, timeout).await;
+    let mut ret = Vec::new();
+    // 按完成顺序返回，先完成的延迟更低
+    while let Some(result) = join_set.join_next().await {
+        if let Ok(Ok((addr, _))) = result {
+            ret.push((addr, start.elapsed()));
+        }
+    }
+    ret
+}
+
 pub async fn tcp_connect_fastest(
     addrs: Vec<SocketAddr>,
     timeout: Duration,
@@ -0,0 +1,131 @@
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+use crate::{Client, RQError, RQResult, SendRateLimit};
+
+/// 受频率限制的发送目标
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum SendTarget {
+    Group(i64),
+    Friend(i64),
+}
+
+/// 频率限制的处理结果，通过 `Client::set_throttle_callback` 通知
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Throttled {
+    /// 等待后发送
+    Delayed(Duration),
+    /// 需要等待的时间超过 `max_delay`，返回 `RQError::RateLimited`
+    Rejected(Duration),
+}
+
+pub type ThrottleCallback = Arc<dyn Fn(SendTarget, Throttled) + Send + Sync>;
+
+struct Bucket {
+    /// 可能为负数，表示已经预约了之后的令牌
+    tokens: f64,
+    updated: Instant,
+}
+
+/// 每个目标一个令牌桶
+#[derive(Default)]
+pub(crate) struct SendLimiter {
+    buckets: Mutex<HashMap<SendTarget, Bucket>>,
+}
+
+impl SendLimiter {
+    /// 取一个令牌，返回发送前需要等待的时间，超过 max_delay 时不取令牌并返回 Err
+    fn reserve(
+        &self,
+        target: SendTarget,
+        limit: &SendRateLimit,
+        now: Instant,
+    ) -> Result<Duration, Duration> {
+        let rate = limit.per_minute as f64 / 60.0;
+        let burst = limit.burst as f64;
+        let mut buckets = self.buckets.lock().unwrap();
+        let bucket = buckets.entry(target).or_insert(Bucket {
+            tokens: burst,
+            updated: now,
+        });
+        let elapsed = now.saturating_duration_since(bucket.updated).as_secs_f64();
+        bucket.tokens = (bucket.tokens + elapsed * rate).min(burst);
+        bucket.updated = now;
+        let wait = if bucket.tokens >= 1.0 {
+            Duration::ZERO
+        } else {
+            Duration::from_secs_f64((1.0 - bucket.tokens) / rate)
+        };
+        if wait > limit.max_delay {
+            return Err(wait);
+        }
+        bucket.tokens -= 1.0;
+        Ok(wait)
+    }
+}
+
+impl Client {
+    /// 设置频率限制回调，发送被延迟或拒绝时调用
+    pub async fn set_throttle_callback(&self, callback: Option<ThrottleCallback>) {
+        *self.throttle_callback.write().await = callback;
+    }
+
+    /// 按 `ClientOptions::send_rate_limit` 等待发送，超出限制时返回 `RQError::RateLimited`
+    pub(crate) async fn throttle_send(&self, target: SendTarget) -> RQResult<()> {
+        let limit = match self.options.send_rate_limit.as_ref() {
+            Some(limit) => limit,
+            None => return Ok(()),
+        };
+        let result = self.send_limiter.reserve(target, limit, Instant::now());
+        let throttled = match result {
+            Ok(wait) if wait.is_zero() => return Ok(()),
+            Ok(wait) => Throttled::Delayed(wait),
+            Err(wait) => Throttled::Rejected(wait),
+        };
+        if let Some(callback) = self.throttle_callback.read().await.as_ref() {
+            callback(target, throttled);
+        }
+        match throttled {
+            Throttled::Delayed(wait) => {
+                tokio::time::sleep(wait).await;
+                Ok(())
+            }
+            Throttled::Rejected(wait) => Err(RQError::RateLimited(wait)),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_send_limiter() {
+        let limiter = SendLimiter::default();
+        let limit = SendRateLimit {
+            per_minute: 60,
+            burst: 2,
+            max_delay: Duration::from_secs(1),
+        };
+        let group = SendTarget::Group(1);
+        let now = Instant::now();
+        assert_eq!(limiter.reserve(group, &limit, now), Ok(Duration::ZERO));
+        assert_eq!(limiter.reserve(group, &limit, now), Ok(Duration::ZERO));
+        assert_eq!(
+            limiter.reserve(group, &limit, now),
+            Ok(Duration::from_secs(1))
+        );
+        assert_eq!(
+            limiter.reserve(group, &limit, now),
+            Err(Duration::from_secs(2))
+        );
+        // 其他群单独计数
+        assert_eq!(
+            limiter.reserve(SendTarget::Group(2), &limit, now),
+            Ok(Duration::ZERO)
+        );
+        let later = now + Duration::from_secs(2);
+        assert_eq!(limiter.reserve(group, &limit, later), Ok(Duration::ZERO));
+    }
+}
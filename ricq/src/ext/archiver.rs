@@ -0,0 +1,347 @@
+//! 消息归档，把所有消息事件写入 JSONL 文件（或开启 `archiver-sqlite` 后写入 SQLite）
+//!
+//! 每条记录为 [`ArchiveRecord`]，内容与 [`EventEnvelope`] 相同，另外带有事件类型和归档时间。
+//! 写入在单独的线程中进行，不阻塞事件分发；写入跟不上时丢弃新的记录并打印警告。
+
+use std::fs::{self, File, OpenOptions};
+use std::io::{self, BufWriter, Write};
+use std::path::{Path, PathBuf};
+use std::sync::mpsc;
+use std::time::{Duration, Instant, UNIX_EPOCH};
+
+use async_trait::async_trait;
+use serde::{Deserialize, Serialize};
+
+use crate::handler::{EventEnvelope, Handler, QEvent};
+
+/// 归档的事件类型
+pub const ARCHIVE_KINDS: [&str; 10] = [
+    "GroupMessage",
+    "GroupAudioMessage",
+    "FriendMessage",
+    "FriendAudioMessage",
+    "GroupTempMessage",
+    "TempMessage",
+    "SelfGroupMessage",
+    "SelfFriendMessage",
+    "GroupMessageRecall",
+    "FriendMessageRecall",
+];
+
+/// 默认等待写入的记录数
+pub const ARCHIVE_QUEUE_SIZE: usize = 4096;
+
+/// 归档记录
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ArchiveRecord {
+    /// 事件类型，见 `QEvent::kind`
+    pub kind: String,
+    /// 归档时的时间戳（秒）
+    pub archived_at: i64,
+    #[serde(flatten)]
+    pub envelope: EventEnvelope,
+}
+
+/// 归档写入目标，在归档线程中调用
+pub trait ArchiveSink: Send + 'static {
+    fn write(&mut self, record: &ArchiveRecord) -> io::Result<()>;
+    fn flush(&mut self) -> io::Result<()>;
+}
+
+/// 文件轮转条件，都为 None 时不轮转
+#[derive(Debug, Clone, Default)]
+pub struct Rotation {
+    /// 单个文件超过该大小后写入新文件
+    pub max_bytes: Option<u64>,
+    /// 单个文件写入超过该时间后写入新文件
+    pub max_age: Option<Duration>,
+}
+
+impl Rotation {
+    pub fn daily() -> Self {
+        Self {
+            max_bytes: None,
+            max_age: Some(Duration::from_secs(24 * 60 * 60)),
+        }
+    }
+}
+
+/// 按 `{prefix}-{时间戳}.{ext}` 生成文件名，并判断是否需要轮转
+struct RotatingPath {
+    dir: PathBuf,
+    prefix: String,
+    ext: &'static str,
+    rotation: Rotation,
+    opened_at: Instant,
+    written: u64,
+}
+
+impl RotatingPath {
+    fn new(dir: &Path, prefix: &str, ext: &'static str, rotation: Rotation) -> io::Result<Self> {
+        fs::create_dir_all(dir)?;
+        Ok(Self {
+            dir: dir.to_path_buf(),
+            prefix: prefix.to_string(),
+            ext,
+            rotation,
+            opened_at: Instant::now(),
+            written: 0,
+        })
+    }
+
+    fn next_path(&mut self) -> PathBuf {
+        self.opened_at = Instant::now();
+        self.written = 0;
+        let secs = UNIX_EPOCH.elapsed().unwrap_or_default().as_secs();
+        let mut path = self
+            .dir
+            .join(format!("{}-{}.{}", self.prefix, secs, self.ext));
+        // 同一秒内多次轮转
+        let mut n = 1;
+        while path.exists() {
+            path = self
+                .dir
+                .join(format!("{}-{}-{}.{}", self.prefix, secs, n, self.ext));
+            n += 1;
+        }
+        path
+    }
+
+    fn should_rotate(&self) -> bool {
+        self.rotation
+            .max_bytes
+            .is_some_and(|max| self.written >= max)
+            || self
+                .rotation
+                .max_age
+                .is_some_and(|max| self.opened_at.elapsed() >= max)
+    }
+}
+
+/// 每行一条 JSON 记录
+pub struct JsonlSink {
+    path: RotatingPath,
+    file: BufWriter<File>,
+}
+
+impl JsonlSink {
+    /// 在 dir 下创建 `{prefix}-{时间戳}.jsonl`
+    pub fn new(dir: impl AsRef<Path>, prefix: &str, rotation: Rotation) -> io::Result<Self> {
+        let mut path = RotatingPath::new(dir.as_ref(), prefix, "jsonl", rotation)?;
+        let file = open_append(&path.next_path())?;
+        Ok(Self { path, file })
+    }
+}
+
+fn open_append(path: &Path) -> io::Result<BufWriter<File>> {
+    Ok(BufWriter::new(
+        OpenOptions::new().create(true).append(true).open(path)?,
+    ))
+}
+
+impl ArchiveSink for JsonlSink {
+    fn write(&mut self, record: &ArchiveRecord) -> io::Result<()> {
+        if self.path.should_rotate() {
+            self.file.flush()?;
+            self.file = open_append(&self.path.next_path())?;
+        }
+        let mut line = serde_json::to_vec(record)?;
+        line.push(b'\n');
+        self.file.write_all(&line)?;
+        self.path.written += line.len() as u64;
+        Ok(())
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        self.file.flush()
+    }
+}
+
+/// 写入 SQLite 的 messages 表，data 列为 JSON 格式的记录
+#[cfg(feature = "archiver-sqlite")]
+pub struct SqliteSink {
+    path: RotatingPath,
+    conn: rusqlite::Connection,
+}
+
+#[cfg(feature = "archiver-sqlite")]
+impl SqliteSink {
+    /// 在 dir 下创建 `{prefix}-{时间戳}.db`
+    pub fn new(dir: impl AsRef<Path>, prefix: &str, rotation: Rotation) -> io::Result<Self> {
+        let mut path = RotatingPath::new(dir.as_ref(), prefix, "db", rotation)?;
+        let conn = Self::open(&path.next_path())?;
+        Ok(Self { path, conn })
+    }
+
+    fn open(path: &Path) -> io::Result<rusqlite::Connection> {
+        let conn = rusqlite::Connection::open(path).map_err(io::Error::other)?;
+        conn.execute_batch(
+            "CREATE TABLE IF NOT EXISTS messages (
+                id INTEGER PRIMARY KEY AUTOINCREMENT,
+                self_uin INTEGER NOT NULL,
+                kind TEXT NOT NULL,
+                archived_at INTEGER NOT NULL,
+                data TEXT NOT NULL
+            );
+            CREATE INDEX IF NOT EXISTS messages_kind ON messages (kind, archived_at);",
+        )
+        .map_err(io::Error::other)?;
+        Ok(conn)
+    }
+}
+
+#[cfg(feature = "archiver-sqlite")]
+impl ArchiveSink for SqliteSink {
+    fn write(&mut self, record: &ArchiveRecord) -> io::Result<()> {
+        if self.path.should_rotate() {
+            self.conn = Self::open(&self.path.next_path())?;
+        }
+        let data = serde_json::to_string(record)?;
+        self.conn
+            .execute(
+                "INSERT INTO messages (self_uin, kind, archived_at, data) VALUES (?1, ?2, ?3, ?4)",
+                rusqlite::params![
+                    record.envelope.self_uin,
+                    record.kind,
+                    record.archived_at,
+                    data
+                ],
+            )
+            .map_err(io::Error::other)?;
+        self.path.written += data.len() as u64;
+        Ok(())
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        Ok(())
+    }
+}
+
+/// 归档所有消息事件的 Handler，可以通过 `HandlerChain` 等与其他 Handler 组合
+pub struct MessageArchiver {
+    sender: mpsc::SyncSender<ArchiveRecord>,
+}
+
+impl MessageArchiver {
+    /// 启动归档线程，Archiver 释放后线程写完剩余记录退出
+    pub fn new<S: ArchiveSink>(sink: S) -> Self {
+        Self::with_capacity(sink, ARCHIVE_QUEUE_SIZE)
+    }
+
+    /// 最多缓存 capacity 条等待写入的记录
+    pub fn with_capacity<S: ArchiveSink>(mut sink: S, capacity: usize) -> Self {
+        let (sender, receiver) = mpsc::sync_channel::<ArchiveRecord>(capacity);
+        std::thread::spawn(move || {
+            while let Ok(record) = receiver.recv() {
+                // 一次写入所有已到达的记录后再 flush
+                for record in std::iter::once(record).chain(receiver.try_iter()) {
+                    if let Err(err) = sink.write(&record) {
+                        tracing::warn!("failed to archive {} event: {}", record.kind, err);
+                    }
+                }
+                if let Err(err) = sink.flush() {
+                    tracing::warn!("failed to flush archive: {}", err);
+                }
+            }
+        });
+        Self { sender }
+    }
+}
+
+#[async_trait]
+impl Handler for MessageArchiver {
+    async fn handle(&self, event: QEvent) {
+        let kind = event.kind();
+        if !self.subscribes(kind) {
+            return;
+        }
+        let record = ArchiveRecord {
+            kind: kind.to_string(),
+            archived_at: UNIX_EPOCH.elapsed().unwrap_or_default().as_secs() as i64,
+            envelope: EventEnvelope::from_event(&event).await,
+        };
+        match self.sender.try_send(record) {
+            Ok(()) => {}
+            Err(mpsc::TrySendError::Full(record)) => {
+                tracing::warn!("archive queue is full, dropped {} event", record.kind);
+            }
+            Err(mpsc::TrySendError::Disconnected(_)) => {}
+        }
+    }
+
+    fn subscribes(&self, kind: &str) -> bool {
+        ARCHIVE_KINDS.contains(&kind)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::handler::EventPayload;
+
+    fn record(uin: i64) -> ArchiveRecord {
+        ArchiveRecord {
+            kind: "Login".into(),
+            archived_at: 0,
+            envelope: EventEnvelope {
+                version: 1,
+                self_uin: uin,
+                event: EventPayload::Login(uin),
+            },
+        }
+    }
+
+    fn archive_files(dir: &Path) -> Vec<PathBuf> {
+        let mut files: Vec<PathBuf> = fs::read_dir(dir)
+            .unwrap()
+            .map(|e| e.unwrap().path())
+            .collect();
+        files.sort();
+        files
+    }
+
+    #[test]
+    fn test_jsonl_sink() {
+        let dir = std::env::temp_dir().join(format!("ricq-archive-{}", std::process::id()));
+        let mut sink = JsonlSink::new(&dir, "msg", Rotation::default()).unwrap();
+        sink.write(&record(1)).unwrap();
+        sink.write(&record(2)).unwrap();
+        sink.flush().unwrap();
+        let files = archive_files(&dir);
+        let content = fs::read_to_string(&files[0]).unwrap();
+        fs::remove_dir_all(&dir).ok();
+        assert_eq!(files.len(), 1);
+        let uins: Vec<i64> = content
+            .lines()
+            .map(|line| {
+                serde_json::from_str::<ArchiveRecord>(line)
+                    .unwrap()
+                    .envelope
+                    .self_uin
+            })
+            .collect();
+        assert_eq!(uins, vec![1, 2]);
+    }
+
+    #[test]
+    fn test_jsonl_rotation() {
+        let dir = std::env::temp_dir().join(format!("ricq-archive-rotate-{}", std::process::id()));
+        let rotation = Rotation {
+            max_bytes: Some(1),
+            max_age: None,
+        };
+        let mut sink = JsonlSink::new(&dir, "msg", rotation).unwrap();
+        for uin in 0..3 {
+            sink.write(&record(uin)).unwrap();
+        }
+        sink.flush().unwrap();
+        let files = archive_files(&dir);
+        let lines: Vec<usize> = files
+            .iter()
+            .map(|f| fs::read_to_string(f).unwrap().lines().count())
+            .collect();
+        fs::remove_dir_all(&dir).ok();
+        // 每个文件写入一条后超过 max_bytes，下一条写入新文件
+        assert_eq!(lines, vec![1, 1, 1]);
+    }
+}
@@ -0,0 +1,137 @@
+//! 语音格式转换，silk 与 PCM / WAV 互相转换
+//!
+//! PCM 均为 16 bit 小端单声道。
+//!
+//! 只支持 silk，不包含 amr 编解码器：收到的 amr 语音（旧版客户端发送）会返回错误，需要自行用 ffmpeg 等工具转换。
+
+use crate::{RQError, RQResult};
+
+/// QQ 语音常用的采样率
+pub const DEFAULT_SAMPLE_RATE: u32 = 24000;
+
+const SILK_BIT_RATE: i32 = 24000;
+const WAV_HEADER_LEN: usize = 44;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum AudioFormat {
+    Silk,
+    Amr,
+    Wav,
+    Unknown,
+}
+
+/// 根据文件头判断格式，QQ 的 silk 文件头前面有一个 0x02
+pub fn detect_format(data: &[u8]) -> AudioFormat {
+    if data.starts_with(b"\x02#!SILK_V3") || data.starts_with(b"#!SILK_V3") {
+        AudioFormat::Silk
+    } else if data.starts_with(b"#!AMR") {
+        AudioFormat::Amr
+    } else if data.len() >= 12 && data.starts_with(b"RIFF") && &data[8..12] == b"WAVE" {
+        AudioFormat::Wav
+    } else {
+        AudioFormat::Unknown
+    }
+}
+
+/// silk 解码为 PCM
+pub fn silk_to_pcm(data: &[u8], sample_rate: u32) -> RQResult<Vec<u8>> {
+    silk_rs::decode_silk(data, sample_rate as i32)
+        .map_err(|e| RQError::Other(format!("failed to decode silk: {e:?}")))
+}
+
+/// PCM 编码为 QQ 使用的 silk（带 0x02 前缀），可以直接上传
+pub fn pcm_to_silk(pcm: &[u8], sample_rate: u32) -> RQResult<Vec<u8>> {
+    silk_rs::encode_silk(pcm, sample_rate as i32, SILK_BIT_RATE, true)
+        .map_err(|e| RQError::Other(format!("failed to encode silk: {e:?}")))
+}
+
+/// PCM 加上 WAV 文件头
+pub fn pcm_to_wav(pcm: &[u8], sample_rate: u32) -> Vec<u8> {
+    let mut wav = Vec::with_capacity(WAV_HEADER_LEN + pcm.len());
+    wav.extend_from_slice(b"RIFF");
+    wav.extend_from_slice(&(36 + pcm.len() as u32).to_le_bytes());
+    wav.extend_from_slice(b"WAVEfmt ");
+    wav.extend_from_slice(&16u32.to_le_bytes());
+    wav.extend_from_slice(&1u16.to_le_bytes()); // PCM
+    wav.extend_from_slice(&1u16.to_le_bytes()); // 单声道
+    wav.extend_from_slice(&sample_rate.to_le_bytes());
+    wav.extend_from_slice(&(sample_rate * 2).to_le_bytes()); // byte rate
+    wav.extend_from_slice(&2u16.to_le_bytes()); // block align
+    wav.extend_from_slice(&16u16.to_le_bytes());
+    wav.extend_from_slice(b"data");
+    wav.extend_from_slice(&(pcm.len() as u32).to_le_bytes());
+    wav.extend_from_slice(pcm);
+    wav
+}
+
+/// 解析 WAV，返回 PCM 和采样率，只支持 16 bit 单声道
+pub fn wav_to_pcm(wav: &[u8]) -> RQResult<(Vec<u8>, u32)> {
+    if detect_format(wav) != AudioFormat::Wav {
+        return Err(RQError::Decode("not a wav file".into()));
+    }
+    let mut sample_rate = None;
+    let mut pos = 12;
+    while pos + 8 <= wav.len() {
+        let id = &wav[pos..pos + 4];
+        let len = u32::from_le_bytes(wav[pos + 4..pos + 8].try_into().unwrap()) as usize;
+        let body = &wav[pos + 8..wav.len().min(pos + 8 + len)];
+        match id {
+            b"fmt " if body.len() >= 16 => {
+                let format = u16::from_le_bytes([body[0], body[1]]);
+                let channels = u16::from_le_bytes([body[2], body[3]]);
+                let bits = u16::from_le_bytes([body[14], body[15]]);
+                if format != 1 || channels != 1 || bits != 16 {
+                    return Err(RQError::Decode(format!(
+                        "unsupported wav: format={format}, channels={channels}, bits={bits}"
+                    )));
+                }
+                sample_rate = Some(u32::from_le_bytes(body[4..8].try_into().unwrap()));
+            }
+            b"data" => {
+                let sample_rate = sample_rate.ok_or(RQError::EmptyField("fmt"))?;
+                return Ok((body.to_vec(), sample_rate));
+            }
+            _ => {}
+        }
+        // chunk 按 2 字节对齐
+        pos += 8 + len + (len & 1);
+    }
+    Err(RQError::EmptyField("data"))
+}
+
+/// 收到的语音转为 WAV，方便交给语音识别等工具，amr 格式返回错误
+pub fn audio_to_wav(data: &[u8]) -> RQResult<Vec<u8>> {
+    match detect_format(data) {
+        AudioFormat::Silk => Ok(pcm_to_wav(
+            &silk_to_pcm(data, DEFAULT_SAMPLE_RATE)?,
+            DEFAULT_SAMPLE_RATE,
+        )),
+        AudioFormat::Wav => Ok(data.to_vec()),
+        AudioFormat::Amr => Err(RQError::Other("amr audio is not supported".into())),
+        format => Err(RQError::Other(format!(
+            "unsupported audio format: {format:?}"
+        ))),
+    }
+}
+
+/// WAV 转为 silk，上传语音时使用 codec 1
+pub fn wav_to_silk(wav: &[u8]) -> RQResult<Vec<u8>> {
+    let (pcm, sample_rate) = wav_to_pcm(wav)?;
+    pcm_to_silk(&pcm, sample_rate)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_wav_round_trip() {
+        let pcm: Vec<u8> = (0..100u8).collect();
+        let wav = pcm_to_wav(&pcm, 16000);
+        assert_eq!(detect_format(&wav), AudioFormat::Wav);
+        assert_eq!(wav_to_pcm(&wav).unwrap(), (pcm, 16000));
+        assert_eq!(detect_format(b"\x02#!SILK_V3\x0c"), AudioFormat::Silk);
+        assert_eq!(detect_format(b"#!AMR\n"), AudioFormat::Amr);
+        assert!(audio_to_wav(b"#!AMR\n").is_err());
+    }
+}
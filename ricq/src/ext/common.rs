@@ -1,24 +1,79 @@
 use std::sync::atomic::Ordering;
 use std::sync::Arc;
+use std::time::Duration;
+
+use ricq_core::structs::Battery;
 
 use crate::Client;
 
+const MEMBER_DIFF_TICK: Duration = Duration::from_secs(30);
+
 /// 登录后必须执行的操作
 pub async fn after_login(client: &Arc<Client>) {
+    // 注册后服务器推送的 PushNotify 会先同步消息，需要在注册前标记按离线消息处理
+    client.offline_sync.arm(client.options.offline_messages);
     if let Err(err) = client.register_client().await {
         tracing::error!("failed to register client: {}", err)
     }
     start_heartbeat(client.clone()).await;
+    start_sig_refresh(client.clone());
     if let Err(err) = client.refresh_status().await {
         tracing::error!("failed to refresh status: {}", err)
     }
+    if client.options.offline_messages {
+        match client.sync_pending_offline_messages().await {
+            Ok(Some(count)) => tracing::info!("synced {} offline messages", count),
+            Ok(None) => {}
+            Err(err) => tracing::error!("failed to sync offline messages: {}", err),
+        }
+    }
 }
 
 /// 如果当前启动心跳，spawn 开始心跳
 pub async fn start_heartbeat(client: Arc<Client>) {
     if !client.heartbeat_enabled.load(Ordering::Relaxed) {
         tokio::spawn(async move {
-            client.do_heartbeat().await;
+            client.do_heartbeat_with_events().await;
+        });
+    }
+}
+
+/// 如果未启动，spawn 在 skey 过期前自动刷新登录凭证
+pub fn start_sig_refresh(client: Arc<Client>) {
+    if client.options.sig_refresh_ahead.is_some()
+        && !client.sig_refresh_enabled.load(Ordering::Relaxed)
+    {
+        tokio::spawn(async move {
+            client.do_refresh_sig().await;
         });
     }
 }
+
+/// 定时上报电量状态，level 返回当前电量百分比，掉线后停止
+pub fn start_battery_refresh<F>(client: Arc<Client>, interval: Duration, level: F)
+where
+    F: Fn() -> u8 + Send + 'static,
+{
+    tokio::spawn(async move {
+        while client.online.load(Ordering::SeqCst) {
+            if let Err(err) = client.set_online_status(Battery(level())).await {
+                tracing::error!("failed to refresh battery status: {}", err)
+            }
+            tokio::time::sleep(interval).await;
+        }
+    });
+}
+
+/// 定时比对 `Client::watch_group_members` 添加的群，间隔见 `MemberDiffOptions::interval`，掉线后停止
+pub fn start_member_diff(client: Arc<Client>) {
+    tokio::spawn(async move {
+        while client.online.load(Ordering::SeqCst) {
+            for group_code in client.member_diff_due_groups().await {
+                if let Err(err) = client.diff_group_members(group_code).await {
+                    tracing::warn!("failed to diff members of group {}: {}", group_code, err)
+                }
+            }
+            tokio::time::sleep(MEMBER_DIFF_TICK).await;
+        }
+    });
+}
@@ -1,11 +1,23 @@
 use std::sync::Arc;
 use std::time::Duration;
 
-use ricq_core::command::wtlogin::{LoginResponse, QRCodeConfirmed, QRCodeState};
-use ricq_core::{RQError, RQResult};
+use async_trait::async_trait;
+use serde::{Deserialize, Serialize};
+use tokio::io::{AsyncBufReadExt, BufReader};
 
+use ricq_core::command::wtlogin::{
+    LoginDeviceLocked, LoginNeedCaptcha, LoginResponse, QRCodeConfirmed, QRCodeState,
+};
+use ricq_core::{A1Token, RQError, RQResult};
+
+use crate::version::Protocol;
 use crate::Client;
 
+/// 连续提交 ticket 的最大次数，超过后返回错误
+const MAX_CAPTCHA_ATTEMPTS: usize = 3;
+/// 短信验证的最大次数
+const MAX_SMS_ATTEMPTS: usize = 3;
+
 /// 扫码登录：自动查询二维码状态，忽略中间结果，成功或失败返回
 pub async fn auto_query_qrcode(client: &Arc<Client>, sig: &[u8]) -> RQResult<()> {
     loop {
@@ -41,3 +53,238 @@ pub async fn auto_query_qrcode(client: &Arc<Client>, sig: &[u8]) -> RQResult<()>
         tokio::time::sleep(Duration::from_secs(4)).await;
     }
 }
+
+/// 手表协议扫码登录后切换到 target 协议
+///
+/// 只有手表协议（AndroidWatch）支持扫码登录，但手表协议功能较少。扫码登录成功后，
+/// 使用本次登录得到的 encrypted A1 以 target 协议换取新的登录凭证。
+/// 服务器不允许切换时恢复为手表协议的登录状态并返回错误，调用方可以继续使用手表协议。
+///
+/// 和 [`auto_query_qrcode`] 一样，成功后需要自行 register
+///
+/// ```ignore
+/// let client = Client::new(device, get_version(Protocol::AndroidWatch), qsign, handler);
+/// let QRCodeState::ImageFetch(fetch) = client.fetch_qrcode().await? else { unreachable!() };
+/// // 展示 fetch.image_data 给用户扫码
+/// qrcode_login_and_upgrade(&client, &fetch.sig, Protocol::AndroidPad).await?;
+/// after_login(&client).await;
+/// ```
+pub async fn qrcode_login_and_upgrade(
+    client: &Arc<Client>,
+    sig: &[u8],
+    target: Protocol,
+) -> RQResult<()> {
+    let watch = client.version().await;
+    if !matches!(watch.protocol, Protocol::AndroidWatch) {
+        return Err(RQError::Other(
+            "qrcode login requires AndroidWatch protocol".into(),
+        ));
+    }
+    auto_query_qrcode(client, sig).await?;
+    if matches!(target, Protocol::AndroidWatch) {
+        return Ok(());
+    }
+    let token = client.gen_token().await;
+    let a1 = client.gen_a1_token().await;
+    client.set_version(target.clone().into()).await;
+    let err = match client.fast_login(a1).await {
+        Ok(LoginResponse::Success(_)) => return Ok(()),
+        Ok(resp) => format!("upgrade to {target:?} rejected: {resp:?}"),
+        Err(err) => format!("upgrade to {target:?} failed: {err}"),
+    };
+    client.set_version(watch).await;
+    client.load_token(token).await;
+    Err(RQError::Other(err))
+}
+
+/// 处理滑块验证码，返回滑块完成后得到的 ticket
+#[async_trait]
+pub trait CaptchaResolver: Send + Sync {
+    async fn resolve(&self, verify_url: &str) -> RQResult<String>;
+}
+
+/// 在终端打印滑块链接，从标准输入读取 ticket
+pub struct ConsoleCaptchaResolver;
+
+#[async_trait]
+impl CaptchaResolver for ConsoleCaptchaResolver {
+    async fn resolve(&self, verify_url: &str) -> RQResult<String> {
+        println!("滑块URL: {verify_url}");
+        println!("请输入ticket:");
+        let mut line = String::new();
+        BufReader::new(tokio::io::stdin())
+            .read_line(&mut line)
+            .await?;
+        match line.trim() {
+            "" => Err(RQError::Other("empty ticket".into())),
+            ticket => Ok(ticket.into()),
+        }
+    }
+}
+
+#[derive(Serialize)]
+struct CaptchaRequest<'a> {
+    url: &'a str,
+}
+
+#[derive(Deserialize)]
+struct CaptchaResult {
+    ticket: String,
+}
+
+/// 把滑块链接 POST 到 HTTP 接口，由打码平台或浏览器桥接完成滑块
+///
+/// 请求体为 `{"url": "..."}`，接口在完成后返回 `{"ticket": "..."}`
+pub struct HttpCaptchaResolver {
+    pub endpoint: String,
+    /// 等待接口返回的时间，需要包含人工或打码平台处理的时间
+    pub timeout: Duration,
+    /// 请求使用的 http client，可以传入 `Client::http_client` 使用相同的域名解析
+    pub http: reqwest::Client,
+}
+
+impl HttpCaptchaResolver {
+    pub fn new(endpoint: impl Into<String>) -> Self {
+        Self {
+            endpoint: endpoint.into(),
+            timeout: Duration::from_secs(300),
+            http: reqwest::Client::new(),
+        }
+    }
+
+    pub fn with_http_client(mut self, http: reqwest::Client) -> Self {
+        self.http = http;
+        self
+    }
+}
+
+#[async_trait]
+impl CaptchaResolver for HttpCaptchaResolver {
+    async fn resolve(&self, verify_url: &str) -> RQResult<String> {
+        let result: CaptchaResult = self
+            .http
+            .post(&self.endpoint)
+            .timeout(self.timeout)
+            .json(&CaptchaRequest { url: verify_url })
+            .send()
+            .await
+            .and_then(|resp| resp.error_for_status())
+            .map_err(|e| RQError::Other(e.to_string()))?
+            .json()
+            .await
+            .map_err(|e| RQError::Decode(e.to_string()))?;
+        Ok(result.ticket)
+    }
+}
+
+/// 密码登录：遇到滑块时调用 resolver 并自动提交 ticket，设备锁等其他结果原样返回
+pub async fn password_login_with_captcha(
+    client: &Client,
+    uin: i64,
+    password: &str,
+    resolver: &dyn CaptchaResolver,
+) -> RQResult<LoginResponse> {
+    let resp = client.password_login(uin, password).await?;
+    resolve_captcha(client, resp, resolver).await
+}
+
+/// 处理登录结果中的滑块和设备锁登录，直到返回其他结果
+pub async fn resolve_captcha(
+    client: &Client,
+    mut resp: LoginResponse,
+    resolver: &dyn CaptchaResolver,
+) -> RQResult<LoginResponse> {
+    let mut attempts = 0;
+    loop {
+        resp = match resp {
+            LoginResponse::NeedCaptcha(LoginNeedCaptcha {
+                verify_url: Some(ref verify_url),
+                ..
+            }) => {
+                if attempts >= MAX_CAPTCHA_ATTEMPTS {
+                    return Err(RQError::Other("too many captcha attempts".into()));
+                }
+                attempts += 1;
+                let ticket = resolver.resolve(verify_url).await?;
+                client.submit_ticket(&ticket).await?
+            }
+            LoginResponse::DeviceLockLogin(_) => client.device_lock_login().await?,
+            other => return Ok(other),
+        };
+    }
+}
+
+/// 提供设备锁短信验证码
+#[async_trait]
+pub trait SmsCodeProvider: Send + Sync {
+    /// phone 为服务器返回的脱敏手机号
+    async fn sms_code(&self, phone: &str) -> RQResult<String>;
+}
+
+/// 从标准输入读取短信验证码
+pub struct ConsoleSmsCodeProvider;
+
+#[async_trait]
+impl SmsCodeProvider for ConsoleSmsCodeProvider {
+    async fn sms_code(&self, phone: &str) -> RQResult<String> {
+        println!("验证码已发送到 {phone}，请输入验证码:");
+        let mut line = String::new();
+        BufReader::new(tokio::io::stdin())
+            .read_line(&mut line)
+            .await?;
+        match line.trim() {
+            "" => Err(RQError::Other("empty sms code".into())),
+            code => Ok(code.into()),
+        }
+    }
+}
+
+/// 完成设备锁验证：DeviceLocked 时请求短信并提交验证码，DeviceLockLogin 时重新发送登录包
+///
+/// 没有绑定手机号的 DeviceLocked 需要打开 verify_url 验证，和其他结果一样原样返回
+pub async fn complete_device_lock_login(
+    client: &Client,
+    mut resp: LoginResponse,
+    sms: &dyn SmsCodeProvider,
+) -> RQResult<LoginResponse> {
+    let mut attempts = 0;
+    loop {
+        resp = match resp {
+            LoginResponse::DeviceLocked(LoginDeviceLocked {
+                sms_phone: Some(ref phone),
+                ..
+            }) => {
+                if attempts >= MAX_SMS_ATTEMPTS {
+                    return Err(RQError::Other("too many sms attempts".into()));
+                }
+                attempts += 1;
+                match client.request_sms().await? {
+                    // 短信已发送
+                    LoginResponse::DeviceLocked(_) => {}
+                    other => return Ok(other),
+                }
+                let code = sms.sms_code(phone).await?;
+                client.submit_sms_code(&code).await?
+            }
+            LoginResponse::DeviceLockLogin(_) => client.device_lock_login().await?,
+            other => return Ok(other),
+        };
+    }
+}
+
+/// 优先使用 A1Token 快速登录，没有凭证或凭证被拒绝时使用密码登录
+pub async fn fast_login_or_password(
+    client: &Client,
+    token: Option<A1Token>,
+    uin: i64,
+    password: &str,
+) -> RQResult<LoginResponse> {
+    if let Some(token) = token.filter(|t| t.uin == uin) {
+        match client.fast_login(token).await {
+            Ok(resp @ LoginResponse::Success(_)) => return Ok(resp),
+            Ok(resp) => tracing::info!("fast login rejected: {:?}, use password", resp),
+            Err(err) => tracing::info!("fast login failed: {}, use password", err),
+        }
+    }
+    client.password_login(uin, password).await
+}
@@ -1,4 +1,15 @@
+#[cfg(feature = "archiver")]
+pub mod archiver;
+#[cfg(feature = "audio-codec")]
+pub mod audio;
 pub mod common;
+pub mod connect;
 pub mod image;
 pub mod login;
+#[cfg(feature = "onebot")]
+pub mod onebot;
 pub mod reconnect;
+#[cfg(feature = "native-t544")]
+pub mod t544;
+#[cfg(feature = "webhook")]
+pub mod webhook;
@@ -0,0 +1,257 @@
+use std::sync::atomic::Ordering;
+
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Map, Value};
+
+use ricq_core::structs::GroupMemberInfo;
+
+use crate::{Client, RQError};
+
+use super::event::BotSelf;
+use super::message::{parse_id, segments_to_chain, MessageId, MessageKind, Segment};
+use super::{IMPL, ONEBOT_VERSION};
+
+// OneBot 12 返回码
+pub const BAD_REQUEST: i64 = 10001;
+pub const UNSUPPORTED_ACTION: i64 = 10002;
+pub const BAD_PARAM: i64 = 10003;
+pub const UNSUPPORTED_SEGMENT: i64 = 10005;
+/// 协议端调用 RICQ 出错
+pub const PLATFORM_ERROR: i64 = 34000;
+/// 目标不存在等业务错误
+pub const LOGIC_ERROR: i64 = 35000;
+
+const SUPPORTED_ACTIONS: &[&str] = &[
+    "get_supported_actions",
+    "get_status",
+    "get_version",
+    "get_self_info",
+    "send_message",
+    "delete_message",
+    "get_friend_list",
+    "get_group_info",
+    "get_group_list",
+    "get_group_member_info",
+    "get_group_member_list",
+];
+
+/// 动作请求
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ActionRequest {
+    pub action: String,
+    #[serde(default)]
+    pub params: Map<String, Value>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub echo: Option<Value>,
+}
+
+/// 动作响应
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ActionResponse {
+    pub status: String,
+    pub retcode: i64,
+    pub data: Value,
+    pub message: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub echo: Option<Value>,
+}
+
+impl ActionResponse {
+    pub fn ok(data: Value, echo: Option<Value>) -> Self {
+        Self {
+            status: "ok".into(),
+            retcode: 0,
+            data,
+            message: String::new(),
+            echo,
+        }
+    }
+
+    pub fn failed(error: ActionError, echo: Option<Value>) -> Self {
+        Self {
+            status: "failed".into(),
+            retcode: error.retcode,
+            data: Value::Null,
+            message: error.message,
+            echo,
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct ActionError {
+    pub retcode: i64,
+    pub message: String,
+}
+
+impl ActionError {
+    pub fn new(retcode: i64, message: String) -> Self {
+        Self { retcode, message }
+    }
+}
+
+impl From<RQError> for ActionError {
+    fn from(e: RQError) -> Self {
+        Self::new(PLATFORM_ERROR, e.to_string())
+    }
+}
+
+struct Params<'a>(&'a Map<String, Value>);
+
+impl Params<'_> {
+    fn str(&self, key: &str) -> Result<&str, ActionError> {
+        self.0
+            .get(key)
+            .and_then(Value::as_str)
+            .ok_or_else(|| ActionError::new(BAD_PARAM, format!("{key} required")))
+    }
+
+    fn id(&self, key: &str) -> Result<i64, ActionError> {
+        parse_id(self.0.get(key))
+            .ok_or_else(|| ActionError::new(BAD_PARAM, format!("{key} invalid")))
+    }
+
+    fn segments(&self, key: &str) -> Result<Vec<Segment>, ActionError> {
+        let value = self
+            .0
+            .get(key)
+            .ok_or_else(|| ActionError::new(BAD_PARAM, format!("{key} required")))?;
+        serde_json::from_value(value.clone())
+            .map_err(|e| ActionError::new(BAD_PARAM, format!("{key}: {e}")))
+    }
+}
+
+fn member_json(m: &GroupMemberInfo) -> Value {
+    json!({
+        "user_id": m.uin.to_string(),
+        "user_name": m.nickname,
+        "user_displayname": m.card_name,
+    })
+}
+
+/// 执行动作请求，错误转为对应的返回码
+pub async fn handle_action(client: &Client, req: ActionRequest) -> ActionResponse {
+    match do_action(client, &req.action, Params(&req.params)).await {
+        Ok(data) => ActionResponse::ok(data, req.echo),
+        Err(e) => ActionResponse::failed(e, req.echo),
+    }
+}
+
+async fn do_action(
+    client: &Client,
+    action: &str,
+    params: Params<'_>,
+) -> Result<Value, ActionError> {
+    let data = match action {
+        "get_supported_actions" => json!(SUPPORTED_ACTIONS),
+        "get_status" => {
+            let online = client.online.load(Ordering::SeqCst);
+            json!({
+                "good": online,
+                "bots": [{ "self": BotSelf::new(client.uin().await), "online": online }],
+            })
+        }
+        "get_version" => json!({
+            "impl": IMPL,
+            "version": env!("CARGO_PKG_VERSION"),
+            "onebot_version": ONEBOT_VERSION,
+        }),
+        "get_self_info" => json!({
+            "user_id": client.uin().await.to_string(),
+            "user_name": client.account_info.read().await.nickname,
+            "user_displayname": "",
+        }),
+        "send_message" => {
+            let chain = segments_to_chain(&params.segments("message")?)?;
+            let (kind, target, receipt) = match params.str("detail_type")? {
+                "private" => {
+                    let target = params.id("user_id")?;
+                    let receipt = client.send_friend_message(target, chain).await?;
+                    (MessageKind::Private, target, receipt)
+                }
+                "group" => {
+                    let target = params.id("group_id")?;
+                    let receipt = client.send_group_message(target, chain).await?;
+                    (MessageKind::Group, target, receipt)
+                }
+                detail_type => {
+                    return Err(ActionError::new(
+                        UNSUPPORTED_ACTION,
+                        format!("unsupported detail_type: {detail_type}"),
+                    ))
+                }
+            };
+            let message_id = MessageId {
+                kind,
+                target,
+                seq: receipt.seq(),
+                rand: receipt.rand(),
+                time: receipt.time,
+            };
+            json!({ "message_id": message_id.to_string(), "time": receipt.time })
+        }
+        "delete_message" => {
+            let id: MessageId = params.str("message_id")?.parse()?;
+            match id.kind {
+                MessageKind::Private => {
+                    client
+                        .recall_friend_message(id.target, id.time, vec![id.seq], vec![id.rand])
+                        .await?
+                }
+                MessageKind::Group => {
+                    client
+                        .recall_group_message(id.target, vec![id.seq], vec![id.rand])
+                        .await?
+                }
+            }
+            Value::Null
+        }
+        "get_friend_list" => {
+            let friends = client.get_friend_list().await?.friends;
+            friends
+                .iter()
+                .map(|f| {
+                    json!({
+                        "user_id": f.uin.to_string(),
+                        "user_name": f.nick,
+                        "user_displayname": "",
+                        "user_remark": f.remark,
+                    })
+                })
+                .collect()
+        }
+        "get_group_info" => {
+            let group_code = params.id("group_id")?;
+            let info = client
+                .get_public_group_info(group_code)
+                .await?
+                .ok_or_else(|| ActionError::new(LOGIC_ERROR, "group not found".into()))?;
+            json!({ "group_id": info.code.to_string(), "group_name": info.name })
+        }
+        "get_group_list" => client
+            .get_group_list()
+            .await?
+            .iter()
+            .map(|g| json!({ "group_id": g.code.to_string(), "group_name": g.name }))
+            .collect(),
+        "get_group_member_info" => {
+            let member = client
+                .get_group_member_info_cached(params.id("group_id")?, params.id("user_id")?)
+                .await?;
+            member_json(&member)
+        }
+        "get_group_member_list" => client
+            .get_group_members_cached(params.id("group_id")?)
+            .await?
+            .iter()
+            .map(member_json)
+            .collect(),
+        action => {
+            return Err(ActionError::new(
+                UNSUPPORTED_ACTION,
+                format!("unsupported action: {action}"),
+            ))
+        }
+    };
+    Ok(data)
+}
@@ -0,0 +1,250 @@
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Map, Value};
+
+use crate::handler::QEvent;
+
+use super::message::{chain_to_segments, MessageId, MessageKind};
+use super::PLATFORM;
+
+/// 事件中的机器人自身标识
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct BotSelf {
+    pub platform: String,
+    pub user_id: String,
+}
+
+impl BotSelf {
+    pub fn new(uin: i64) -> Self {
+        Self {
+            platform: PLATFORM.into(),
+            user_id: uin.to_string(),
+        }
+    }
+}
+
+/// OneBot 12 事件，各类型特有的字段放在 extra 中
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct OneBotEvent {
+    pub id: String,
+    pub time: f64,
+    #[serde(rename = "type")]
+    pub kind: String,
+    pub detail_type: String,
+    #[serde(default)]
+    pub sub_type: String,
+    #[serde(rename = "self")]
+    pub bot_self: BotSelf,
+    #[serde(flatten)]
+    pub extra: Map<String, Value>,
+}
+
+static EVENT_ID: AtomicU64 = AtomicU64::new(0);
+
+fn now() -> f64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_secs_f64())
+        .unwrap_or_default()
+}
+
+impl OneBotEvent {
+    pub fn new(
+        uin: i64,
+        time: f64,
+        kind: &str,
+        detail_type: &str,
+        sub_type: &str,
+        extra: Value,
+    ) -> Self {
+        Self {
+            id: format!("{uin}-{}", EVENT_ID.fetch_add(1, Ordering::Relaxed)),
+            time,
+            kind: kind.into(),
+            detail_type: detail_type.into(),
+            sub_type: sub_type.into(),
+            bot_self: BotSelf::new(uin),
+            extra: match extra {
+                Value::Object(map) => map,
+                _ => Map::new(),
+            },
+        }
+    }
+
+    /// 转换 QEvent，OneBot 12 没有对应事件时返回 None
+    pub async fn from_qevent(event: &QEvent) -> Option<Self> {
+        let e = match event {
+            QEvent::Login(uin) => Self::new(
+                *uin,
+                now(),
+                "meta",
+                "status_update",
+                "",
+                json!({ "status": { "good": true, "bots": [{ "self": BotSelf::new(*uin), "online": true }] } }),
+            ),
+            QEvent::GroupMessage(e) => {
+                let m = &e.inner;
+                let message_id = MessageId {
+                    kind: MessageKind::Group,
+                    target: m.group_code,
+                    seq: m.seqs.first().copied().unwrap_or_default(),
+                    rand: m.rands.first().copied().unwrap_or_default(),
+                    time: m.time as i64,
+                };
+                Self::new(
+                    e.client.uin().await,
+                    m.time as f64,
+                    "message",
+                    "group",
+                    "",
+                    json!({
+                        "message_id": message_id.to_string(),
+                        "message": chain_to_segments(&m.elements, MessageKind::Group, m.group_code),
+                        "alt_message": m.elements.to_text(),
+                        "user_id": m.from_uin.to_string(),
+                        "group_id": m.group_code.to_string(),
+                    }),
+                )
+            }
+            QEvent::FriendMessage(e) => {
+                let m = &e.inner;
+                let message_id = MessageId {
+                    kind: MessageKind::Private,
+                    target: m.from_uin,
+                    seq: m.seqs.first().copied().unwrap_or_default(),
+                    rand: m.rands.first().copied().unwrap_or_default(),
+                    time: m.time as i64,
+                };
+                Self::new(
+                    e.client.uin().await,
+                    m.time as f64,
+                    "message",
+                    "private",
+                    "",
+                    json!({
+                        "message_id": message_id.to_string(),
+                        "message": chain_to_segments(&m.elements, MessageKind::Private, m.from_uin),
+                        "alt_message": m.elements.to_text(),
+                        "user_id": m.from_uin.to_string(),
+                    }),
+                )
+            }
+            QEvent::GroupMessageRecall(e) => {
+                let m = &e.inner;
+                let message_id = MessageId {
+                    kind: MessageKind::Group,
+                    target: m.group_code,
+                    seq: m.msg_seq,
+                    rand: 0,
+                    time: m.time as i64,
+                };
+                let sub_type = if m.operator_uin == m.author_uin {
+                    "recall"
+                } else {
+                    "delete"
+                };
+                Self::new(
+                    e.client.uin().await,
+                    now(),
+                    "notice",
+                    "group_message_delete",
+                    sub_type,
+                    json!({
+                        "group_id": m.group_code.to_string(),
+                        "message_id": message_id.to_string(),
+                        "user_id": m.author_uin.to_string(),
+                        "operator_id": m.operator_uin.to_string(),
+                    }),
+                )
+            }
+            QEvent::FriendMessageRecall(e) => {
+                let m = &e.inner;
+                let message_id = MessageId {
+                    kind: MessageKind::Private,
+                    target: m.friend_uin,
+                    seq: m.msg_seq,
+                    rand: 0,
+                    time: m.time,
+                };
+                Self::new(
+                    e.client.uin().await,
+                    now(),
+                    "notice",
+                    "private_message_delete",
+                    "",
+                    json!({
+                        "message_id": message_id.to_string(),
+                        "user_id": m.friend_uin.to_string(),
+                    }),
+                )
+            }
+            QEvent::NewMember(e) => Self::new(
+                e.client.uin().await,
+                now(),
+                "notice",
+                "group_member_increase",
+                "join",
+                json!({
+                    "group_id": e.inner.group_code.to_string(),
+                    "user_id": e.inner.member_uin.to_string(),
+                    "operator_id": "",
+                }),
+            ),
+            QEvent::GroupLeave(e) => {
+                let m = &e.inner;
+                let (sub_type, operator) = match m.operator_uin {
+                    Some(operator) if operator != m.member_uin => ("kick", operator.to_string()),
+                    _ => ("leave", m.member_uin.to_string()),
+                };
+                Self::new(
+                    e.client.uin().await,
+                    now(),
+                    "notice",
+                    "group_member_decrease",
+                    sub_type,
+                    json!({
+                        "group_id": m.group_code.to_string(),
+                        "user_id": m.member_uin.to_string(),
+                        "operator_id": operator,
+                    }),
+                )
+            }
+            QEvent::NewFriend(e) => Self::new(
+                e.client.uin().await,
+                now(),
+                "notice",
+                "friend_increase",
+                "",
+                json!({ "user_id": e.inner.uin.to_string() }),
+            ),
+            QEvent::DeleteFriend(e) => Self::new(
+                e.client.uin().await,
+                now(),
+                "notice",
+                "friend_decrease",
+                "",
+                json!({ "user_id": e.inner.uin.to_string() }),
+            ),
+            _ => return None,
+        };
+        Some(e)
+    }
+
+    /// 能转换为 OneBot 12 事件的 QEvent 类型
+    pub fn supports(kind: &str) -> bool {
+        matches!(
+            kind,
+            "Login"
+                | "GroupMessage"
+                | "FriendMessage"
+                | "GroupMessageRecall"
+                | "FriendMessageRecall"
+                | "NewMember"
+                | "GroupLeave"
+                | "NewFriend"
+                | "DeleteFriend"
+        )
+    }
+}
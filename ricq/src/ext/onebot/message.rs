@@ -0,0 +1,259 @@
+use std::fmt;
+use std::str::FromStr;
+
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Map, Value};
+
+use ricq_core::msg::elem::{At, Face, Location, RQElem, Reply, Text};
+use ricq_core::msg::MessageChain;
+
+use super::action::{ActionError, BAD_PARAM, UNSUPPORTED_SEGMENT};
+
+/// OneBot 12 消息段
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Segment {
+    #[serde(rename = "type")]
+    pub kind: String,
+    #[serde(default)]
+    pub data: Map<String, Value>,
+}
+
+impl Segment {
+    pub fn new(kind: &str, data: Value) -> Self {
+        Self {
+            kind: kind.into(),
+            data: match data {
+                Value::Object(map) => map,
+                _ => Map::new(),
+            },
+        }
+    }
+
+    fn str(&self, key: &str) -> Result<&str, ActionError> {
+        self.data
+            .get(key)
+            .and_then(Value::as_str)
+            .ok_or_else(|| ActionError::new(BAD_PARAM, format!("{}.{key} required", self.kind)))
+    }
+
+    fn id(&self, key: &str) -> Result<i64, ActionError> {
+        parse_id(self.data.get(key))
+            .ok_or_else(|| ActionError::new(BAD_PARAM, format!("{}.{key} invalid", self.kind)))
+    }
+
+    fn f64(&self, key: &str) -> Result<f64, ActionError> {
+        self.data
+            .get(key)
+            .and_then(Value::as_f64)
+            .ok_or_else(|| ActionError::new(BAD_PARAM, format!("{}.{key} invalid", self.kind)))
+    }
+}
+
+/// OneBot 12 的 id 均为字符串，这里兼容数字
+pub(crate) fn parse_id(value: Option<&Value>) -> Option<i64> {
+    match value? {
+        Value::String(s) => s.parse().ok(),
+        v => v.as_i64(),
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MessageKind {
+    Private,
+    Group,
+}
+
+/// 消息 id，编码撤回和引用回复需要的全部信息，不依赖消息存储
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct MessageId {
+    pub kind: MessageKind,
+    /// 群号或好友 QQ
+    pub target: i64,
+    pub seq: i32,
+    pub rand: i32,
+    pub time: i64,
+}
+
+impl fmt::Display for MessageId {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let kind = match self.kind {
+            MessageKind::Private => "p",
+            MessageKind::Group => "g",
+        };
+        write!(
+            f,
+            "{kind}_{}_{}_{}_{}",
+            self.target, self.seq, self.rand, self.time
+        )
+    }
+}
+
+impl FromStr for MessageId {
+    type Err = ActionError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let invalid = || ActionError::new(BAD_PARAM, format!("invalid message_id: {s}"));
+        let parts: Vec<&str> = s.split('_').collect();
+        if parts.len() != 5 {
+            return Err(invalid());
+        }
+        let kind = match parts[0] {
+            "p" => MessageKind::Private,
+            "g" => MessageKind::Group,
+            _ => return Err(invalid()),
+        };
+        Ok(Self {
+            kind,
+            target: parts[1].parse().map_err(|_| invalid())?,
+            seq: parts[2].parse().map_err(|_| invalid())?,
+            rand: parts[3].parse().map_err(|_| invalid())?,
+            time: parts[4].parse().map_err(|_| invalid())?,
+        })
+    }
+}
+
+/// MessageChain 转为消息段，引用回复放在最前
+pub fn chain_to_segments(chain: &MessageChain, kind: MessageKind, target: i64) -> Vec<Segment> {
+    let mut segments = Vec::new();
+    if let Some(reply) = chain.reply() {
+        let message_id = MessageId {
+            kind,
+            target,
+            seq: reply.reply_seq,
+            rand: 0,
+            time: reply.time as i64,
+        };
+        segments.push(Segment::new(
+            "reply",
+            json!({ "message_id": message_id.to_string(), "user_id": reply.sender.to_string() }),
+        ));
+    }
+    for elem in chain.clone() {
+        let segment = match elem {
+            RQElem::Text(text) => Segment::new("text", json!({ "text": text.content })),
+            RQElem::At(at) if at.target == 0 => Segment::new("mention_all", json!({})),
+            RQElem::At(at) => Segment::new("mention", json!({ "user_id": at.target.to_string() })),
+            RQElem::Face(face) => {
+                Segment::new("qq.face", json!({ "id": face.index, "name": face.name }))
+            }
+            RQElem::Location(location) => Segment::new(
+                "location",
+                json!({
+                    "latitude": location.lat,
+                    "longitude": location.lon,
+                    "title": location.name,
+                    "content": location.address,
+                }),
+            ),
+            RQElem::GroupImage(image) => {
+                let url = image.url();
+                Segment::new("image", json!({ "file_id": url, "url": url }))
+            }
+            RQElem::FriendImage(image) => {
+                let url = image.url();
+                Segment::new("image", json!({ "file_id": url, "url": url }))
+            }
+            RQElem::FlashImage(image) => {
+                let url = image.url();
+                Segment::new(
+                    "image",
+                    json!({ "file_id": url, "url": url, "qq.flash": true }),
+                )
+            }
+            RQElem::Dice(dice) => Segment::new("qq.dice", json!({ "value": dice.value })),
+            RQElem::Poke(poke) => Segment::new(
+                "qq.poke",
+                json!({ "type": poke.poke_type, "name": poke.name }),
+            ),
+            _ => continue,
+        };
+        segments.push(segment);
+    }
+    segments
+}
+
+/// 消息段转为 MessageChain，图片需要先上传，暂不支持
+pub fn segments_to_chain(segments: &[Segment]) -> Result<MessageChain, ActionError> {
+    let mut chain = MessageChain::default();
+    let mut reply = None;
+    for segment in segments {
+        match segment.kind.as_str() {
+            "text" => chain.push(Text::new(segment.str("text")?.into())),
+            "mention" => chain.push(At::new(segment.id("user_id")?)),
+            "mention_all" => chain.push(At::new(0)),
+            "qq.face" => chain.push(Face::new(segment.id("id")? as i32)),
+            "location" => chain.push(Location::new(
+                segment.f64("latitude")?,
+                segment.f64("longitude")?,
+                segment.str("title")?.into(),
+                segment.str("content")?.into(),
+            )),
+            "reply" => {
+                let id: MessageId = segment.str("message_id")?.parse()?;
+                let sender = match segment.data.get("user_id") {
+                    Some(v) => parse_id(Some(v)).ok_or_else(|| {
+                        ActionError::new(BAD_PARAM, "reply.user_id invalid".into())
+                    })?,
+                    None => 0,
+                };
+                reply = Some(Reply {
+                    reply_seq: id.seq,
+                    sender,
+                    time: id.time as i32,
+                    elements: MessageChain::default(),
+                });
+            }
+            kind => {
+                return Err(ActionError::new(
+                    UNSUPPORTED_SEGMENT,
+                    format!("unsupported segment: {kind}"),
+                ))
+            }
+        }
+    }
+    if let Some(reply) = reply {
+        chain.with_reply(reply);
+    }
+    Ok(chain)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_message_id() {
+        let id = MessageId {
+            kind: MessageKind::Group,
+            target: 12345,
+            seq: 100,
+            rand: -7,
+            time: 1650000000,
+        };
+        assert_eq!(id.to_string().parse::<MessageId>().unwrap(), id);
+        assert!("x_1_2_3_4".parse::<MessageId>().is_err());
+        assert!("g_1_2".parse::<MessageId>().is_err());
+    }
+
+    #[test]
+    fn test_segments_round_trip() {
+        let segments: Vec<Segment> = serde_json::from_value(json!([
+            { "type": "reply", "data": { "message_id": "g_1_10_0_100", "user_id": "2" } },
+            { "type": "mention", "data": { "user_id": "3" } },
+            { "type": "text", "data": { "text": "hello" } },
+        ]))
+        .unwrap();
+        let chain = segments_to_chain(&segments).unwrap();
+        let parsed = chain_to_segments(&chain, MessageKind::Group, 1);
+        assert_eq!(parsed[0].data["message_id"], "g_1_10_0_100");
+        assert_eq!(parsed[1].kind, "mention");
+        assert_eq!(parsed[1].data["user_id"], "3");
+        assert_eq!(parsed[2].data["text"], "hello");
+
+        let image = [Segment::new("image", json!({ "file_id": "1" }))];
+        assert_eq!(
+            segments_to_chain(&image).unwrap_err().retcode,
+            UNSUPPORTED_SEGMENT
+        );
+    }
+}
@@ -0,0 +1,72 @@
+//! OneBot 12 适配层，负责事件、消息段、动作和 RICQ 之间的转换，
+//! 通信方式由 [`OneBotTransport`] 实现（HTTP / WebSocket 等）
+
+use std::sync::Arc;
+
+use async_trait::async_trait;
+
+use crate::handler::{Handler, QEvent};
+use crate::{Client, RQResult};
+
+pub use action::*;
+pub use event::*;
+pub use message::*;
+
+mod action;
+mod event;
+mod message;
+
+pub const PLATFORM: &str = "qq";
+pub const IMPL: &str = "ricq";
+pub const ONEBOT_VERSION: &str = "12";
+
+/// OneBot 12 的通信方式
+#[async_trait]
+pub trait OneBotTransport: Send + Sync {
+    /// 推送事件
+    async fn send_event(&self, event: OneBotEvent) -> RQResult<()>;
+    /// 接收动作请求，返回 None 表示连接关闭
+    async fn recv_action(&self) -> Option<ActionRequest>;
+    /// 返回动作响应
+    async fn send_response(&self, response: ActionResponse) -> RQResult<()>;
+}
+
+/// 把事件转换后通过 transport 推送
+pub struct OneBotHandler<T> {
+    pub transport: Arc<T>,
+}
+
+impl<T> OneBotHandler<T> {
+    pub fn new(transport: Arc<T>) -> Self {
+        Self { transport }
+    }
+}
+
+#[async_trait]
+impl<T: OneBotTransport> Handler for OneBotHandler<T> {
+    async fn handle(&self, event: QEvent) {
+        if let Some(event) = OneBotEvent::from_qevent(&event).await {
+            if let Err(err) = self.transport.send_event(event).await {
+                tracing::warn!("failed to send onebot event: {}", err);
+            }
+        }
+    }
+
+    fn subscribes(&self, kind: &str) -> bool {
+        OneBotEvent::supports(kind)
+    }
+}
+
+/// 循环处理 transport 收到的动作请求，每个请求单独执行，直到连接关闭
+pub async fn serve_actions<T: OneBotTransport + 'static>(client: Arc<Client>, transport: Arc<T>) {
+    while let Some(req) = transport.recv_action().await {
+        let client = client.clone();
+        let transport = transport.clone();
+        tokio::spawn(async move {
+            let resp = handle_action(&client, req).await;
+            if let Err(err) = transport.send_response(resp).await {
+                tracing::warn!("failed to send onebot response: {}", err);
+            }
+        });
+    }
+}
@@ -63,6 +63,16 @@ pub async fn auto_reconnect<T: AsyncRead + AsyncWrite + 'static + Send>(
     }
 }
 
+/// 按 `ClientOptions::reconnect` 自动重连，在掉线后使用，会阻塞到重连结束
+pub async fn auto_reconnect_with_policy<T: AsyncRead + AsyncWrite + 'static + Send>(
+    client: Arc<Client>,
+    credential: Credential,
+    connector: impl Connector<T>,
+) {
+    let policy = client.options.reconnect.clone();
+    auto_reconnect(client, credential, policy.interval, policy.max, connector).await
+}
+
 pub struct Password {
     pub uin: i64,
     pub password: String,
@@ -0,0 +1,66 @@
+//! 从动态链接库加载 T544 签名，不需要签名服务器
+//!
+//! 动态库需要导出以下 C 函数，返回 0 表示成功，out_len 传入缓冲区大小、返回签名长度：
+//!
+//! ```c
+//! int32_t t544_sign(const char *data, const uint8_t *salt, size_t salt_len,
+//!                   uint8_t *out, size_t *out_len);
+//! ```
+
+use std::ffi::{c_char, CString, OsStr};
+
+use libloading::{Library, Symbol};
+
+use crate::client::energy::EnergyProvider;
+use crate::{RQError, RQResult};
+
+const SIGN_SYMBOL: &[u8] = b"t544_sign\0";
+const MAX_SIGN_LEN: usize = 1024;
+
+type SignFn = unsafe extern "C" fn(*const c_char, *const u8, usize, *mut u8, *mut usize) -> i32;
+
+/// 通过动态库计算 T544
+pub struct NativeT544 {
+    library: Library,
+}
+
+impl NativeT544 {
+    /// 加载动态库并检查是否导出了 t544_sign
+    pub fn load<P: AsRef<OsStr>>(path: P) -> RQResult<Self> {
+        // Safety: 动态库初始化代码由使用者保证可信
+        let library = unsafe { Library::new(path) }.map_err(|e| RQError::Other(e.to_string()))?;
+        unsafe { library.get::<SignFn>(SIGN_SYMBOL) }.map_err(|e| RQError::Other(e.to_string()))?;
+        Ok(Self { library })
+    }
+}
+
+impl EnergyProvider for NativeT544 {
+    fn energy(&self, _uin: i64, data: &str, salt: &[u8]) -> RQResult<Vec<u8>> {
+        let data = CString::new(data).map_err(|e| RQError::Other(e.to_string()))?;
+        let mut out = vec![0u8; MAX_SIGN_LEN];
+        let mut out_len = out.len();
+        let code = unsafe {
+            let sign: Symbol<SignFn> = self
+                .library
+                .get(SIGN_SYMBOL)
+                .map_err(|e| RQError::Other(e.to_string()))?;
+            sign(
+                data.as_ptr(),
+                salt.as_ptr(),
+                salt.len(),
+                out.as_mut_ptr(),
+                &mut out_len,
+            )
+        };
+        if code != 0 {
+            return Err(RQError::Other(format!("t544_sign failed: {code}")));
+        }
+        if out_len > MAX_SIGN_LEN {
+            return Err(RQError::Other(format!(
+                "t544_sign returned {out_len} bytes"
+            )));
+        }
+        out.truncate(out_len);
+        Ok(out)
+    }
+}
@@ -0,0 +1,305 @@
+//! 把事件 POST 到 HTTP 接口，不需要编写 Rust Handler 就能接入
+//!
+//! 请求体为 JSON 序列化的 [`EventEnvelope`]，带有 `X-Timestamp` 头（秒级时间戳），
+//! 配置 secret 时带有 `X-Signature: sha256=<hex>` 签名头，签名为 HMAC-SHA256(timestamp + body)，
+//! 接收方可以据此拒绝过期的重放请求。
+//! 接口返回 OneBot 12 动作请求（单个或数组）时会立即执行，结果只记录日志。
+
+use std::sync::{Arc, OnceLock};
+use std::time::{Duration, UNIX_EPOCH};
+
+use async_trait::async_trait;
+use bytes::Bytes;
+use serde::Deserialize;
+use sha2::{Digest, Sha256};
+use tokio::sync::mpsc;
+
+use ricq_core::hex::encode_hex;
+
+use crate::ext::onebot::{handle_action, ActionRequest};
+use crate::handler::{EventEnvelope, Handler, QEvent};
+use crate::{Client, RQError, RQResult};
+
+/// Webhook 配置
+#[derive(Debug, Clone)]
+pub struct WebhookConfig {
+    /// 接收事件的地址，每个事件会发送到所有地址
+    pub endpoints: Vec<String>,
+    /// 签名密钥，为 None 时不签名
+    pub secret: Option<String>,
+    /// 单次请求超时时间
+    pub timeout: Duration,
+    /// 失败后最多重试次数
+    pub max_retries: usize,
+    /// 第一次重试前等待的时间，之后每次翻倍
+    pub retry_interval: Duration,
+    /// 只转发这些类型的事件（类型名见 `QEvent::kind`），为空时全部转发
+    pub kinds: Vec<String>,
+    /// 每个地址等待发送的事件数，队列满时丢弃新的事件
+    pub queue_size: usize,
+}
+
+impl Default for WebhookConfig {
+    fn default() -> Self {
+        Self {
+            endpoints: Vec::new(),
+            secret: None,
+            timeout: Duration::from_secs(5),
+            max_retries: 3,
+            retry_interval: Duration::from_secs(1),
+            kinds: Vec::new(),
+            queue_size: 1024,
+        }
+    }
+}
+
+struct Inner {
+    config: WebhookConfig,
+    http: reqwest::Client,
+}
+
+/// 序列化并签名后的事件，发送到每个地址时共用
+struct Delivery {
+    client: Option<Arc<Client>>,
+    kind: &'static str,
+    self_uin: i64,
+    timestamp: String,
+    body: Bytes,
+    signature: Option<String>,
+}
+
+/// 转发事件的 Handler，每个地址有一个按顺序发送的任务，不阻塞事件分发
+#[derive(Clone)]
+pub struct WebhookForwarder {
+    inner: Arc<Inner>,
+    /// 每个地址的发送队列，第一次收到事件时创建，所有 WebhookForwarder 被 drop 后发送任务退出
+    queues: Arc<OnceLock<Vec<mpsc::Sender<Arc<Delivery>>>>>,
+}
+
+impl WebhookForwarder {
+    pub fn new(config: WebhookConfig) -> RQResult<Self> {
+        if config.queue_size == 0 {
+            return Err(RQError::Other("webhook queue_size must be positive".into()));
+        }
+        let http = reqwest::Client::builder()
+            .timeout(config.timeout)
+            .build()
+            .map_err(|e| RQError::Other(e.to_string()))?;
+        Ok(Self {
+            inner: Arc::new(Inner { config, http }),
+            queues: Default::default(),
+        })
+    }
+
+    fn queues(&self) -> &[mpsc::Sender<Arc<Delivery>>] {
+        self.queues.get_or_init(|| {
+            self.inner
+                .config
+                .endpoints
+                .iter()
+                .map(|endpoint| {
+                    let (tx, mut rx) = mpsc::channel::<Arc<Delivery>>(self.inner.config.queue_size);
+                    let inner = self.inner.clone();
+                    let endpoint = endpoint.clone();
+                    tokio::spawn(async move {
+                        while let Some(delivery) = rx.recv().await {
+                            inner.deliver(&endpoint, &delivery).await;
+                        }
+                    });
+                    tx
+                })
+                .collect()
+        })
+    }
+}
+
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum Callback {
+    One(ActionRequest),
+    Many(Vec<ActionRequest>),
+}
+
+impl Inner {
+    fn prepare(
+        &self,
+        client: Option<Arc<Client>>,
+        kind: &'static str,
+        envelope: EventEnvelope,
+    ) -> Option<Delivery> {
+        let body = match serde_json::to_vec(&envelope) {
+            Ok(body) => body,
+            Err(err) => {
+                tracing::warn!("failed to serialize webhook event: {}", err);
+                return None;
+            }
+        };
+        let timestamp = UNIX_EPOCH
+            .elapsed()
+            .unwrap_or_default()
+            .as_secs()
+            .to_string();
+        let signature = self.config.secret.as_ref().map(|secret| {
+            let mut signed = timestamp.clone().into_bytes();
+            signed.extend_from_slice(&body);
+            format!(
+                "sha256={}",
+                encode_hex(&hmac_sha256(secret.as_bytes(), &signed))
+            )
+        });
+        Some(Delivery {
+            client,
+            kind,
+            self_uin: envelope.self_uin,
+            timestamp,
+            body: Bytes::from(body),
+            signature,
+        })
+    }
+
+    async fn deliver(&self, endpoint: &str, delivery: &Delivery) {
+        let resp = match self.post(endpoint, delivery).await {
+            Ok(resp) => resp,
+            Err(err) => {
+                tracing::warn!(
+                    "webhook {} dropped {} event: {}",
+                    endpoint,
+                    delivery.kind,
+                    err
+                );
+                return;
+            }
+        };
+        if let Some(client) = delivery.client.as_ref() {
+            self.run_callback(client, endpoint, &resp).await;
+        }
+    }
+
+    async fn post(&self, endpoint: &str, delivery: &Delivery) -> RQResult<Bytes> {
+        let mut interval = self.config.retry_interval;
+        let mut retries = 0;
+        loop {
+            let mut req = self
+                .http
+                .post(endpoint)
+                .header(reqwest::header::CONTENT_TYPE, "application/json")
+                .header("X-Event-Kind", delivery.kind)
+                .header("X-Self-ID", delivery.self_uin.to_string())
+                .header("X-Timestamp", &delivery.timestamp)
+                .body(delivery.body.clone());
+            if let Some(signature) = delivery.signature.as_deref() {
+                req = req.header("X-Signature", signature);
+            }
+            let err = match req.send().await {
+                Ok(resp) if resp.status().is_success() => {
+                    return resp
+                        .bytes()
+                        .await
+                        .map_err(|e| RQError::Other(e.to_string()))
+                }
+                // 4xx 是请求本身的问题，重试也没用
+                Ok(resp) if resp.status().is_client_error() => {
+                    return Err(RQError::Other(format!("status {}", resp.status())))
+                }
+                Ok(resp) => format!("status {}", resp.status()),
+                Err(err) => err.to_string(),
+            };
+            if retries >= self.config.max_retries {
+                return Err(RQError::Other(err));
+            }
+            retries += 1;
+            tracing::debug!(
+                "webhook {} failed: {}, retry after {:?}",
+                endpoint,
+                err,
+                interval
+            );
+            tokio::time::sleep(interval).await;
+            interval *= 2;
+        }
+    }
+
+    async fn run_callback(&self, client: &Client, endpoint: &str, body: &[u8]) {
+        if body.iter().all(u8::is_ascii_whitespace) {
+            return;
+        }
+        let actions = match serde_json::from_slice(body) {
+            Ok(Callback::One(action)) => vec![action],
+            Ok(Callback::Many(actions)) => actions,
+            Err(err) => {
+                tracing::debug!("ignore webhook {} response: {}", endpoint, err);
+                return;
+            }
+        };
+        for action in actions {
+            let name = action.action.clone();
+            let resp = handle_action(client, action).await;
+            if resp.retcode != 0 {
+                tracing::warn!(
+                    "webhook {} action {} failed: {} {}",
+                    endpoint,
+                    name,
+                    resp.retcode,
+                    resp.message
+                );
+            }
+        }
+    }
+}
+
+#[async_trait]
+impl Handler for WebhookForwarder {
+    async fn handle(&self, event: QEvent) {
+        if !self.subscribes(event.kind()) {
+            return;
+        }
+        let envelope = EventEnvelope::from_event(&event).await;
+        let kind = event.kind();
+        let delivery = match self.inner.prepare(event.client().cloned(), kind, envelope) {
+            Some(delivery) => Arc::new(delivery),
+            None => return,
+        };
+        for (queue, endpoint) in self.queues().iter().zip(&self.inner.config.endpoints) {
+            if queue.try_send(delivery.clone()).is_err() {
+                tracing::warn!("webhook {} queue is full, dropped {} event", endpoint, kind);
+            }
+        }
+    }
+
+    fn subscribes(&self, kind: &str) -> bool {
+        let kinds = &self.inner.config.kinds;
+        !self.inner.config.endpoints.is_empty()
+            && (kinds.is_empty() || kinds.iter().any(|k| k == kind))
+    }
+}
+
+fn hmac_sha256(key: &[u8], data: &[u8]) -> [u8; 32] {
+    const BLOCK_SIZE: usize = 64;
+    let mut block = [0u8; BLOCK_SIZE];
+    if key.len() > BLOCK_SIZE {
+        block[..32].copy_from_slice(&Sha256::digest(key));
+    } else {
+        block[..key.len()].copy_from_slice(key);
+    }
+    let mut inner = Sha256::new();
+    inner.update(block.map(|b| b ^ 0x36));
+    inner.update(data);
+    let mut outer = Sha256::new();
+    outer.update(block.map(|b| b ^ 0x5c));
+    outer.update(inner.finalize());
+    outer.finalize().into()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_hmac_sha256() {
+        // RFC 4231 test case 2
+        assert_eq!(
+            encode_hex(&hmac_sha256(b"Jefe", b"what do ya want for nothing?")),
+            "5bdcc146bf60754e6a042426089575c75a003f089d2739839dec58b964ec3843"
+        );
+    }
+}
@@ -1,6 +1,8 @@
 #![feature(async_closure)]
 #![feature(let_chains)]
 #![feature(result_flattening)]
+#[cfg(feature = "blocking")]
+pub mod blocking;
 pub mod client;
 mod config;
 pub mod ext;
@@ -9,7 +11,10 @@ pub mod structs;
 
 pub use client::handler;
 pub use client::Client;
-pub use config::Config;
+pub use config::{
+    CacheOptions, ClientBuilder, ClientOptions, Config, HeartbeatPolicy, HighwayOptions,
+    MemberDiffOptions, ReconnectPolicy, SendRateLimit,
+};
 pub use device::Device;
 pub use version::Protocol;
 
@@ -22,3 +27,4 @@ use ricq_core::jce;
 pub use ricq_core::msg;
 pub use ricq_core::protocol::device;
 pub use ricq_core::protocol::version;
+pub use tokio_util::sync::CancellationToken;
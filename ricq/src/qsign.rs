@@ -2,12 +2,13 @@ use bytes::{BufMut, BytesMut};
 use ricq_core::binary::packet_writer::WriteLV;
 use ricq_core::hex::encode_hex;
 use serde::{Deserialize, Serialize};
+use std::sync::RwLock;
 use std::time::Duration;
 
 pub struct QSignClient {
     url: String,
     key: String,
-    client: reqwest::Client,
+    client: RwLock<reqwest::Client>,
     timeout: Duration,
 }
 #[derive(Debug, Clone, Default, Serialize, Deserialize)]
@@ -51,11 +52,20 @@ impl QSignClient {
         Ok(QSignClient {
             url,
             key,
-            client,
+            client: RwLock::new(client),
             timeout,
         })
     }
 
+    /// 替换请求使用的 http client，`Client::set_resolver` 时会调用
+    pub fn set_http_client(&self, client: reqwest::Client) {
+        *self.client.write().unwrap() = client;
+    }
+
+    fn http(&self) -> reqwest::Client {
+        self.client.read().unwrap().clone()
+    }
+
     pub async fn register(
         &self,
         uin: i64,
@@ -64,7 +74,7 @@ impl QSignClient {
         guid: &[u8],
     ) -> reqwest::Result<QSignResponse<String>> {
         let url = format!("{}/register", self.url);
-        self.client
+        self.http()
             .get(url)
             .query(&[
                 ("uin", uin.to_string().as_str()),
@@ -89,7 +99,7 @@ impl QSignClient {
         android_id: &str,
     ) -> reqwest::Result<QSignResponse<String>> {
         let url = format!("{}/custom_energy", self.url);
-        self.client
+        self.http()
             .get(url)
             .query(&[
                 ("uin", uin.to_string().as_str()),
@@ -131,7 +141,7 @@ impl QSignClient {
         android_id: &str,
     ) -> reqwest::Result<QSignResponse<String>> {
         let url = format!("{}/energy", self.url);
-        self.client
+        self.http()
             .get(url)
             .query(&[
                 ("version", version),
@@ -161,7 +171,7 @@ impl QSignClient {
         guid: &[u8],
     ) -> reqwest::Result<QSignResponse<SignData>> {
         let url = format!("{}/sign", self.url);
-        self.client
+        self.http()
             .post(url)
             .form(&[
                 ("uin", uin.to_string().as_str()),
@@ -187,7 +197,7 @@ impl QSignClient {
         buffer: &[u8],
     ) -> reqwest::Result<()> {
         let url = format!("{}/submit", self.url);
-        self.client
+        self.http()
             .get(url)
             .query(&[
                 ("uin", uin.to_string().as_str()),
@@ -1,10 +1,18 @@
+use std::io::SeekFrom;
+
 use serde::{Deserialize, Serialize};
+use tokio::io::{AsyncRead, AsyncReadExt, AsyncSeek, AsyncSeekExt};
 
 use ricq_core::common::RQAddr;
 
 use ricq_core::hex::encode_hex;
 use ricq_core::msg::elem::{FriendImage, GroupImage};
-use ricq_core::RQResult;
+use ricq_core::{RQError, RQResult};
+
+use crate::client::stream_md5;
+
+/// 从 reader 读取图片信息时，只用开头这部分数据识别格式和尺寸
+const IMAGE_HEADER_SIZE: u64 = 64 * 1024;
 
 // 仅用于上传图片，一些临时变量，太多了放一起
 #[derive(Serialize, Deserialize, Debug, Clone)]
@@ -19,11 +27,33 @@ pub struct ImageInfo {
 
 impl ImageInfo {
     pub fn try_new(data: &[u8]) -> RQResult<Self> {
-        let md5 = md5::compute(data).to_vec();
+        Self::from_header(md5::compute(data).to_vec(), data.len() as u32, data)
+    }
+
+    /// 流式计算 md5，只把文件开头读入内存，结束后 reader 回到原来的位置
+    pub async fn try_from_reader<R: AsyncRead + AsyncSeek + Unpin>(
+        reader: &mut R,
+    ) -> RQResult<Self> {
+        let (md5, size) = stream_md5(reader).await?;
+        let start = reader.stream_position().await.map_err(RQError::IO)?;
+        let mut header = Vec::new();
+        (&mut *reader)
+            .take(IMAGE_HEADER_SIZE)
+            .read_to_end(&mut header)
+            .await
+            .map_err(RQError::IO)?;
+        reader
+            .seek(SeekFrom::Start(start))
+            .await
+            .map_err(RQError::IO)?;
+        Self::from_header(md5, size as u32, &header)
+    }
 
+    #[cfg_attr(not(feature = "image-detail"), allow(unused_variables))]
+    fn from_header(md5: Vec<u8>, size: u32, header: &[u8]) -> RQResult<Self> {
         #[cfg(feature = "image-detail")]
         let (width, height, format, ext_name) = {
-            let img_reader = image::io::Reader::new(std::io::Cursor::new(data))
+            let img_reader = image::io::Reader::new(std::io::Cursor::new(header))
                 .with_guessed_format()
                 .map_err(ricq_core::RQError::IO)?;
             let format = img_reader.format().unwrap_or(image::ImageFormat::Png);
@@ -50,7 +80,7 @@ impl ImageInfo {
             },
             #[cfg(not(feature = "image-detail"))]
             image_type: 1001, // PNG
-            size: data.len() as u32,
+            size,
         })
     }
 
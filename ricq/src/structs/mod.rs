@@ -1,4 +1,8 @@
 pub use image_info::*;
 pub use ricq_core::structs::*;
+#[cfg(feature = "web-api")]
+pub use web::*;
 
 mod image_info;
+#[cfg(feature = "web-api")]
+mod web;
@@ -0,0 +1,210 @@
+use std::collections::HashMap;
+
+use serde::{Deserialize, Serialize};
+
+/// 群公告
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct GroupNotice {
+    #[serde(rename = "fid", default)]
+    pub notice_id: String,
+    #[serde(rename = "u", default)]
+    pub sender_uin: i64,
+    #[serde(rename = "pubt", default)]
+    pub publish_time: i64,
+    #[serde(rename = "msg", default)]
+    pub message: GroupNoticeMessage,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct GroupNoticeMessage {
+    #[serde(default)]
+    pub text: String,
+    #[serde(rename = "pics", default)]
+    pub images: Vec<GroupNoticeImage>,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct GroupNoticeImage {
+    #[serde(default)]
+    pub id: String,
+    #[serde(rename = "w", default)]
+    pub width: String,
+    #[serde(rename = "h", default)]
+    pub height: String,
+}
+
+/// 群精华消息
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct GroupEssenceMessage {
+    #[serde(default)]
+    pub msg_seq: i32,
+    #[serde(rename = "msg_random", default)]
+    pub msg_rand: i32,
+    #[serde(default)]
+    pub sender_uin: String,
+    #[serde(default)]
+    pub sender_nick: String,
+    #[serde(default)]
+    pub sender_time: i64,
+    #[serde(rename = "add_digest_uin", default)]
+    pub operator_uin: String,
+    #[serde(rename = "add_digest_nick", default)]
+    pub operator_nick: String,
+    #[serde(rename = "add_digest_time", default)]
+    pub operator_time: i64,
+}
+
+/// 群荣誉类型
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum GroupHonorType {
+    /// 龙王
+    Talkative = 1,
+    /// 群聊之火
+    Performer = 2,
+    /// 群聊炽焰
+    Legend = 3,
+    /// 冒尖小春笋
+    StrongNewbie = 5,
+    /// 快乐源泉
+    Emotion = 6,
+}
+
+/// 群荣誉信息，只会填充请求的类型对应的列表
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct GroupHonorInfo {
+    #[serde(default)]
+    pub current_talkative: Option<CurrentTalkative>,
+    #[serde(default)]
+    pub talkative_list: Vec<GroupHonorMember>,
+    #[serde(rename = "actorList", default)]
+    pub performer_list: Vec<GroupHonorMember>,
+    #[serde(default)]
+    pub legend_list: Vec<GroupHonorMember>,
+    #[serde(rename = "strongnewbieList", default)]
+    pub strong_newbie_list: Vec<GroupHonorMember>,
+    #[serde(default)]
+    pub emotion_list: Vec<GroupHonorMember>,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct CurrentTalkative {
+    #[serde(default)]
+    pub uin: i64,
+    #[serde(default)]
+    pub day_count: i32,
+    #[serde(default)]
+    pub avatar: String,
+    #[serde(default)]
+    pub nick: String,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct GroupHonorMember {
+    #[serde(default)]
+    pub uin: i64,
+    #[serde(default)]
+    pub avatar: String,
+    #[serde(default)]
+    pub name: String,
+    #[serde(default)]
+    pub desc: String,
+}
+
+/// 上传到群相册的照片
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct GroupAlbumPhotoUpload {
+    /// 照片 id
+    #[serde(default)]
+    pub lloc: String,
+    #[serde(default)]
+    pub sloc: String,
+    #[serde(rename = "albumid", default)]
+    pub album_id: String,
+    #[serde(default)]
+    pub url: String,
+    #[serde(default)]
+    pub width: i32,
+    #[serde(default)]
+    pub height: i32,
+}
+
+/// 群相册
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct GroupAlbum {
+    #[serde(default)]
+    pub id: String,
+    #[serde(rename = "title", default)]
+    pub name: String,
+    #[serde(default)]
+    pub desc: String,
+    #[serde(rename = "createtime", default)]
+    pub create_time: i64,
+    #[serde(rename = "num", default)]
+    pub photo_count: i32,
+    #[serde(default)]
+    pub creator: GroupAlbumUser,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct GroupAlbumUser {
+    #[serde(default)]
+    pub uin: i64,
+    #[serde(default)]
+    pub nick: String,
+}
+
+/// 群相册中的照片
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct GroupAlbumPhoto {
+    /// 照片 id
+    #[serde(default)]
+    pub lloc: String,
+    #[serde(default)]
+    pub url: String,
+    #[serde(default)]
+    pub width: i32,
+    #[serde(default)]
+    pub height: i32,
+    #[serde(rename = "uploadtime", default)]
+    pub upload_time: i64,
+    #[serde(default)]
+    pub uploader: GroupAlbumUser,
+}
+
+/// 群等级 (活跃度) 排行
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct GroupLevelRanking {
+    /// 等级对应的头衔名称
+    pub level_names: HashMap<i32, String>,
+    /// 按活跃度从高到低排列
+    pub members: Vec<GroupMemberActivity>,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct GroupMemberActivity {
+    #[serde(default)]
+    pub uin: i64,
+    #[serde(default)]
+    pub nick: String,
+    #[serde(default)]
+    pub card: String,
+    #[serde(rename = "lv", default)]
+    pub level: GroupMemberLevel,
+    #[serde(default)]
+    pub join_time: i64,
+    #[serde(default)]
+    pub last_speak_time: i64,
+    /// 0 群主, 1 管理员, 2 成员
+    #[serde(default)]
+    pub role: i32,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct GroupMemberLevel {
+    /// 活跃度积分
+    #[serde(default)]
+    pub point: i64,
+    #[serde(default)]
+    pub level: i32,
+}